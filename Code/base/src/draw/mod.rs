@@ -16,7 +16,11 @@ pub use pi_draw::{
     SK9822PiDraw, SK9822PiDrawBuilder, SK9822PiDrawInfo,
 };
 
-use std::time::Instant;
+#[cfg(target_os = "linux")]
+pub use smart_leds_draw::{SmartLedsDraw, SmartLedsDrawBuilder, SmartLedsDrawInfo};
+
+use std::io;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::{fmt, ops};
 
 use crate::animation::Animation;
@@ -28,6 +32,9 @@ pub mod term_draw;
 #[cfg(target_os = "linux")]
 pub mod pi_draw;
 
+#[cfg(target_os = "linux")]
+pub mod smart_leds_draw;
+
 /// Trait defining the ability to draw a frame of colors to LEDs.
 pub trait Draw {
     /// Adds an [`Animation`][0] to the queue.
@@ -86,6 +93,11 @@ pub struct DrawStats {
     end: Instant,
     frames: usize,
     num: usize,
+
+    /// Wall-clock time corresponding to `start`, tracked alongside it purely
+    /// so `end` (an [`Instant`], which has no wall-clock meaning of its own)
+    /// can be converted into a timestamp for [`Self::to_line_protocol`].
+    wall_start: SystemTime,
 }
 
 impl DrawStats {
@@ -97,6 +109,8 @@ impl DrawStats {
             end: Instant::now(),
             frames: 0,
             num: 0,
+
+            wall_start: SystemTime::now(),
         }
     }
 
@@ -126,6 +140,37 @@ impl DrawStats {
     pub fn end(&mut self) {
         self.end = Instant::now();
     }
+
+    /// Serializes the current stats as an InfluxDB line-protocol record:
+    /// `measurement,tag=val fps=<f64>,led_rate=<f64>,frames=<usize>i,num_leds=<usize>i <nanos-timestamp>`,
+    /// timestamped at the wall-clock instant corresponding to [`Self::end`].
+    ///
+    /// `tags` are written in the order given; an empty slice omits the
+    /// tag-set entirely rather than leaving a dangling comma.
+    pub fn to_line_protocol(&self, measurement: &str, tags: &[(&str, &str)]) -> String {
+        let duration = self.end.duration_since(self.start).as_secs_f64();
+        let num_frames = self.frames as f64;
+        let num_leds = self.num as f64;
+        let fps = num_frames / duration;
+        let led_rate = (num_frames * num_leds) / duration;
+
+        let tag_set = tags
+            .iter()
+            .map(|(k, v)| format!(",{}={}", k, v))
+            .collect::<String>();
+
+        let timestamp_nanos = self
+            .wall_start
+            .checked_add(self.end.duration_since(self.start))
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        format!(
+            "{}{} fps={},led_rate={},frames={}i,num_leds={}i {}",
+            measurement, tag_set, fps, led_rate, self.frames, self.num, timestamp_nanos
+        )
+    }
 }
 
 /// # Pretty printing.
@@ -166,6 +211,8 @@ impl ops::Add<DrawStats> for DrawStats {
             } else {
                 rhs.num
             },
+
+            wall_start: self.wall_start + (rhs.start - self.end),
         }
     }
 }
@@ -173,6 +220,7 @@ impl ops::Add<DrawStats> for DrawStats {
 /// Like ops::Add, but assigns to self.
 impl ops::AddAssign<DrawStats> for DrawStats {
     fn add_assign(&mut self, rhs: Self) {
+        self.wall_start += rhs.start - self.end;
         self.start += rhs.start - self.end;
         self.end = rhs.end;
         self.frames += rhs.frames;
@@ -184,6 +232,27 @@ impl ops::AddAssign<DrawStats> for DrawStats {
     }
 }
 
+/// Destination a [`DrawStats`] line-protocol record can be flushed to --
+/// implemented for anything that already implements [`io::Write`] (a
+/// [`File`](std::fs::File), a [`TcpStream`](std::net::TcpStream), or
+/// [`io::Stdout`]), so none of the usual destinations need a bespoke wrapper
+/// type.
+///
+/// Writes are best-effort: a failed write (e.g. a dropped TCP connection) is
+/// silently discarded rather than propagated, since telemetry export should
+/// never be able to interrupt the render loop it's reporting on.
+pub trait StatsSink: std::fmt::Debug {
+    /// Writes a single already-formatted line-protocol record, e.g. one
+    /// produced by [`DrawStats::to_line_protocol`].
+    fn write_stats(&mut self, line: &str);
+}
+
+impl<W: io::Write + std::fmt::Debug> StatsSink for W {
+    fn write_stats(&mut self, line: &str) {
+        let _ = writeln!(self, "{}", line);
+    }
+}
+
 /// Returns a `Vec` of drawer `Info` objects
 #[cfg(target_os = "linux")]
 pub fn draw_info() -> Vec<Box<dyn Info>> {
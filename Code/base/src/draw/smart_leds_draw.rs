@@ -0,0 +1,228 @@
+//! # Smart LEDs Draw
+
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+use smart_leds::{SmartLedsWrite, RGB8};
+use std::collections::VecDeque;
+use std::time::Duration;
+use ws2812_spi::Ws2812;
+
+use crate::ds::collections::frame::Frame;
+use crate::util::{Info, Timer};
+
+use super::*;
+
+/// The default SPI bus to drive the data line over.
+pub const DEFAULT_BUS: Bus = Bus::Spi0;
+/// The default SPI chip-select slave to use.
+pub const DEFAULT_SLAVE_SELECT: SlaveSelect = SlaveSelect::Ss0;
+/// The SPI clock speed WS2812-class strips expect their bit-banged signal encoded at.
+pub const DEFAULT_CLOCK_SPEED: u32 = 3_000_000;
+
+/// Presents some info about `SmartLedsDraw` for pretty printing.
+#[derive(Default, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct SmartLedsDrawInfo();
+
+impl Info for SmartLedsDrawInfo {
+    fn new() -> Box<dyn Info>
+    where
+        Self: Sized,
+    {
+        Box::new(SmartLedsDrawInfo::default())
+    }
+
+    fn name(&self) -> String {
+        "SmartLedsDraw".to_owned()
+    }
+
+    fn details(&self) -> String {
+        "Drives a strip of clockless WS2812-class LEDs over SPI via the smart_leds SmartLedsWrite trait.".to_owned()
+    }
+}
+
+/// Drives a strip of clockless WS2812-class LEDs over SPI, using the
+/// [`ws2812_spi`] driver (an implementer of [`smart_leds::SmartLedsWrite`])
+/// to shift each [`RGB`][0] out as the strip's one-wire protocol expects.
+///
+/// Unlike [`TermDraw`][1]'s terminal emulation, this actually drives
+/// physical hardware wired to the Pi's SPI bus.
+///
+/// [0]: ../../ds/rgb/struct.RGB.html
+/// [1]: ../struct.TermDraw.html
+#[derive(Debug)]
+pub struct SmartLedsDraw {
+    leds: Ws2812<Spi>,
+    count: usize,
+
+    queue: VecDeque<Box<dyn Animation>>,
+    timer: Timer,
+
+    stats: DrawStats,
+}
+
+impl SmartLedsDraw {
+    /// Returns a builder for this struct.
+    pub fn builder() -> SmartLedsDrawBuilder {
+        SmartLedsDrawBuilder::new()
+    }
+
+    /// Creates a new `SmartLedsDraw` object wired to `spi`, driving `count` LEDs.
+    pub fn new(spi: Spi, count: usize, timer: Timer) -> Self {
+        Self {
+            leds: Ws2812::new(spi),
+            count,
+
+            queue: VecDeque::new(),
+            timer,
+
+            stats: DrawStats::new(),
+        }
+    }
+
+    /// Scales `frame` by its own brightness, maps it to [`RGB8`], and shifts
+    /// it out over SPI.
+    fn write_frame(&mut self, frame: &Frame) {
+        let pixels: Vec<RGB8> = frame
+            .iter()
+            .map(|led| {
+                let led = led.scale(frame.brightness());
+                RGB8 {
+                    r: led.red(),
+                    g: led.green(),
+                    b: led.blue(),
+                }
+            })
+            .collect();
+
+        self.leds.write(pixels.into_iter()).unwrap();
+    }
+
+    /// Writes an all-black frame sized to `count`, so the strip goes dark
+    /// instead of freezing lit on whatever frame happened to be showing.
+    fn blank(&mut self) {
+        let _ = self
+            .leds
+            .write(std::iter::repeat(RGB8 { r: 0, g: 0, b: 0 }).take(self.count));
+    }
+}
+
+impl Draw for SmartLedsDraw {
+    fn push_queue(&mut self, a: Box<dyn Animation>) {
+        self.queue.push_back(a);
+    }
+
+    fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn run(&mut self) -> Vec<Box<dyn Animation>> {
+        self.timer.reset();
+        self.stats.reset();
+
+        let zero_duration = Duration::new(0, 0);
+
+        let mut out = Vec::new();
+
+        while let Some(mut ani) = self.queue.pop_front() {
+            while ani.time_remaining() > zero_duration {
+                ani.update(self.timer.ping());
+                self.write_frame(ani.frame());
+
+                self.stats.inc_frames();
+            }
+
+            self.stats.set_num(ani.frame().len());
+            self.stats.end();
+
+            out.push(ani);
+        }
+
+        out
+    }
+
+    fn stats(&self) -> DrawStats {
+        self.stats
+    }
+}
+
+impl Drop for SmartLedsDraw {
+    /// Blanks the strip when the drawer is dropped, the same way
+    /// [`APA102CPiDraw`][crate::draw::pi_draw::APA102CPiDraw] does, so the
+    /// LEDs don't stay lit on whatever frame happened to be showing when the
+    /// process ends.
+    fn drop(&mut self) {
+        self.blank();
+    }
+}
+
+/// Builder for [`SmartLedsDraw`].
+///
+/// Allows for optional setting of the `bus`, `slave_select`, `count`, and
+/// `timer` parameters. If a parameter is not supplied, a default value will
+/// be inserted in its place.
+#[derive(Copy, Clone)]
+pub struct SmartLedsDrawBuilder {
+    bus: Bus,
+    slave_select: SlaveSelect,
+    count: usize,
+    timer: Option<Timer>,
+}
+
+impl SmartLedsDrawBuilder {
+    /// Creates a new builder with safe default values: SPI bus 0, slave
+    /// select 0, and an empty (zero-LED) strip, which must be set via
+    /// [`Self::count`] before building.
+    pub fn new() -> Self {
+        Self {
+            bus: DEFAULT_BUS,
+            slave_select: DEFAULT_SLAVE_SELECT,
+            count: 0,
+            timer: None,
+        }
+    }
+
+    /// Sets the SPI bus the strip's data line is wired to.
+    pub fn bus(mut self, bus: Bus) -> Self {
+        self.bus = bus;
+
+        self
+    }
+
+    /// Sets the SPI chip-select slave the strip's data line is wired to.
+    pub fn slave_select(mut self, slave_select: SlaveSelect) -> Self {
+        self.slave_select = slave_select;
+
+        self
+    }
+
+    /// Sets the number of LEDs on the strip, so [`SmartLedsDraw::blank`]
+    /// knows how many LEDs to clear.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+
+        self
+    }
+}
+
+impl Default for SmartLedsDrawBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrawBuilder for SmartLedsDrawBuilder {
+    fn timer(mut self, timer: Timer) -> Self {
+        self.timer = Some(timer);
+
+        self
+    }
+
+    fn build(self) -> Box<dyn Draw> {
+        let spi = Spi::new(self.bus, self.slave_select, DEFAULT_CLOCK_SPEED, Mode::Mode0).unwrap();
+
+        Box::new(SmartLedsDraw::new(
+            spi,
+            self.count,
+            self.timer.unwrap_or_else(|| Timer::new(None)),
+        ))
+    }
+}
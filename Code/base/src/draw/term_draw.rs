@@ -2,7 +2,7 @@
 
 use colored::Colorize;
 use std::collections::VecDeque;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::ds::collections::frame::Frame;
 use crate::util::{Info, Timer};
@@ -43,6 +43,9 @@ pub struct TermDraw {
     timer: Timer,
 
     stats: DrawStats,
+
+    sink: Option<Box<dyn StatsSink>>,
+    sink_interval: Duration,
 }
 
 impl TermDraw {
@@ -73,6 +76,9 @@ impl TermDraw {
             timer,
 
             stats: DrawStats::new(),
+
+            sink: None,
+            sink_interval: Duration::from_secs(1),
         }
     }
 
@@ -100,6 +106,14 @@ impl TermDraw {
 
         println!("{}", output);
     }
+
+    /// Emits the current [`DrawStats`] as a single line-protocol record to
+    /// the configured [`StatsSink`], if one is set. No-op otherwise.
+    fn flush_stats(&mut self) {
+        if let Some(sink) = self.sink.as_mut() {
+            sink.write_stats(&self.stats.to_line_protocol("term_draw", &[]));
+        }
+    }
 }
 
 impl Draw for TermDraw {
@@ -116,6 +130,7 @@ impl Draw for TermDraw {
         self.stats.reset();
 
         let zero_duration = Duration::new(0, 0);
+        let mut last_flush = Instant::now();
 
         let mut out = Vec::new();
 
@@ -125,6 +140,13 @@ impl Draw for TermDraw {
                 self.write_frame(ani.frame());
 
                 self.stats.inc_frames();
+
+                if self.sink.is_some() && last_flush.elapsed() >= self.sink_interval {
+                    self.stats.set_num(ani.frame().len());
+                    self.stats.end();
+                    self.flush_stats();
+                    last_flush = Instant::now();
+                }
             }
 
             self.stats.set_num(ani.frame().len());
@@ -133,6 +155,8 @@ impl Draw for TermDraw {
             out.push(ani);
         }
 
+        self.flush_stats();
+
         out
     }
 
@@ -156,10 +180,12 @@ impl Default for TermDraw {
 /// [0]: struct.TermDraw.html
 /// [1]: struct.TermDraw.html#method.new
 /// [2]: struct.TermDraw.html#method.default
-#[derive(Default, Copy, Clone)]
+#[derive(Default)]
 pub struct TermDrawBuilder {
     max_width: Option<usize>,
     timer: Option<Timer>,
+    sink: Option<Box<dyn StatsSink>>,
+    sink_interval: Option<Duration>,
 }
 
 impl TermDrawBuilder {
@@ -176,6 +202,20 @@ impl TermDrawBuilder {
 
         self
     }
+
+    /// Sets the destination the running [`DrawStats`] are periodically
+    /// serialized to (see [`DrawStats::to_line_protocol`]) as `sink`, flushed
+    /// no more often than once per `interval` while running, plus once more
+    /// when `run` finishes.
+    ///
+    /// If this parameter is not set, no stats are ever flushed mid-run --
+    /// [`Draw::stats`] is still available as usual once `run` returns.
+    pub fn stats_sink(mut self, sink: Box<dyn StatsSink>, interval: Duration) -> Self {
+        self.sink = Some(sink);
+        self.sink_interval = Some(interval);
+
+        self
+    }
 }
 
 impl DrawBuilder for TermDrawBuilder {
@@ -186,9 +226,16 @@ impl DrawBuilder for TermDrawBuilder {
     }
 
     fn build(self) -> Box<dyn Draw> {
-        Box::new(TermDraw::new(
+        let mut draw = TermDraw::new(
             self.max_width.unwrap_or(8),
             self.timer.unwrap_or(Timer::new(None)),
-        ))
+        );
+
+        draw.sink = self.sink;
+        if let Some(interval) = self.sink_interval {
+            draw.sink_interval = interval;
+        }
+
+        Box::new(draw)
     }
 }
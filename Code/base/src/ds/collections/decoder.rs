@@ -0,0 +1,128 @@
+//! # Decoder
+
+use std::fmt;
+
+/// Error returned by [`Decoder`] when a read runs past the end of the
+/// buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecodeError;
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unexpected end of buffer")
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Reads big-endian integers and raw bytes back out of a buffer, advancing a
+/// cursor as it goes and bounds-checking every read. See [`Encoder`][0] for
+/// the matching writer.
+///
+/// [0]: super::encoder::Encoder
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a new `Decoder` reading from the start of `buf`.
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// The number of bytes not yet read.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns `true` if every byte has been read.
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    /// Reads a single byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if the buffer is exhausted.
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Reads a big-endian `u16`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if the buffer is exhausted.
+    pub fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a big-endian `u32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if the buffer is exhausted.
+    pub fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a big-endian `u64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if the buffer is exhausted.
+    pub fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        let bytes = self.read_bytes(8)?;
+        let mut arr = [0_u8; 8];
+        arr.copy_from_slice(bytes);
+        Ok(u64::from_be_bytes(arr))
+    }
+
+    /// Reads `len` raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if fewer than `len` bytes remain.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self.pos.checked_add(len).ok_or(DecodeError)?;
+        let bytes = self.buf.get(self.pos..end).ok_or(DecodeError)?;
+        self.pos = end;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod decoder_test {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let data = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+            0x0F, 0x10, 0x11,
+        ];
+        let mut dec = Decoder::new(&data);
+
+        assert_eq!(dec.read_u8().unwrap(), 0x01);
+        assert_eq!(dec.read_u16().unwrap(), 0x0203);
+        assert_eq!(dec.read_u32().unwrap(), 0x04050607);
+        assert_eq!(dec.read_u64().unwrap(), 0x08090A0B0C0D0E0F);
+        assert_eq!(dec.read_bytes(2).unwrap(), &[0x10, 0x11]);
+        assert!(dec.is_empty());
+    }
+
+    #[test]
+    fn test_read_past_end() {
+        let data = [0x01];
+        let mut dec = Decoder::new(&data);
+
+        assert_eq!(dec.read_u8(), Ok(0x01));
+        assert_eq!(dec.read_u8(), Err(DecodeError));
+    }
+}
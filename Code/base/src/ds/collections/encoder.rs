@@ -0,0 +1,87 @@
+//! # Encoder
+
+/// Appends big-endian integers and raw bytes to an in-memory buffer, for
+/// building up a binary stream one field at a time. See [`Decoder`][0] for
+/// the matching reader.
+///
+/// [0]: super::decoder::Decoder
+#[derive(Debug, Default, Clone)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates a new, empty `Encoder`.
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    /// Appends a single byte.
+    pub fn write_u8(&mut self, val: u8) {
+        self.buf.push(val);
+    }
+
+    /// Appends a `u16`, big-endian.
+    pub fn write_u16(&mut self, val: u16) {
+        self.buf.extend_from_slice(&val.to_be_bytes());
+    }
+
+    /// Appends a `u32`, big-endian.
+    pub fn write_u32(&mut self, val: u32) {
+        self.buf.extend_from_slice(&val.to_be_bytes());
+    }
+
+    /// Appends a `u64`, big-endian.
+    pub fn write_u64(&mut self, val: u64) {
+        self.buf.extend_from_slice(&val.to_be_bytes());
+    }
+
+    /// Appends a slice of raw bytes, unmodified.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// The number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the encoded bytes written so far, as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Consumes the `Encoder`, returning the encoded bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod encoder_test {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let mut enc = Encoder::new();
+
+        enc.write_u8(0x01);
+        enc.write_u16(0x0203);
+        enc.write_u32(0x04050607);
+        enc.write_u64(0x08090A0B0C0D0E0F);
+        enc.write_bytes(&[0x10, 0x11]);
+
+        assert_eq!(
+            enc.into_vec(),
+            vec![
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+                0x0E, 0x0F, 0x10, 0x11
+            ]
+        );
+    }
+}
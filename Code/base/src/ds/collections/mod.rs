@@ -2,11 +2,15 @@
 //!
 //! Module of collections used within this project.
 
+pub use decoder::Decoder;
+pub use encoder::Encoder;
 pub use frame::Frame;
 pub use sparse_vec::SparseVec;
 pub use vec_reader::VecReader;
 pub use vec_writer::VecWriter;
 
+pub mod decoder;
+pub mod encoder;
 pub mod frame;
 pub mod sparse_vec;
 pub mod vec_reader;
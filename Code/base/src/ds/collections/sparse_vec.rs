@@ -89,48 +89,18 @@ impl<T> SparseVec<T> {
     ///
     /// If a value already exists at the given index then it is overwritten by the inserted value.
     pub fn insert(&mut self, ind: usize, val: T) {
-        if self.buf.len() > 0 {
-            let mut insert_before = None;
-
-            for (i, v) in self.buf.iter_mut().enumerate() {
-                if v.0 == ind {
-                    v.1 = val;
-                    return;
-                }
-
-                if v.0 > ind {
-                    insert_before = Some(i);
-                    break;
-                }
-            }
-
-            if let Some(before) = insert_before {
-                self.buf.insert(before, (ind, val));
-            } else {
-                self.buf.push((ind, val));
-            }
-        } else {
-            self.buf.push((ind, val));
+        match self.buf.binary_search_by_key(&ind, |v| v.0) {
+            Ok(pos) => self.buf[pos].1 = val,
+            Err(pos) => self.buf.insert(pos, (ind, val)),
         }
     }
 
     /// Removes a value at the given index, returning the value if it existed.
     pub fn remove(&mut self, ind: usize) -> Option<IndVal<T>> {
-        let mut remove = None;
-        for (i, v) in self.buf.iter().enumerate() {
-            if v.0 == ind {
-                remove = Some(i);
-                break;
-            }
+        match self.buf.binary_search_by_key(&ind, |v| v.0) {
+            Ok(pos) => Some(self.buf.remove(pos)),
+            Err(_) => None,
         }
-
-        let out = if let Some(i) = remove {
-            Some(self.buf.remove(i))
-        } else {
-            None
-        };
-
-        out
     }
 
     /// Returns the maximum index of all currently stored sparse data points.
@@ -149,10 +119,9 @@ impl<T> SparseVec<T> {
     /// * If the element exists, then Some(Value(element)) is returned.
     /// * If the element doesn't exist, then Some(Empty) is returned.
     pub fn get_ref(&self, ind: usize) -> SparseVecElement<T> {
-        if let Some(dat) = self.buf.iter().find(|v| v.0 == ind) {
-            SparseVecElement::Value(dat)
-        } else {
-            SparseVecElement::Empty
+        match self.buf.binary_search_by_key(&ind, |v| v.0) {
+            Ok(pos) => SparseVecElement::Value(&self.buf[pos]),
+            Err(_) => SparseVecElement::Empty,
         }
     }
 
@@ -163,10 +132,9 @@ impl<T> SparseVec<T> {
     /// * If the element exists, then Some(Value(element)) is returned.
     /// * If the element doesn't exist, then Some(Empty) is returned.
     pub fn get_mut(&mut self, ind: usize) -> SparseVecElementMut<T> {
-        if let Some(dat) = self.buf.iter_mut().find(|v| v.0 == ind) {
-            SparseVecElementMut::Value(dat)
-        } else {
-            SparseVecElementMut::Empty
+        match self.buf.binary_search_by_key(&ind, |v| v.0) {
+            Ok(pos) => SparseVecElementMut::Value(&mut self.buf[pos]),
+            Err(_) => SparseVecElementMut::Empty,
         }
     }
 
@@ -2,10 +2,15 @@
 
 pub mod breath;
 pub mod rainbow;
+pub mod recording;
+
+pub use recording::{RecordedAnimation, Recorder, RecordingError};
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 use crate::draw::Draw;
 use crate::util::rgb::RGB;
-use crate::util::timer::Timer;
 use std::time::{Instant, Duration};
 
 /// Trait for types that implement animations that sets the LEDs to a given
@@ -15,47 +20,177 @@ pub trait Animation {
     fn update(&mut self, dt: Duration, frame: &mut [RGB]);
 }
 
-/// This struct is the manager of all the other systems. It ensures that an
-/// animation is updated before being drawn and properly tracks the passage of
-/// time to provide accurate delta-time readings to the animation.
-pub struct Runner<A>//, D>
-where
-    A: Animation,
-    //D: Draw
-{
-    animation: A,
-    drawer: Box<dyn Draw>,//D,
-    timer: Timer,
-    max_duration: Duration,
+/// Controls how a [`Layer`]'s frame is combined with whatever's already in
+/// the master frame when [`Runner`] composites its layers each tick.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BlendMode {
+    /// Overwrites the master frame outright.
+    Replace,
+    /// Adds each channel onto the master frame, saturating at 255.
+    Additive,
+    /// Linearly interpolates each channel towards this layer's color by
+    /// `alpha`, where `0.0` is fully transparent and `1.0` fully opaque.
+    Alpha(f32),
+}
+
+fn composite(base: RGB, color: RGB, mode: BlendMode) -> RGB {
+    use crate::util::rgb::RGBOrder;
+
+    match mode {
+        BlendMode::Replace => color,
+        BlendMode::Additive => RGB::from_tuple(
+            (
+                base.red().saturating_add(color.red()),
+                base.green().saturating_add(color.green()),
+                base.blue().saturating_add(color.blue()),
+            ),
+            RGBOrder::RGB,
+        ),
+        BlendMode::Alpha(alpha) => {
+            let alpha = alpha.max(0.0).min(1.0);
+            let lerp = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * alpha).round() as u8;
+
+            RGB::from_tuple(
+                (
+                    lerp(base.red(), color.red()),
+                    lerp(base.green(), color.green()),
+                    lerp(base.blue(), color.blue()),
+                ),
+                RGBOrder::RGB,
+            )
+        }
+    }
+}
+
+/// A single animation registered with a [`Runner`]: its own frame buffer,
+/// the period it's updated at, and how its output is composited onto the
+/// master frame.
+struct Layer {
+    animation: Box<dyn Animation>,
+    frame: Vec<RGB>,
+    period: Duration,
+    blend: BlendMode,
+    last_update: Instant,
+}
+
+/// Identifies a layer registered with a [`Runner`] via [`Runner::add_layer`],
+/// for later removal via [`Runner::remove_layer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LayerId(usize);
+
+/// A non-blocking, multi-layer LED scheduler.
+///
+/// Unlike a single-[`Animation`] runner that blocks for a fixed
+/// `max_duration`, `Runner` holds a set of independently-timed layers and
+/// drives exactly one round of updates per [`Self::tick`] call before
+/// returning control to the caller. A min-heap of `(next_deadline,
+/// LayerId)` means [`Self::tick`] only updates the layers that are actually
+/// due, and [`Self::next_deadline`] tells the caller precisely how long it
+/// can sleep for -- or service a SIGINT, reload a config, or push a
+/// network-fed frame -- before the next layer needs attention.
+///
+/// Each tick, every due layer is updated into its own frame buffer, all
+/// layers' buffers are composited into one master frame according to each
+/// layer's [`BlendMode`], and the result is drawn once.
+pub struct Runner {
+    drawer: Box<dyn Draw>,
+    layers: Vec<Option<Layer>>,
+    queue: BinaryHeap<Reverse<(Instant, usize)>>,
+    master: Vec<RGB>,
 }
 
-impl<A>/*, D>*/ Runner<A>//, D>
-where
-    A: Animation,
-    //D: Draw
-{
-    /// Constructs a `Runner` from the given animation and drawer, and with a
-    /// maximum duration that `Runner::run` is allowed to run for.
-    pub fn new(animation: A, drawer: Box<dyn Draw>, target_frame_duration: Option<Duration>, max_duration: Duration) -> Self {
+impl Runner {
+    /// Constructs a `Runner` with no layers registered yet, compositing
+    /// `num_leds` LEDs and drawing them with `drawer`.
+    pub fn new(drawer: Box<dyn Draw>, num_leds: usize) -> Self {
         Self {
-            animation,
             drawer,
-            timer: Timer::new(target_frame_duration),
-            max_duration,
+            layers: Vec::new(),
+            queue: BinaryHeap::new(),
+            master: vec![RGB::new(); num_leds],
+        }
+    }
+
+    /// Registers `animation` as a new layer, updated every `period` and
+    /// composited onto the master frame via `blend`. It's polled for the
+    /// first time on the next [`Self::tick`] call. Returns an id that can be
+    /// passed to [`Self::remove_layer`] to unregister it again.
+    pub fn add_layer(&mut self, animation: Box<dyn Animation>, period: Duration, blend: BlendMode) -> LayerId {
+        let id = self.layers.len();
+        let num_leds = self.master.len();
+
+        self.layers.push(Some(Layer {
+            animation,
+            frame: vec![RGB::new(); num_leds],
+            period,
+            blend,
+            last_update: Instant::now(),
+        }));
+        self.queue.push(Reverse((Instant::now(), id)));
+
+        LayerId(id)
+    }
+
+    /// Unregisters a previously-added layer. A no-op if `id` was already removed.
+    pub fn remove_layer(&mut self, id: LayerId) {
+        if let Some(slot) = self.layers.get_mut(id.0) {
+            *slot = None;
         }
+        self.queue = self.queue.drain().filter(|Reverse((_, i))| *i != id.0).collect();
+    }
+
+    /// The number of layers currently registered.
+    pub fn layer_count(&self) -> usize {
+        self.layers.iter().filter(|layer| layer.is_some()).count()
     }
 
-    /// Runs for the set max duration of time, updating the animation each loop
-    /// before drawing the animation.
-    pub fn run(&mut self) -> Result<(), String> {
-        let begin = Instant::now();
+    /// The instant the next registered layer is due to be updated, or `None`
+    /// if no layers are registered. A caller driving its own loop can sleep
+    /// until this instant rather than busy-spinning between calls to
+    /// [`Self::tick`].
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.queue.peek().map(|Reverse((deadline, _))| *deadline)
+    }
+
+    /// Updates every layer whose deadline has elapsed, composites the
+    /// result into the master frame, and draws it once.
+    ///
+    /// This drives a single round of work and always returns immediately
+    /// afterwards -- it never blocks waiting for a layer's deadline to
+    /// arrive -- so it's safe to call from inside an external loop that
+    /// also services SIGINT, config reloads, or incoming network frames
+    /// between ticks.
+    pub fn tick(&mut self) -> Result<(), String> {
+        let now = Instant::now();
+
+        while let Some(&Reverse((deadline, id))) = self.queue.peek() {
+            if deadline > now {
+                break;
+            }
+            self.queue.pop();
+
+            if let Some(Some(layer)) = self.layers.get_mut(id) {
+                let dt = now - layer.last_update;
+                layer.animation.update(dt, &mut layer.frame);
+                layer.last_update = now;
 
-        while Instant::now() - begin < self.max_duration {
-            let dt = self.timer.ping();
-            self.animation.update(dt, self.drawer.as_mut_slice());
-            self.drawer.write_frame()?;
+                self.queue.push(Reverse((now + layer.period, id)));
+            }
         }
 
-        Ok(())
+        for led in self.master.iter_mut() {
+            *led = RGB::new();
+        }
+        for layer in self.layers.iter().flatten() {
+            for (master, color) in self.master.iter_mut().zip(layer.frame.iter()) {
+                *master = composite(*master, *color, layer.blend);
+            }
+        }
+
+        let target = self.drawer.as_mut_slice();
+        let len = target.len().min(self.master.len());
+        target[..len].copy_from_slice(&self.master[..len]);
+
+        self.drawer.write_frame()
     }
 }
@@ -0,0 +1,278 @@
+//! # Recording
+//!
+//! Records an [`Animation`]'s session to a compact, delta-encoded binary
+//! stream via [`Recorder`], and replays one back as an `Animation` via
+//! [`RecordedAnimation`].
+//!
+//! ## Format
+//!
+//! A recording is a header followed by a sequence of length-prefixed delta
+//! records:
+//!
+//! ```text
+//! header: led_count: u32, color_order: u8
+//! record:  len: u32, {
+//!              secs: u64, nanos: u32,
+//!              count: u32, { index: u32, r: u8, g: u8, b: u8 } * count
+//!          }
+//! ```
+//!
+//! Each record only stores the LEDs that changed since the previous tick, via
+//! a [`SparseVec<RGB>`], so static scenes cost almost nothing.
+
+use std::error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::ds::collections::decoder::{DecodeError, Decoder};
+use crate::ds::collections::encoder::Encoder;
+use crate::ds::collections::sparse_vec::SparseVec;
+use crate::runner::Animation;
+use crate::util::rgb::{RGBOrder, RGB};
+
+fn order_to_byte(order: RGBOrder) -> u8 {
+    match order {
+        RGBOrder::RGB => 0,
+        RGBOrder::RBG => 1,
+        RGBOrder::GRB => 2,
+        RGBOrder::GBR => 3,
+        RGBOrder::BRG => 4,
+        RGBOrder::BGR => 5,
+    }
+}
+
+fn order_from_byte(byte: u8) -> Option<RGBOrder> {
+    Some(match byte {
+        0 => RGBOrder::RGB,
+        1 => RGBOrder::RBG,
+        2 => RGBOrder::GRB,
+        3 => RGBOrder::GBR,
+        4 => RGBOrder::BRG,
+        5 => RGBOrder::BGR,
+        _ => return None,
+    })
+}
+
+/// Error returned by [`RecordedAnimation::new`] when a recording is corrupt.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecordingError {
+    /// The data ended before a complete header or record could be read.
+    Truncated,
+    /// The header's color order byte didn't match a known [`RGBOrder`] variant.
+    InvalidColorOrder(u8),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "recording ended unexpectedly"),
+            Self::InvalidColorOrder(byte) => {
+                write!(f, "recording has an unrecognized color order byte: {}", byte)
+            }
+        }
+    }
+}
+
+impl error::Error for RecordingError {}
+
+impl From<DecodeError> for RecordingError {
+    fn from(_: DecodeError) -> Self {
+        Self::Truncated
+    }
+}
+
+/// Wraps an [`Animation`], recording each tick's frame as a delta against the
+/// previous one into a compact binary stream as it's driven by a
+/// [`Runner`][crate::runner::Runner], for later playback via
+/// [`RecordedAnimation`].
+pub struct Recorder<A> {
+    inner: A,
+    order: RGBOrder,
+    encoder: Encoder,
+    prev: Vec<RGB>,
+    header_written: bool,
+}
+
+impl<A: Animation> Recorder<A> {
+    /// Wraps `inner`, recording its session in the given `order` as it's run.
+    pub fn new(inner: A, order: RGBOrder) -> Self {
+        Self {
+            inner,
+            order,
+            encoder: Encoder::new(),
+            prev: Vec::new(),
+            header_written: false,
+        }
+    }
+
+    /// Consumes the recorder, returning the delta stream recorded so far,
+    /// ready to be replayed via [`RecordedAnimation::new`].
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.encoder.into_vec()
+    }
+}
+
+impl<A: Animation> Animation for Recorder<A> {
+    fn update(&mut self, dt: Duration, frame: &mut [RGB]) {
+        self.inner.update(dt, frame);
+
+        if !self.header_written {
+            self.encoder.write_u32(frame.len() as u32);
+            self.encoder.write_u8(order_to_byte(self.order));
+            self.prev = vec![RGB::new(); frame.len()];
+            self.header_written = true;
+        }
+
+        let mut delta = SparseVec::new();
+        for (i, (new, old)) in frame.iter().zip(self.prev.iter()).enumerate() {
+            if new.as_tuple(RGBOrder::RGB) != old.as_tuple(RGBOrder::RGB) {
+                delta.insert(i, *new);
+            }
+        }
+        self.prev.copy_from_slice(frame);
+
+        let mut record = Encoder::new();
+        record.write_u64(dt.as_secs());
+        record.write_u32(dt.subsec_nanos());
+
+        let entries: Vec<_> = delta.iter().collect();
+        record.write_u32(entries.len() as u32);
+        for (ind, val) in entries {
+            record.write_u32(*ind as u32);
+            let (r, g, b) = val.as_tuple(RGBOrder::RGB);
+            record.write_u8(r);
+            record.write_u8(g);
+            record.write_u8(b);
+        }
+
+        self.encoder.write_u32(record.len() as u32);
+        self.encoder.write_bytes(record.as_slice());
+    }
+}
+
+/// Replays a delta-encoded recording produced by [`Recorder`] as an
+/// [`Animation`], deterministically reproducing the original session without
+/// rerunning whatever generated it.
+pub struct RecordedAnimation {
+    order: RGBOrder,
+    records: Vec<(Duration, SparseVec<RGB>)>,
+    cursor: usize,
+    accum: Duration,
+    frame: Vec<RGB>,
+}
+
+impl RecordedAnimation {
+    /// Decodes a recording produced by [`Recorder::into_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` doesn't hold a complete, well-formed header
+    /// and sequence of delta records.
+    pub fn new(data: &[u8]) -> Result<Self, RecordingError> {
+        let mut dec = Decoder::new(data);
+
+        let led_count = dec.read_u32()? as usize;
+        let order_byte = dec.read_u8()?;
+        let order = order_from_byte(order_byte).ok_or(RecordingError::InvalidColorOrder(order_byte))?;
+
+        let mut records = Vec::new();
+        while !dec.is_empty() {
+            let record_len = dec.read_u32()? as usize;
+            let mut record_dec = Decoder::new(dec.read_bytes(record_len)?);
+
+            let dt = Duration::new(record_dec.read_u64()?, record_dec.read_u32()?);
+
+            let count = record_dec.read_u32()?;
+            let mut delta = SparseVec::new();
+            for _ in 0..count {
+                let ind = record_dec.read_u32()? as usize;
+                let r = record_dec.read_u8()?;
+                let g = record_dec.read_u8()?;
+                let b = record_dec.read_u8()?;
+                delta.insert(ind, RGB::from_tuple((r, g, b), RGBOrder::RGB));
+            }
+
+            records.push((dt, delta));
+        }
+
+        Ok(Self {
+            order,
+            records,
+            cursor: 0,
+            accum: Duration::ZERO,
+            frame: vec![RGB::new(); led_count],
+        })
+    }
+
+    /// The color order the recording was captured in.
+    pub fn color_order(&self) -> RGBOrder {
+        self.order
+    }
+}
+
+impl Animation for RecordedAnimation {
+    fn update(&mut self, dt: Duration, frame: &mut [RGB]) {
+        self.accum += dt;
+
+        while self.cursor < self.records.len() && self.accum >= self.records[self.cursor].0 {
+            self.accum -= self.records[self.cursor].0;
+
+            for (ind, val) in self.records[self.cursor].1.iter() {
+                if let Some(led) = self.frame.get_mut(*ind) {
+                    *led = *val;
+                }
+            }
+
+            self.cursor += 1;
+        }
+
+        let len = frame.len().min(self.frame.len());
+        frame[..len].copy_from_slice(&self.frame[..len]);
+    }
+}
+
+#[cfg(test)]
+mod recording_test {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct ConstAnimation {
+        color: RGB,
+    }
+
+    impl Animation for ConstAnimation {
+        fn update(&mut self, _dt: Duration, frame: &mut [RGB]) {
+            for led in frame.iter_mut() {
+                *led = self.color;
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut recorder = Recorder::new(ConstAnimation::default(), RGBOrder::RGB);
+        let mut frame = vec![RGB::new(); 4];
+
+        recorder.update(Duration::from_millis(10), &mut frame);
+
+        recorder.inner.color = RGB::from_tuple((1, 2, 3), RGBOrder::RGB);
+        recorder.update(Duration::from_millis(20), &mut frame);
+
+        let bytes = recorder.into_bytes();
+
+        let mut playback = RecordedAnimation::new(&bytes).unwrap();
+        assert_eq!(playback.color_order(), RGBOrder::RGB);
+
+        let mut out = vec![RGB::new(); 4];
+
+        playback.update(Duration::from_millis(10), &mut out);
+        for led in out.iter() {
+            assert_eq!(led.as_tuple(RGBOrder::RGB), (0, 0, 0));
+        }
+
+        playback.update(Duration::from_millis(20), &mut out);
+        for led in out.iter() {
+            assert_eq!(led.as_tuple(RGBOrder::RGB), (1, 2, 3));
+        }
+    }
+}
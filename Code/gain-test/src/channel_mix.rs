@@ -0,0 +1,114 @@
+use super::*;
+
+/// Coefficient matrix describing how to derive each output channel as a
+/// weighted sum of input channels, for use with [`remix`]. Row `i` holds one
+/// weight per input channel, used to compute output channel `i` as
+/// `Σ_j coeffs[i][j] * input[j]` -- the same matrix shape covers both
+/// downmixing (e.g. 5.1 -> stereo) and upmixing (e.g. mono -> stereo).
+#[derive(Debug, Clone)]
+pub struct ChannelMap {
+    coeffs: Vec<Vec<SampleT>>,
+}
+
+impl ChannelMap {
+    /// Constructs a [`ChannelMap`] from an explicit coefficient matrix: one
+    /// row per output channel, each row holding one weight per input channel.
+    pub fn new(coeffs: Vec<Vec<SampleT>>) -> Self {
+        Self { coeffs }
+    }
+
+    /// The standard ITU-R downmix from 5.1 (`L, R, C, LFE, Ls, Rs`) to
+    /// stereo: `L' = L + 0.707*C + 0.707*Ls`, `R' = R + 0.707*C + 0.707*Rs`,
+    /// with the LFE channel dropped.
+    pub fn surround_5_1_to_stereo() -> Self {
+        const COEF: SampleT = std::f32::consts::FRAC_1_SQRT_2;
+
+        Self::new(vec![
+            vec![1.0, 0.0, COEF, 0.0, COEF, 0.0],
+            vec![0.0, 1.0, COEF, 0.0, 0.0, COEF],
+        ])
+    }
+
+    /// The standard ITU-R downmix from 5.1 (`L, R, C, LFE, Ls, Rs`) to
+    /// stereo, folding the LFE channel into both output channels at `lfe_gain`.
+    pub fn surround_5_1_to_stereo_with_lfe(lfe_gain: SampleT) -> Self {
+        const COEF: SampleT = std::f32::consts::FRAC_1_SQRT_2;
+
+        Self::new(vec![
+            vec![1.0, 0.0, COEF, lfe_gain, COEF, 0.0],
+            vec![0.0, 1.0, COEF, lfe_gain, 0.0, COEF],
+        ])
+    }
+
+    /// Downmixes stereo to mono as `0.5*(L+R)`.
+    pub fn stereo_to_mono() -> Self {
+        Self::new(vec![vec![0.5, 0.5]])
+    }
+
+    /// Upmixes mono to `channels` output channels, duplicating the source
+    /// signal into each with unity gain.
+    pub fn mono_to_n(channels: usize) -> Self {
+        Self::new(vec![vec![1.0]; channels])
+    }
+
+    /// The number of output channels this map produces.
+    pub fn num_outputs(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    /// The number of input channels this map expects.
+    pub fn num_inputs(&self) -> usize {
+        self.coeffs.first().map(Vec::len).unwrap_or(0)
+    }
+}
+
+/// Converts `tracks` (one [`SampleTrackT`] per input channel) to a new set of
+/// channels via `map`'s coefficient matrix, mixing each output channel as a
+/// weighted sum of the input channels. The result can be fed straight into
+/// [`WaveWriteOptions::write`] with a different channel count than `tracks`.
+///
+/// If `normalize_db` is `Some`, each output channel is peak-normalized to
+/// that dBFS level (via [`normalize`]) afterwards, to guard against clipping
+/// introduced by summing channels together.
+///
+/// # Panics
+///
+/// Panics if `tracks.len()` doesn't match `map.num_inputs()`, or if the
+/// input channels have mismatched lengths.
+pub fn remix(
+    tracks: &[SampleTrackT],
+    map: &ChannelMap,
+    normalize_db: Option<MathT>,
+) -> Vec<SampleTrackT> {
+    assert_eq!(
+        tracks.len(),
+        map.num_inputs(),
+        "ChannelMap expects {} input channel(s), got {}",
+        map.num_inputs(),
+        tracks.len()
+    );
+
+    let len = tracks.first().map(SampleTrackT::len).unwrap_or(0);
+    for t in tracks {
+        assert_eq!(t.len(), len, "input channels have mismatched lengths");
+    }
+
+    let mut out: Vec<SampleTrackT> = (0..map.num_outputs()).map(|_| vec![0.0; len]).collect();
+
+    for (o, row) in map.coeffs.iter().enumerate() {
+        for i in 0..len {
+            out[o][i] = row
+                .iter()
+                .enumerate()
+                .fold(0.0, |sample, (in_ch, &coeff)| sample + tracks[in_ch][i] * coeff);
+        }
+    }
+
+    if let Some(db) = normalize_db {
+        for t in &mut out {
+            normalize(db, t);
+        }
+    }
+
+    out
+}
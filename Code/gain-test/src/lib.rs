@@ -8,16 +8,28 @@
 //! As there is no i24 built-in type, i32 is used in it's place where
 //! applicable. In most cases where a 24-bit sample is stored in a 32-bit data
 //! type, the upper byte is ignored or explicitly set to 0.
+//!
+//! Besides integer PCM (8/16/24-bit), IEEE-754 32-bit float PCM is also
+//! supported, selected via [`WaveSampleFormat::Float`] on [`WaveWriteOptions`]
+//! and decoded automatically by [`read_wav`].
 
 extern crate wav;
 
+pub mod channel_mix;
 pub mod gain;
+pub mod meter;
 pub mod mono;
+pub mod resample;
 pub mod stereo;
+pub mod wav_stream;
 
+pub use channel_mix::*;
 pub use gain::*;
+pub use meter::*;
 pub use mono::*;
+pub use resample::*;
 pub use stereo::*;
+pub use wav_stream::*;
 
 use std::convert::TryFrom;
 use std::ops::*;
@@ -92,6 +104,8 @@ pub trait SampleFormat:
     + Into<Vec<i16>>
     + TryFrom<Vec<i32>, Error = String>
     + Into<Vec<i32>>
+    + TryFrom<Vec<f32>, Error = String>
+    + Into<Vec<f32>>
 {
     /// Creates an object from a single monophonic sample.
     fn from_sample(x: SampleT) -> Self;
@@ -177,6 +191,44 @@ pub fn sample_to_i24_bytes(s: SampleT) -> [u8; 3] {
     [i[0], i[1], i[2]]
 }
 
+/// Converts a full-width i32 32-bit sample to a `SampleT`.
+pub fn sample_from_i32(v: i32) -> SampleT {
+    v as SampleT / (i32::MAX as SampleT)
+}
+/// Converts raw bytes to a `SampleT`.
+pub fn sample_from_i32_bytes(v: [u8; 4]) -> SampleT {
+    (i32::from_le_bytes(v) as SampleT) / (i32::MAX as SampleT)
+}
+
+/// Converts a `SampleT` to an `i32`.
+pub fn sample_to_i32(s: SampleT) -> i32 {
+    (s * (i32::MAX as SampleT)).round() as i32
+}
+/// Converts a `SampleT` to raw little-endian bytes.
+pub fn sample_to_i32_bytes(s: SampleT) -> [u8; 4] {
+    sample_to_i32(s).to_le_bytes()
+}
+
+/// Converts an IEEE-754 32-bit float sample to a `SampleT`, which is a no-op
+/// since `SampleT` is already `f32`.
+pub fn sample_from_f32(v: f32) -> SampleT {
+    v
+}
+/// Converts raw bytes to a `SampleT`.
+pub fn sample_from_f32_bytes(v: [u8; 4]) -> SampleT {
+    f32::from_le_bytes(v)
+}
+
+/// Converts a `SampleT` to an IEEE-754 32-bit float, which is a no-op since
+/// `SampleT` is already `f32`.
+pub fn sample_to_f32(s: SampleT) -> f32 {
+    s
+}
+/// Converts a `SampleT` to raw little-endian bytes.
+pub fn sample_to_f32_bytes(s: SampleT) -> [u8; 4] {
+    sample_to_f32(s).to_le_bytes()
+}
+
 /// Linear interpolation (y-y1 = m * (x-x1)) of a given value.
 #[inline]
 pub fn lerp<T>(x: T, x1: T, x2: T, y1: T, y2: T) -> T
@@ -241,23 +293,23 @@ pub fn db_to_linear(db: MathT) -> MathT {
     10.0_f64.powf(db / 20.0)
 }
 
+/// Computes the DC offset (mean) of a track's samples, removed before peak
+/// detection by [`normalize`] and [`meter::TrackAnalysis::compute`].
+fn dc_offset(t: &[SampleT]) -> SampleT {
+    t.iter().sum::<SampleT>() / t.len() as SampleT
+}
+
 /// Normalizes the given audio track to have a peak value at the given dBFS
 /// value.
 pub fn normalize(db: MathT, t: &mut SampleTrackT) {
-    let y = t.clone();
-    let mut dc = 0.0;
-
-    for s in &y {
-        dc += s;
-    }
-
-    dc /= y.len() as SampleT;
+    let dc = dc_offset(t);
 
     let mut max = 0.0;
 
-    for s in y {
-        if (s - dc).abs() > max {
-            max = (s - dc).abs();
+    for s in t.iter() {
+        let ac = (*s - dc).abs();
+        if ac > max {
+            max = ac;
         }
     }
 
@@ -324,17 +376,43 @@ pub fn read_wav(s: &mut dyn std::io::Read) -> std::io::Result<(wav::Header, Vec<
                 tracks[i % h.channel_count as usize].push(sample_from_i24(d[i]));
             }
         }
+        wav::BitDepth::ThirtyTwoFloat(d) => {
+            for i in 0..d.len() {
+                tracks[i % h.channel_count as usize].push(sample_from_f32(d[i]));
+            }
+        }
 
-        _ => (),
+        wav::BitDepth::Empty => (),
     }
 
     Ok((h, tracks))
 }
 
+/// Selects between integer and IEEE-754 floating-point PCM sample encoding
+/// for [`WaveWriteOptions::write`][0], mirroring the distinction `hound`
+/// draws between `WAVE_FORMAT_PCM` and `WAVE_FORMAT_IEEE_FLOAT`.
+///
+/// [0]: #method.write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveSampleFormat {
+    /// Integer PCM (`WAVE_FORMAT_PCM`). Valid with a bit depth of 8, 16, or 24.
+    Int,
+    /// IEEE-754 floating-point PCM (`WAVE_FORMAT_IEEE_FLOAT`). Valid with a
+    /// bit depth of 32.
+    Float,
+}
+
+impl Default for WaveSampleFormat {
+    fn default() -> Self {
+        WaveSampleFormat::Int
+    }
+}
+
 /// Structure representing the options available to configure the format of the
 /// wave file resulting from a call to [`WaveWriteOptions::write`][0], letting
-/// you control the bits per sample, sampling rate, and whether or not the track
-/// given to [`WaveWriteOptions::write`][0] will be clipped.
+/// you control the bits per sample, whether those bits are integer or
+/// IEEE-754 float PCM, sampling rate, and whether or not the track given to
+/// [`WaveWriteOptions::write`][0] will be clipped.
 ///
 /// This struct uses a builder pattern, allowing you to chain the methods that
 /// set the internal parameters, and then write the values at the end.
@@ -343,6 +421,7 @@ pub fn read_wav(s: &mut dyn std::io::Read) -> std::io::Result<(wav::Header, Vec<
 #[derive(Default)]
 pub struct WaveWriteOptions {
     bps: u16,
+    format: WaveSampleFormat,
     r: MathT,
     clip: bool,
 }
@@ -355,16 +434,31 @@ impl WaveWriteOptions {
 
     /// Sets the bits per sample value.
     ///
-    /// Succeeds if bps is one of either 8, 16, or 24, fails otherwise.
+    /// Succeeds if bps is one of either 8, 16, or 24 with [`WaveSampleFormat::Int`],
+    /// or 32 with [`WaveSampleFormat::Float`], fails otherwise.
     pub fn bps<'a>(&'a mut self, bps: u16) -> Result<&'a mut WaveWriteOptions, ()> {
-        if bps == 8 || bps == 16 || bps == 24 {
-            self.bps = bps;
-            Ok(self)
-        } else {
-            Err(())
+        match (self.format, bps) {
+            (WaveSampleFormat::Int, 8) | (WaveSampleFormat::Int, 16) | (WaveSampleFormat::Int, 24) => {
+                self.bps = bps;
+                Ok(self)
+            }
+            (WaveSampleFormat::Float, 32) => {
+                self.bps = bps;
+                Ok(self)
+            }
+            _ => Err(()),
         }
     }
 
+    /// Sets whether samples are written as integer or IEEE-754 float PCM.
+    ///
+    /// Changing this may invalidate a previously set [`Self::bps`]; call
+    /// [`Self::bps`] again afterwards to pick a value valid for the new format.
+    pub fn format<'a>(&'a mut self, format: WaveSampleFormat) -> &'a mut WaveWriteOptions {
+        self.format = format;
+        self
+    }
+
     /// Sets the sampling rate.
     pub fn r<'a>(&'a mut self, r: MathT) -> &'a mut WaveWriteOptions {
         self.r = r;
@@ -438,8 +532,8 @@ impl WaveWriteOptions {
             }
         }
 
-        match self.bps {
-            8 => {
+        match (self.format, self.bps) {
+            (WaveSampleFormat::Int, 8) => {
                 let mut v = Vec::new();
 
                 for i in 0..len {
@@ -454,7 +548,7 @@ impl WaveWriteOptions {
                     d,
                 )?;
             }
-            16 => {
+            (WaveSampleFormat::Int, 16) => {
                 let mut v = Vec::new();
 
                 for i in 0..len {
@@ -469,7 +563,7 @@ impl WaveWriteOptions {
                     d,
                 )?;
             }
-            24 => {
+            (WaveSampleFormat::Int, 24) => {
                 let mut v = Vec::new();
 
                 for i in 0..len {
@@ -484,10 +578,25 @@ impl WaveWriteOptions {
                     d,
                 )?;
             }
+            (WaveSampleFormat::Float, 32) => {
+                let mut v = Vec::new();
+
+                for i in 0..len {
+                    for t in &tracks {
+                        v.push(sample_to_f32(t[i]));
+                    }
+                }
+
+                wav::write(
+                    wav::Header::new(1, tracks.len() as u16, self.r as u32, self.bps),
+                    wav::BitDepth::ThirtyTwoFloat(v),
+                    d,
+                )?;
+            }
             _ => {
                 return Err(Error::new(
                     ErrorKind::InvalidData,
-                    "Unsupported bit depth, aborting.",
+                    "Unsupported bit depth/format combination, aborting.",
                 ))
             }
         }
@@ -0,0 +1,130 @@
+use super::*;
+
+/// True-peak oversampling factor used by [`TrackAnalysis::compute`],
+/// following the ITU-R BS.1770 recommendation of 4x.
+const TRUE_PEAK_OVERSAMPLE: MathT = 4.0;
+
+/// Relative gating threshold, in dB below the mean block energy, used by
+/// [`TrackAnalysis::compute`]'s integrated-loudness measurement to discard
+/// quiet blocks (e.g. silence) before averaging.
+const LOUDNESS_GATE_DB: MathT = -10.0;
+
+/// Single-pass peak/RMS/loudness metering over a [`SampleTrackT`], computed
+/// in `chunk_size`-sample blocks by [`TrackAnalysis::compute`].
+#[derive(Debug, Clone)]
+pub struct TrackAnalysis {
+    /// Per-block RMS, one entry per `chunk_size`-sample block, as computed by
+    /// [`gain::calc`].
+    pub block_rms: SampleTrackT,
+    /// Per-block peak (DC-removed), one entry per `chunk_size`-sample block.
+    pub block_peak: SampleTrackT,
+    /// The largest [`Self::block_peak`] value across the whole track.
+    pub global_peak: SampleT,
+    /// The RMS of the whole track, treated as a single block.
+    pub global_rms: SampleT,
+    /// `global_peak / global_rms`: how much headroom the track's peaks have
+    /// over its average level.
+    pub crest_factor: SampleT,
+    /// The largest true-peak estimate across the whole track, obtained by
+    /// 4x-oversampling each block (via [`resample_sinc`]) before taking the
+    /// max, to catch inter-sample peaks a sample-domain peak would miss.
+    pub true_peak: SampleT,
+    /// Gated integrated loudness across the track: the mean RMS of all
+    /// blocks whose energy is no more than [`LOUDNESS_GATE_DB`] below the
+    /// mean block energy, discarding outlying quiet blocks (e.g. silence).
+    pub integrated_loudness: SampleT,
+}
+
+impl TrackAnalysis {
+    /// Computes a [`TrackAnalysis`] over `track` in `chunk_size`-sample
+    /// blocks, at `sample_rate` Hz (used to pace the true-peak oversampling).
+    pub fn compute(track: &SampleTrackT, chunk_size: usize, sample_rate: MathT) -> Self {
+        let block_rms = calc(track.clone(), chunk_size);
+
+        let dc = dc_offset(track);
+
+        let block_peak: SampleTrackT = track
+            .chunks(chunk_size)
+            .map(|block| {
+                block
+                    .iter()
+                    .fold(0.0, |max: SampleT, &s| max.max((s - dc).abs()))
+            })
+            .collect();
+
+        let global_peak = block_peak.iter().cloned().fold(0.0, SampleT::max);
+
+        let global_rms = calc(track.clone(), track.len().max(1))
+            .first()
+            .copied()
+            .unwrap_or(0.0);
+
+        let crest_factor = if global_rms > 0.0 {
+            global_peak / global_rms
+        } else {
+            0.0
+        };
+
+        let true_peak = track
+            .chunks(chunk_size)
+            .map(|block| {
+                let block = block.to_vec();
+                let oversampled =
+                    resample_sinc(&block, sample_rate, sample_rate * TRUE_PEAK_OVERSAMPLE);
+
+                oversampled
+                    .iter()
+                    .fold(0.0, |max: SampleT, &s| max.max(s.abs()))
+            })
+            .fold(0.0, SampleT::max);
+
+        let block_energy: SampleTrackT = block_rms.iter().map(|r| r * r).collect();
+        let mean_energy =
+            block_energy.iter().sum::<SampleT>() / block_energy.len().max(1) as SampleT;
+        // Energy is a power quantity, so the dB offset is applied as 10^(dB/10).
+        let gate = mean_energy * (10.0 as MathT).powf(LOUDNESS_GATE_DB / 10.0) as SampleT;
+
+        let gated: SampleTrackT = block_rms
+            .iter()
+            .zip(block_energy.iter())
+            .filter(|(_, &e)| e >= gate)
+            .map(|(&r, _)| r)
+            .collect();
+
+        let integrated_loudness = if gated.is_empty() {
+            0.0
+        } else {
+            gated.iter().sum::<SampleT>() / gated.len() as SampleT
+        };
+
+        Self {
+            block_rms,
+            block_peak,
+            global_peak,
+            global_rms,
+            crest_factor,
+            true_peak,
+            integrated_loudness,
+        }
+    }
+
+    /// [`Self::global_peak`], expressed in dBFS.
+    pub fn global_peak_db(&self) -> MathT {
+        linear_to_db(self.global_peak as MathT)
+    }
+
+    /// [`Self::global_rms`], expressed in dBFS.
+    pub fn global_rms_db(&self) -> MathT {
+        linear_to_db(self.global_rms as MathT)
+    }
+
+    /// [`Self::true_peak`], expressed in dBFS (dBTP).
+    pub fn true_peak_db(&self) -> MathT {
+        linear_to_db(self.true_peak as MathT)
+    }
+
+    /// [`Self::integrated_loudness`], expressed in dBFS.
+    pub fn integrated_loudness_db(&self) -> MathT {
+        linear_to_db(self.integrated_loudness as MathT)
+    }
+}
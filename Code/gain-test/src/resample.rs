@@ -0,0 +1,111 @@
+use super::*;
+
+/// Number of taps on each side of the sinc kernel used by [`resample_sinc`].
+const SINC_TAPS: isize = 16;
+
+/// Kaiser window shape parameter for [`resample_sinc`]'s sinc kernel, chosen
+/// for a reasonably deep stopband without an excessively wide kernel.
+const KAISER_BETA: f64 = 8.6;
+
+/// Zeroth-order modified Bessel function of the first kind, approximated by
+/// its power series -- used to evaluate the Kaiser window.
+fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.0;
+
+    let mut sum = 1.0;
+    let mut term = 1.0;
+
+    for k in 1..20 {
+        term *= (half_x / k as f64).powi(2);
+        sum += term;
+    }
+
+    sum
+}
+
+/// The Kaiser window, evaluated at integer offset `k` within `±taps`.
+fn kaiser(k: isize, taps: isize, beta: f64) -> f64 {
+    let ratio = k as f64 / taps as f64;
+
+    bessel_i0(beta * (1.0 - ratio * ratio).max(0.0).sqrt()) / bessel_i0(beta)
+}
+
+/// The normalized sinc function, `sin(πx)/(πx)`, with `sinc(0) = 1`.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Resamples `track` from `from` Hz to `to` Hz using linear interpolation.
+///
+/// For each output index `n`, the source position is `p = n * from/to`, and
+/// the output sample is [`clerp`]ed between `track[p.floor()]` and
+/// `track[p.floor() + 1]` (zero past the end of `track`).
+///
+/// Fast, but introduces audible aliasing/imaging artifacts; prefer
+/// [`resample_sinc`] for higher-quality conversions.
+pub fn resample(track: &SampleTrackT, from: MathT, to: MathT) -> SampleTrackT {
+    let ratio = from / to;
+    let out_len = (track.len() as f64 / ratio).round() as usize;
+
+    (0..out_len)
+        .map(|n| {
+            let p = n as MathT * ratio;
+            let i = p.floor() as usize;
+            let frac = (p - p.floor()) as SampleT;
+
+            let s0 = track.get(i).copied().unwrap_or(0.0);
+            let s1 = track.get(i + 1).copied().unwrap_or(0.0);
+
+            clerp(frac, 0.0, 1.0, s0, s1)
+        })
+        .collect()
+}
+
+/// Resamples `track` from `from` Hz to `to` Hz using a windowed-sinc filter.
+///
+/// Each output sample convolves `track` against a sinc kernel over
+/// `±`[`SINC_TAPS`] taps around the source position, windowed by a Kaiser
+/// window to taper the kernel's edges. The kernel's cutoff is lowered to
+/// `min(1.0, to/from)` to suppress aliasing when downsampling. Track edges
+/// are treated as zero-padded.
+///
+/// Substantially higher quality than [`resample`], at the cost of `O(taps)`
+/// work per output sample instead of `O(1)`.
+pub fn resample_sinc(track: &SampleTrackT, from: MathT, to: MathT) -> SampleTrackT {
+    let ratio = from / to;
+    let out_len = (track.len() as f64 / ratio).round() as usize;
+    let cutoff = (to / from).min(1.0);
+
+    (0..out_len)
+        .map(|n| {
+            let p = n as MathT * ratio;
+            let i = p.floor() as isize;
+            let frac = p - p.floor();
+
+            let mut acc = 0.0_f64;
+
+            for k in -SINC_TAPS..=SINC_TAPS {
+                let idx = i + k;
+                let sample = if idx >= 0 {
+                    track.get(idx as usize).copied().unwrap_or(0.0)
+                } else {
+                    0.0
+                } as f64;
+
+                acc +=
+                    sample * sinc((frac - k as f64) * cutoff) * kaiser(k, SINC_TAPS, KAISER_BETA);
+            }
+
+            // The windowed sinc above is evaluated at `cutoff` frequency, so
+            // its passband gain is `1/cutoff`; multiplying back by `cutoff`
+            // normalizes it to unity gain instead of amplifying every
+            // downsampled output by that factor.
+            (acc * cutoff) as SampleT
+        })
+        .collect()
+}
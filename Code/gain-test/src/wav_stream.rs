@@ -0,0 +1,341 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use super::*;
+
+/// WAV audio format code for integer PCM (`WAVE_FORMAT_PCM`).
+const FORMAT_PCM: u16 = 1;
+/// WAV audio format code for IEEE-754 float PCM (`WAVE_FORMAT_IEEE_FLOAT`).
+const FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// Size in bytes of the canonical header [`WavStreamWriter`] writes: a
+/// `RIFF`/`WAVE` chunk header, a 16-byte `fmt ` chunk, and a `data` chunk
+/// header immediately preceding the sample data, with no intervening chunks.
+const HEADER_LEN: u64 = 44;
+
+/// Streams frames (one [`SampleT`] per channel) out of a WAV file lazily via
+/// [`Iterator`], rather than loading the whole file into memory up front like
+/// [`read_wav`].
+///
+/// Parses the `RIFF`/`WAVE` header once on construction, then each call to
+/// [`Iterator::next`] reads and converts exactly one frame on the fly via the
+/// existing `sample_from_*` helpers.
+pub struct WavStreamReader<R> {
+    reader: R,
+    channels: u16,
+    bits_per_sample: u16,
+    format: u16,
+    sample_rate: u32,
+    frames_remaining: u64,
+}
+
+impl<R: Read> WavStreamReader<R> {
+    /// Parses the WAV header from `reader`, leaving it positioned at the
+    /// start of the sample data ready for [`Iterator::next`] to begin
+    /// streaming frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `reader` isn't a well-formed `RIFF`/`WAVE` stream
+    /// with a `fmt ` chunk describing a supported bit depth/format pair, or
+    /// if reading from it fails.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut riff = [0u8; 12];
+        reader.read_exact(&mut riff)?;
+
+        if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a RIFF/WAVE stream",
+            ));
+        }
+
+        let (format, channels, sample_rate, bits_per_sample, data_len) =
+            Self::read_chunks(&mut reader)?;
+
+        match (format, bits_per_sample) {
+            (FORMAT_PCM, 8) | (FORMAT_PCM, 16) | (FORMAT_PCM, 24) | (FORMAT_PCM, 32)
+            | (FORMAT_IEEE_FLOAT, 32) => (),
+            (format, bits) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unsupported WAV format {} at {} bits per sample", format, bits),
+                ))
+            }
+        }
+
+        let bytes_per_sample = (bits_per_sample / 8) as u64;
+        let frame_len = bytes_per_sample * channels as u64;
+        let frames_remaining = if frame_len == 0 { 0 } else { data_len / frame_len };
+
+        Ok(Self {
+            reader,
+            channels,
+            bits_per_sample,
+            format,
+            sample_rate,
+            frames_remaining,
+        })
+    }
+
+    /// Reads chunks one at a time until the `fmt ` and `data` chunks are
+    /// both found, skipping over any others (e.g. `LIST`), and returns the
+    /// `fmt ` chunk's fields alongside the `data` chunk's byte length.
+    fn read_chunks(reader: &mut R) -> io::Result<(u16, u16, u32, u16, u64)> {
+        let mut format = None;
+        let mut channels = 0;
+        let mut sample_rate = 0;
+        let mut bits_per_sample = 0;
+
+        loop {
+            let mut chunk_id = [0u8; 4];
+            reader.read_exact(&mut chunk_id)?;
+
+            let mut len_buf = [0u8; 4];
+            reader.read_exact(&mut len_buf)?;
+            let len = u32::from_le_bytes(len_buf);
+
+            if &chunk_id == b"fmt " {
+                let mut fmt = vec![0u8; len as usize];
+                reader.read_exact(&mut fmt)?;
+
+                if fmt.len() < 16 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "fmt chunk is too short to contain bits_per_sample",
+                    ));
+                }
+
+                format = Some(u16::from_le_bytes([fmt[0], fmt[1]]));
+                channels = u16::from_le_bytes([fmt[2], fmt[3]]);
+                sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+                bits_per_sample = u16::from_le_bytes([fmt[14], fmt[15]]);
+            } else if &chunk_id == b"data" {
+                let format = format.ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "data chunk before fmt chunk")
+                })?;
+
+                return Ok((format, channels, sample_rate, bits_per_sample, len as u64));
+            } else {
+                // RIFF chunks are padded to an even length.
+                let mut skip_buf = vec![0u8; (len + (len % 2)) as usize];
+                reader.read_exact(&mut skip_buf)?;
+            }
+        }
+    }
+
+    /// The number of channels each frame yielded by this reader holds.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// The sample rate read from the file's `fmt ` chunk.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn read_sample(&mut self) -> io::Result<SampleT> {
+        Ok(match (self.format, self.bits_per_sample) {
+            (FORMAT_PCM, 8) => {
+                let mut buf = [0u8; 1];
+                self.reader.read_exact(&mut buf)?;
+                sample_from_u8_bytes(buf)
+            }
+            (FORMAT_PCM, 16) => {
+                let mut buf = [0u8; 2];
+                self.reader.read_exact(&mut buf)?;
+                sample_from_i16_bytes(buf)
+            }
+            (FORMAT_PCM, 24) => {
+                let mut buf = [0u8; 3];
+                self.reader.read_exact(&mut buf)?;
+                sample_from_i24_bytes(buf)
+            }
+            (FORMAT_PCM, 32) => {
+                let mut buf = [0u8; 4];
+                self.reader.read_exact(&mut buf)?;
+                sample_from_i32_bytes(buf)
+            }
+            (FORMAT_IEEE_FLOAT, 32) => {
+                let mut buf = [0u8; 4];
+                self.reader.read_exact(&mut buf)?;
+                sample_from_f32_bytes(buf)
+            }
+            (format, bits) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unsupported WAV format {} at {} bits per sample", format, bits),
+                ))
+            }
+        })
+    }
+}
+
+impl<R: Read> Iterator for WavStreamReader<R> {
+    type Item = io::Result<Vec<SampleT>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frames_remaining == 0 {
+            return None;
+        }
+
+        let mut frame = Vec::with_capacity(self.channels as usize);
+        for _ in 0..self.channels {
+            match self.read_sample() {
+                Ok(s) => frame.push(s),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        self.frames_remaining -= 1;
+
+        Some(Ok(frame))
+    }
+}
+
+/// Streams frames (one [`SampleT`] per channel) into a WAV file
+/// incrementally, rather than requiring every channel fully buffered in
+/// memory up front like [`WaveWriteOptions::write`].
+///
+/// Writes a header with placeholder RIFF/`data` chunk sizes immediately on
+/// construction, converts and writes each frame pushed via
+/// [`Self::push_frame`] on the fly, and backfills the real chunk sizes once
+/// the total frame count is known, via [`Self::finalize`] or, if not called
+/// explicitly, on [`Drop`].
+pub struct WavStreamWriter<W> {
+    writer: W,
+    channels: u16,
+    bps: u16,
+    format: WaveSampleFormat,
+    clip: bool,
+    frames_written: u64,
+    finalized: bool,
+}
+
+impl<W: Write + Seek> WavStreamWriter<W> {
+    /// Writes a WAV header to `writer` for `channels` channels of audio per
+    /// `options`, ready for frames to be pushed via [`Self::push_frame`]. The
+    /// RIFF and `data` chunk sizes are placeholders, backfilled once the
+    /// total frame count is known by [`Self::finalize`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing the header to `writer` fails.
+    pub fn new(mut writer: W, options: &WaveWriteOptions, channels: u16) -> io::Result<Self> {
+        let format_code = match options.format {
+            WaveSampleFormat::Int => FORMAT_PCM,
+            WaveSampleFormat::Float => FORMAT_IEEE_FLOAT,
+        };
+        let bps = options.bps;
+        let sample_rate = options.r as u32;
+        let block_align: u16 = channels * (bps / 8);
+        let byte_rate = sample_rate * block_align as u32;
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&0u32.to_le_bytes())?; // placeholder, backfilled by finalize()
+        writer.write_all(b"WAVE")?;
+
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&format_code.to_le_bytes())?;
+        writer.write_all(&channels.to_le_bytes())?;
+        writer.write_all(&sample_rate.to_le_bytes())?;
+        writer.write_all(&byte_rate.to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&bps.to_le_bytes())?;
+
+        writer.write_all(b"data")?;
+        writer.write_all(&0u32.to_le_bytes())?; // placeholder, backfilled by finalize()
+
+        Ok(Self {
+            writer,
+            channels,
+            bps,
+            format: options.format,
+            clip: options.clip,
+            frames_written: 0,
+            finalized: false,
+        })
+    }
+
+    /// The number of channels each frame pushed to this writer must hold.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Converts and writes one frame (one [`SampleT`] per channel) to the
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `frame.len()` doesn't match [`Self::channels`], or
+    /// if writing to the underlying stream fails.
+    pub fn push_frame(&mut self, frame: &[SampleT]) -> io::Result<()> {
+        if frame.len() != self.channels as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected {} channel(s), got {}", self.channels, frame.len()),
+            ));
+        }
+
+        for &s in frame {
+            let s = if self.clip { s.max(-1.0).min(1.0) } else { s };
+
+            match (self.format, self.bps) {
+                (WaveSampleFormat::Int, 8) => self.writer.write_all(&sample_to_u8_bytes(s))?,
+                (WaveSampleFormat::Int, 16) => self.writer.write_all(&sample_to_i16_bytes(s))?,
+                (WaveSampleFormat::Int, 24) => self.writer.write_all(&sample_to_i24_bytes(s))?,
+                (WaveSampleFormat::Float, 32) => self.writer.write_all(&sample_to_f32_bytes(s))?,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unsupported bit depth/format combination",
+                    ))
+                }
+            }
+        }
+
+        self.frames_written += 1;
+
+        Ok(())
+    }
+
+    /// Backfills the RIFF and `data` chunk sizes now that the total frame
+    /// count is known, and flushes the underlying writer.
+    ///
+    /// Idempotent: safe to call more than once, and called automatically (its
+    /// error, if any, discarded) on [`Drop`] if not already called explicitly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if seeking or writing to the underlying stream fails.
+    pub fn finalize(&mut self) -> io::Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+
+        let block_align = self.channels as u64 * (self.bps as u64 / 8);
+        let data_len = self.frames_written * block_align;
+        let riff_len = HEADER_LEN - 8 + data_len;
+
+        let end = self.writer.stream_position()?;
+
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&(riff_len as u32).to_le_bytes())?;
+
+        self.writer.seek(SeekFrom::Start(40))?;
+        self.writer.write_all(&(data_len as u32).to_le_bytes())?;
+
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        self.finalized = true;
+
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Drop for WavStreamWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.finalize();
+    }
+}
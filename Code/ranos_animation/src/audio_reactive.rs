@@ -0,0 +1,309 @@
+//! An animation driven by a WAV track's envelope and spectrum, rather than a
+//! fixed procedural pattern -- see [`AudioReactive`].
+
+use std::{fs::File, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::{
+    accessor::Accessor,
+    util::{read_wav, ANALYSIS_SAMPLE_RATE},
+};
+use ranos_core::Diagnostic;
+use ranos_ds::{collections::frame::Frame, const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// Lowest frequency, in Hz, [`AudioReactive`]'s logarithmically-spaced bands start from.
+const LOW_HZ: f32 = 20.0;
+
+/// How much a band's rolling-max normalizer relaxes each frame it isn't
+/// pushed to a new peak, so a single loud transient doesn't permanently
+/// desensitize that band.
+const ROLLING_MAX_DECAY: f32 = 0.999;
+
+/// Where [`AudioReactiveBuilder`] loads its samples from.
+#[derive(Debug, Clone)]
+enum Source {
+    /// A WAV file path, read via [`read_wav`] at build time.
+    Path(PathBuf),
+    /// An already-decoded mono sample buffer and its sample rate.
+    Buffer(Vec<f32>, usize),
+}
+
+/// Builder for the [`AudioReactive`] animation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AudioReactive")]
+pub struct AudioReactiveBuilder {
+    runtime: Duration,
+    #[serde(skip)]
+    source: Option<Source>,
+    num_bands: usize,
+    attack: Duration,
+    release: Duration,
+    gain_floor: f32,
+}
+
+impl AudioReactiveBuilder {
+    /// Sets the length of time the animation should run for.
+    pub fn runtime(mut self: Box<Self>, runtime: Duration) -> Box<Self> {
+        self.runtime = runtime;
+
+        self
+    }
+
+    /// Sets the WAV file this animation reads samples from, via [`read_wav`] at build time.
+    pub fn wav_path(mut self: Box<Self>, path: impl Into<PathBuf>) -> Box<Self> {
+        self.source = Some(Source::Path(path.into()));
+
+        self
+    }
+
+    /// Sets an already-decoded mono sample buffer (and its sample rate) this
+    /// animation reads from, instead of loading a WAV file via [`Self::wav_path`].
+    pub fn samples(mut self: Box<Self>, data: Vec<f32>, sample_rate: usize) -> Box<Self> {
+        self.source = Some(Source::Buffer(data, sample_rate));
+
+        self
+    }
+
+    /// Sets the number of logarithmically-spaced frequency bands the
+    /// spectrum is grouped into, one per LED segment.
+    pub fn num_bands(mut self: Box<Self>, num_bands: usize) -> Box<Self> {
+        self.num_bands = num_bands.max(1);
+
+        self
+    }
+
+    /// Sets how quickly a band's displayed value rises to meet a louder one; see [`AudioReactive`].
+    pub fn attack(mut self: Box<Self>, attack: Duration) -> Box<Self> {
+        self.attack = attack;
+
+        self
+    }
+
+    /// Sets how quickly a band's displayed value falls once it quiets down; see [`AudioReactive`].
+    pub fn release(mut self: Box<Self>, release: Duration) -> Box<Self> {
+        self.release = release;
+
+        self
+    }
+
+    /// Sets the minimum value a band's rolling-max normalizer can fall to,
+    /// so near-silence doesn't get amplified into noise.
+    pub fn gain_floor(mut self: Box<Self>, gain_floor: f32) -> Box<Self> {
+        self.gain_floor = gain_floor.max(f32::EPSILON);
+
+        self
+    }
+
+    /// Constructs an [`AudioReactive`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither [`Self::wav_path`] nor [`Self::samples`] was called,
+    /// or if the WAV file at [`Self::wav_path`] couldn't be read.
+    pub fn build(self: Box<Self>) -> AudioReactive {
+        AudioReactive::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for AudioReactiveBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if self.attack.is_zero() {
+            self.attack = Duration::from_millis(1);
+
+            diagnostics.push(Diagnostic::warning(
+                "attack was zero, which would produce an infinite rise rate; bumped to 1ms",
+            ));
+        }
+
+        if self.release.is_zero() {
+            self.release = Duration::from_millis(1);
+
+            diagnostics.push(Diagnostic::warning(
+                "release was zero, which would produce an infinite fall rate; bumped to 1ms",
+            ));
+        }
+
+        diagnostics
+    }
+}
+
+/// Struct for an animation that modulates a [`Frame`] from a loaded WAV
+/// track's envelope and spectrum, rather than a fixed procedural pattern.
+///
+/// Each frame, [`Self::render_frame`] advances an owned [`Accessor`] by `dt`,
+/// takes its most recent samples' RMS as an overall brightness scalar, then
+/// groups its FFT spectrum into [`AudioReactiveBuilder::num_bands`]
+/// logarithmically-spaced frequency bands (one per LED segment), each
+/// normalized against its own rolling maximum and smoothed frame-to-frame by
+/// [`AudioReactiveBuilder::attack`]/[`AudioReactiveBuilder::release`] to
+/// avoid flicker, then mapped to a hue (band index) and value (smoothed
+/// magnitude) via [`RGB::from_hsv`].
+#[derive(Debug)]
+pub struct AudioReactive {
+    runtime: ConstVal<Duration>,
+    time_remaining: Duration,
+
+    accessor: Accessor,
+    num_bands: ConstVal<usize>,
+    band_edges: Vec<f32>,
+    rolling_max: Vec<f32>,
+    smoothed: Vec<f32>,
+    attack: ConstVal<Duration>,
+    release: ConstVal<Duration>,
+    gain_floor: ConstVal<f32>,
+}
+
+impl AudioReactive {
+    /// Constructs a builder object with safe default values. A sample source
+    /// must still be supplied via [`AudioReactiveBuilder::wav_path`] or
+    /// [`AudioReactiveBuilder::samples`] before building.
+    pub fn builder() -> Box<AudioReactiveBuilder> {
+        Box::new(AudioReactiveBuilder {
+            runtime: Duration::from_secs(60),
+            source: None,
+            num_bands: 32,
+            attack: Duration::from_millis(50),
+            release: Duration::from_millis(300),
+            gain_floor: 0.05,
+        })
+    }
+
+    fn from_builder(builder: Box<AudioReactiveBuilder>) -> Self {
+        let (sample_rate, data) = match builder
+            .source
+            .expect("AudioReactive animation requires a sample source, set via AudioReactiveBuilder::wav_path or AudioReactiveBuilder::samples")
+        {
+            Source::Path(path) => {
+                let mut file =
+                    File::open(&path).unwrap_or_else(|e| panic!("failed to open {:?}: {}", path, e));
+
+                read_wav(&mut file).unwrap_or_else(|e| panic!("failed to read wav {:?}: {}", path, e))
+            }
+            Source::Buffer(data, sample_rate) => (sample_rate, data),
+        };
+
+        Self::new(
+            builder.runtime,
+            data,
+            sample_rate,
+            builder.num_bands,
+            builder.attack,
+            builder.release,
+            builder.gain_floor,
+        )
+    }
+
+    fn new(
+        runtime: Duration,
+        data: Vec<f32>,
+        sample_rate: usize,
+        num_bands: usize,
+        attack: Duration,
+        release: Duration,
+        gain_floor: f32,
+    ) -> Self {
+        let nyquist = ANALYSIS_SAMPLE_RATE as f32 / 2.0;
+        let band_edges = (0..=num_bands)
+            .map(|i| LOW_HZ * (nyquist / LOW_HZ).powf(i as f32 / num_bands as f32))
+            .collect();
+
+        Self {
+            runtime: ConstVal::new(runtime),
+            time_remaining: runtime,
+
+            accessor: Accessor::new::<()>(data, sample_rate),
+            num_bands: ConstVal::new(num_bands),
+            band_edges,
+            rolling_max: vec![gain_floor; num_bands],
+            smoothed: vec![0.0; num_bands],
+            attack: ConstVal::new(attack),
+            release: ConstVal::new(release),
+            gain_floor: ConstVal::new(gain_floor),
+        }
+    }
+
+    /// Advances the smoothing envelope for every band by one frame of `dt`, given this frame's raw band magnitudes.
+    fn update_bands(&mut self, dt: Duration, magnitudes: &[f32]) {
+        let gain_floor = *self.gain_floor.get();
+        let attack_coeff = 1.0 - (-dt.as_secs_f32() / self.attack.get().as_secs_f32()).exp();
+        let release_coeff = (-dt.as_secs_f32() / self.release.get().as_secs_f32()).exp();
+
+        for i in 0..magnitudes.len() {
+            self.rolling_max[i] = (self.rolling_max[i] * ROLLING_MAX_DECAY)
+                .max(magnitudes[i])
+                .max(gain_floor);
+
+            let target = (magnitudes[i] / self.rolling_max[i]).clamp(0.0, 1.0);
+            let prev = self.smoothed[i];
+
+            self.smoothed[i] = if target > prev {
+                prev + (target - prev) * attack_coeff
+            } else {
+                (prev * release_coeff).max(target)
+            };
+        }
+    }
+}
+
+impl Animation for AudioReactive {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        self.accessor.update(dt);
+
+        let samples = self.accessor.most_recent_data();
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        frame.set_brightness(rms.clamp(0.0, 1.0));
+
+        let magnitudes = self.accessor.bands(&self.band_edges);
+        self.update_bands(dt, &magnitudes);
+
+        let num_bands = *self.num_bands.get();
+        let len = frame.len();
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let band = (i * num_bands / len.max(1)).min(num_bands - 1);
+            let hue = band as f32 / num_bands as f32 * 360.0;
+
+            *led = RGB::from_hsv(hue, 1.0, self.smoothed[band]);
+        }
+
+        let mut res = AnimationState::Continue;
+
+        self.time_remaining = if let Some(d) = self.time_remaining.checked_sub(dt) {
+            d
+        } else {
+            res = AnimationState::Last;
+
+            Duration::new(0, 0)
+        };
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.time_remaining = *self.runtime.get();
+
+        let gain_floor = *self.gain_floor.get();
+        for v in self.rolling_max.iter_mut() {
+            *v = gain_floor;
+        }
+        for v in self.smoothed.iter_mut() {
+            *v = 0.0;
+        }
+
+        self
+    }
+}
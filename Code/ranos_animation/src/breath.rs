@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use ranos_core::{ClockDuration, Diagnostic};
 use ranos_ds::{const_val::ConstVal, rgb::RGB};
 
 use super::*;
@@ -50,6 +51,30 @@ impl AnimationBuilder for BreathBuilder {
     fn build(self: Box<Self>) -> Box<dyn Animation> {
         Box::new(self.build())
     }
+
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if let ColorOrder::Ordered(v) = &self.order {
+            if v.is_empty() {
+                self.order = ColorOrder::Random;
+
+                diagnostics.push(Diagnostic::warning(
+                    "order was an empty Ordered list, which would panic on the first frame; reset to Random",
+                ));
+            }
+        }
+
+        if self.breath_duration.is_zero() {
+            self.breath_duration = Duration::from_millis(1);
+
+            diagnostics.push(Diagnostic::warning(
+                "breath_duration was zero, which would divide by zero when computing the breath's position; bumped to 1ms",
+            ));
+        }
+
+        diagnostics
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +114,28 @@ mod builder_test {
             ])
         );
     }
+
+    #[test]
+    fn test_validate_fixes_empty_ordered() {
+        use crate::AnimationBuilder;
+
+        let mut builder = Breath::builder().order(ColorOrder::Ordered(Vec::new()));
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(builder.order, ColorOrder::Random);
+    }
+
+    #[test]
+    fn test_validate_fixes_zero_breath_duration() {
+        use crate::AnimationBuilder;
+
+        let mut builder = Breath::builder().breath_duration(Duration::new(0, 0));
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!builder.breath_duration.is_zero());
+    }
 }
 
 /// Struct for an animated breathing display that will either walk through a
@@ -103,10 +150,15 @@ pub struct Breath {
     ind: usize,
     current_color: RGB,
 
-    acc: ConstVal<f32>,
-    vel: f32,
-    vel0: ConstVal<f32>,
-    pos: f32,
+    breath_duration: ConstVal<ClockDuration>,
+    elapsed: ClockDuration,
+    current_period: u128,
+
+    /// Set by [`Self::qos`] once the render loop reports it's running
+    /// behind; when set, every other frame leaves the LEDs as the last
+    /// rendered scale left them instead of recomputing it.
+    degraded: bool,
+    skip_next: bool,
 }
 
 impl Breath {
@@ -141,36 +193,58 @@ impl Breath {
                 ColorOrder::Ordered(v) => v[0],
                 ColorOrder::Random => RGB::random(),
                 ColorOrder::RandomBright => RGB::random_bright(),
+                ColorOrder::HilbertWalk { bits } => RGB::hilbert_nth(0, bits),
             },
 
-            acc: ConstVal::new(-8.0 / breath_duration.as_secs_f32().powi(2)),
-            vel: 4.0 / breath_duration.as_secs_f32(),
-            vel0: ConstVal::new(4.0 / breath_duration.as_secs_f32()),
-            pos: 0.0,
+            breath_duration: ConstVal::new(ClockDuration::from(breath_duration)),
+            elapsed: ClockDuration::ZERO,
+            current_period: 0,
+            degraded: false,
+            skip_next: false,
         }
     }
 }
 
 impl Animation for Breath {
     fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
-        self.vel += self.acc.get() * dt.as_secs_f32();
-        self.pos += self.vel * dt.as_secs_f32();
-
-        if self.pos <= 0.0 && self.vel < 0.0 {
-            self.pos = 0.0;
-            self.vel = *self.vel0.get();
-
-            if let ColorOrder::Ordered(v) = &self.order {
-                self.ind += 1;
-                self.ind %= v.len();
-                self.current_color = v[self.ind];
-            } else {
-                self.current_color = RGB::random();
+        // Accumulating `elapsed` as an exact `ClockDuration` and deriving
+        // both the breath's position and its period count from it -- rather
+        // than integrating `vel`/`pos` forward by `dt.as_secs_f32()` each
+        // frame -- keeps a long-running breath from drifting out of phase
+        // with its configured `breath_duration`.
+        self.elapsed += ClockDuration::from(dt);
+
+        let period = self.elapsed.periods(*self.breath_duration.get());
+        if period != self.current_period {
+            self.current_period = period;
+
+            match &self.order {
+                ColorOrder::Ordered(v) => {
+                    self.ind += 1;
+                    self.ind %= v.len();
+                    self.current_color = v[self.ind];
+                }
+                ColorOrder::HilbertWalk { bits } => {
+                    self.ind += 1;
+                    self.current_color = RGB::hilbert_nth(self.ind as u64, *bits);
+                }
+                ColorOrder::Random => self.current_color = RGB::random(),
+                ColorOrder::RandomBright => self.current_color = RGB::random_bright(),
             }
         }
 
-        for led in frame.iter_mut() {
-            *led = self.current_color.scale(self.pos);
+        let t_frac = self.elapsed.rem(*self.breath_duration.get()).as_secs_f64()
+            / self.breath_duration.get().as_secs_f64();
+        let pos = (4.0 * t_frac * (1.0 - t_frac)) as f32;
+
+        if self.degraded && self.skip_next {
+            self.skip_next = false;
+        } else {
+            self.skip_next = self.degraded;
+
+            for led in frame.iter_mut() {
+                *led = self.current_color.scale(pos);
+            }
         }
 
         let mut res = AnimationState::Continue;
@@ -197,9 +271,17 @@ impl Animation for Breath {
             ColorOrder::Ordered(v) => v[0],
             ColorOrder::Random => RGB::random(),
             ColorOrder::RandomBright => RGB::random_bright(),
+            ColorOrder::HilbertWalk { bits } => RGB::hilbert_nth(0, *bits),
         };
-        self.vel = *self.vel0.get();
+        self.elapsed = ClockDuration::ZERO;
+        self.current_period = 0;
+        self.degraded = false;
+        self.skip_next = false;
 
         self
     }
+
+    fn qos(&mut self, proportion: f64) {
+        self.degraded = proportion > 1.0;
+    }
 }
@@ -13,4 +13,12 @@ pub enum ColorOrder {
     RandomBright,
     /// Order determined by the associated data which is looped through sequentially.
     Ordered(Vec<RGB>),
+    /// Order determined by walking a 3D Hilbert space-filling curve through
+    /// the RGB cube, `bits` bits per channel, one step per color -- since
+    /// adjacent points on the curve are adjacent in the cube, consecutive
+    /// colors vary smoothly with no harsh jumps.
+    HilbertWalk {
+        /// Bits per channel used by the curve; the walk repeats every `2^(3*bits)` steps.
+        bits: u8,
+    },
 }
@@ -0,0 +1,295 @@
+//! # Compositor
+//!
+//! Blends several [`Animation`] "layers" into one [`Frame`] each tick, each
+//! layer rendered into its own scratch frame and then folded into the output
+//! in ascending priority order using a per-layer [`BlendMode`] -- rather than
+//! [`Timeline`](crate::timeline::Timeline), whose entries take turns owning
+//! the whole frame, every [`Compositor`] layer renders every tick and layers
+//! may freely overlap, composited together instead of scheduled apart.
+//!
+//! Each layer can also carry a list of [`OpacityKeyframe`]s so it fades
+//! in/out over time instead of cutting in at full strength: the interpolated
+//! opacity scales the layer's scratch frame before it's folded in, the same
+//! way [`ranos_display::Display`] scales a frame by its brightness.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{collections::frame::Frame, rgb::BlendMode};
+
+use super::*;
+
+/// A single opacity keyframe: how long it takes to interpolate the layer's
+/// opacity from wherever it currently sits to `target_opacity`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OpacityKeyframe {
+    /// How long the fade from the previous opacity to this one's takes.
+    pub duration: Duration,
+    /// The opacity, in `[0, 1]`, to interpolate towards over `duration`.
+    pub target_opacity: f32,
+}
+
+/// One prioritized layer's static configuration, built up on a
+/// [`CompositorBuilder`] via [`CompositorBuilder::layer`].
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerBuilder {
+    animation: Box<dyn AnimationBuilder>,
+    priority: u32,
+    blend: BlendMode,
+    keyframes: Vec<OpacityKeyframe>,
+}
+
+/// Builder for the [`Compositor`] animation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Compositor")]
+pub struct CompositorBuilder {
+    layers: Vec<LayerBuilder>,
+}
+
+impl CompositorBuilder {
+    /// Adds a layer playing `animation` every tick, folded into the output
+    /// with `blend`. Layers composite low-to-high `priority`, so a
+    /// higher-priority layer folded in afterwards overlays a lower-priority
+    /// one beneath it. `keyframes` fades the layer's opacity over time in
+    /// the order given, holding at the last keyframe's `target_opacity` once
+    /// they're exhausted; pass an empty list for a layer that's simply
+    /// always fully opaque.
+    pub fn layer(
+        mut self: Box<Self>,
+        animation: Box<dyn AnimationBuilder>,
+        priority: u32,
+        blend: BlendMode,
+        keyframes: Vec<OpacityKeyframe>,
+    ) -> Box<Self> {
+        self.layers.push(LayerBuilder {
+            animation,
+            priority,
+            blend,
+            keyframes,
+        });
+
+        self
+    }
+
+    /// Constructs a [`Compositor`] object.
+    pub fn build(self: Box<Self>) -> Compositor {
+        Compositor::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for CompositorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{CompositorBuilder, OpacityKeyframe};
+    use crate::Solid;
+    use ranos_ds::rgb::BlendMode;
+    use std::time::Duration;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Box::new(CompositorBuilder::default()).layer(
+            Solid::builder(),
+            0,
+            BlendMode::Replace,
+            vec![OpacityKeyframe {
+                duration: Duration::from_secs(1),
+                target_opacity: 0.5,
+            }],
+        );
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let expected = r#"(layers:[(animation:(type:"SolidBuilder",value:(runtime:(secs:8,nanos:0),color:(0,255,255))),priority:0,blend:Replace,keyframes:[(duration:(secs:1,nanos:0),target_opacity:0.5)])])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(layers:[(animation:(type:"SolidBuilder",value:(runtime:(secs:8,nanos:0),color:(0,255,255))),priority:2,blend:Additive,keyframes:[])])"#;
+
+        let builder: CompositorBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(builder.layers.len(), 1);
+        assert_eq!(builder.layers[0].priority, 2);
+    }
+}
+
+/// A single layer's runtime state: its sub-animation, the scratch frame it
+/// renders into in isolation, how that scratch frame gets folded into the
+/// composited output, and its opacity keyframe progress.
+#[derive(Debug)]
+struct Layer {
+    animation: Box<dyn Animation>,
+    priority: u32,
+    blend: BlendMode,
+    keyframes: Vec<OpacityKeyframe>,
+
+    scratch: Frame,
+    finished: bool,
+
+    kf_ind: usize,
+    kf_elapsed: Duration,
+    prev_opacity: f32,
+    opacity: f32,
+}
+
+impl Layer {
+    fn from_builder(builder: LayerBuilder) -> Self {
+        Self {
+            animation: builder.animation.build(),
+            priority: builder.priority,
+            blend: builder.blend,
+            keyframes: builder.keyframes,
+
+            scratch: Frame::new(1.0, 0),
+            finished: false,
+
+            kf_ind: 0,
+            kf_elapsed: Duration::new(0, 0),
+            prev_opacity: 1.0,
+            opacity: 1.0,
+        }
+    }
+
+    /// Advances this layer's opacity keyframes by `dt`, holding at the last
+    /// keyframe's `target_opacity` once they've all elapsed.
+    fn advance_opacity(&mut self, dt: Duration) {
+        if self.keyframes.is_empty() {
+            return;
+        }
+
+        self.kf_elapsed += dt;
+        let current = &self.keyframes[self.kf_ind];
+
+        let t = if current.duration.is_zero() {
+            1.0
+        } else {
+            (self.kf_elapsed.as_secs_f32() / current.duration.as_secs_f32()).min(1.0)
+        };
+
+        self.opacity = self.prev_opacity + (current.target_opacity - self.prev_opacity) * t;
+
+        if self.kf_elapsed >= current.duration && self.kf_ind + 1 < self.keyframes.len() {
+            self.prev_opacity = current.target_opacity;
+            self.kf_elapsed -= current.duration;
+            self.kf_ind += 1;
+        }
+    }
+
+    fn reset_opacity(&mut self) {
+        self.kf_ind = 0;
+        self.kf_elapsed = Duration::new(0, 0);
+        self.prev_opacity = 1.0;
+        self.opacity = 1.0;
+    }
+}
+
+/// Animation that plays several sub-animations at once, each a prioritized
+/// layer rendered into its own scratch frame and then folded into the output
+/// frame with its own [`BlendMode`] and opacity, which can itself be
+/// keyframed over time via [`OpacityKeyframe`] to fade layers in and out.
+///
+/// Every layer renders every tick regardless of priority -- priority only
+/// affects the order layers are folded together afterwards, back-to-front.
+///
+/// To create a [`Compositor`], use the associated [builder](CompositorBuilder),
+/// accessed via [`Compositor::builder()`].
+#[derive(Debug)]
+pub struct Compositor {
+    layers: Vec<Layer>,
+}
+
+impl Compositor {
+    /// Constructs a builder object with no layers by default.
+    pub fn builder() -> Box<CompositorBuilder> {
+        Box::<CompositorBuilder>::default()
+    }
+
+    fn from_builder(builder: Box<CompositorBuilder>) -> Self {
+        Self::new(builder.layers)
+    }
+
+    fn new(layer_builders: Vec<LayerBuilder>) -> Self {
+        let mut layers: Vec<Layer> = layer_builders.into_iter().map(Layer::from_builder).collect();
+        layers.sort_by_key(|l| l.priority);
+
+        Self { layers }
+    }
+}
+
+impl Animation for Compositor {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        let len = frame.len();
+
+        for layer in self.layers.iter_mut() {
+            if layer.scratch.len() != len {
+                layer.scratch = Frame::new(1.0, len);
+            }
+
+            layer.advance_opacity(dt);
+
+            if let AnimationState::Last = layer.animation.render_frame(&mut layer.scratch, dt) {
+                layer.finished = true;
+            }
+        }
+
+        for layer in self.layers.iter() {
+            let scaled_brightness = layer.scratch.brightness() * layer.opacity;
+
+            for i in 0..len {
+                let src = layer.scratch.as_slice()[i].scale(scaled_brightness);
+                frame.as_mut_slice()[i] = frame.as_slice()[i].blend(src, layer.blend);
+            }
+        }
+
+        if self.layers.iter().all(|l| l.finished) {
+            AnimationState::Last
+        } else {
+            AnimationState::Continue
+        }
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.layers
+            .iter()
+            .map(|l| l.animation.time_remaining())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        for layer in self.layers.iter_mut() {
+            let animation = std::mem::replace(&mut layer.animation, Box::new(NullAnimation));
+            layer.animation = animation.reset();
+            layer.finished = false;
+            layer.reset_opacity();
+        }
+
+        self
+    }
+}
+
+/// Placeholder animation used only to satisfy `std::mem::replace` while
+/// [`Compositor::reset`] swaps a layer's animation for its own reset result.
+#[derive(Debug)]
+struct NullAnimation;
+
+impl Animation for NullAnimation {
+    fn render_frame(&mut self, _frame: &mut Frame, _dt: Duration) -> AnimationState {
+        AnimationState::Last
+    }
+
+    fn time_remaining(&self) -> Duration {
+        Duration::new(0, 0)
+    }
+
+    fn reset(self: Box<Self>) -> Box<dyn Animation> {
+        self
+    }
+}
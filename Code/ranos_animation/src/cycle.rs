@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
+use ranos_core::Diagnostic;
 use ranos_ds::{const_val::ConstVal, rgb::{RGB, RGBOrder}};
 
 use super::*;
@@ -49,6 +50,55 @@ impl AnimationBuilder for CycleBuilder {
     fn build(self: Box<Self>) -> Box<dyn Animation> {
         Box::new(self.build())
     }
+
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if let ColorOrder::Ordered(v) = &self.order {
+            if v.is_empty() {
+                self.order = ColorOrder::Random;
+
+                diagnostics.push(Diagnostic::warning(
+                    "order was an empty Ordered list, which would panic on the first frame; reset to Random",
+                ));
+            }
+        }
+
+        if self.cycle_period.is_zero() {
+            self.cycle_period = Duration::from_millis(1);
+
+            diagnostics.push(Diagnostic::warning(
+                "cycle_period was zero, which would underflow the color timer and panic on the first frame; bumped to 1ms",
+            ));
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::Cycle;
+    use crate::{AnimationBuilder, ColorOrder};
+    use std::time::Duration;
+
+    #[test]
+    fn test_validate_fixes_empty_ordered() {
+        let mut builder = Cycle::builder().order(ColorOrder::Ordered(Vec::new()));
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(builder.order, ColorOrder::Random);
+    }
+
+    #[test]
+    fn test_validate_fixes_zero_cycle_period() {
+        let mut builder = Cycle::builder().cycle_period(Duration::new(0, 0));
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!builder.cycle_period.is_zero());
+    }
 }
 
 /// Struct for a simple cycling between colors by either walking a provided list
@@ -102,6 +152,7 @@ impl Cycle {
                 ColorOrder::Ordered(v) => v[0],
                 ColorOrder::Random => RGB::random(),
                 ColorOrder::RandomBright => RGB::random_bright(),
+                ColorOrder::HilbertWalk { bits } => RGB::hilbert_nth(0, bits),
             },
 
             cycle_period: cycle_period.into(),
@@ -115,12 +166,18 @@ impl Animation for Cycle {
         self.cycle_time_remaining = if let Some(d) = self.cycle_time_remaining.checked_sub(dt) {
             d
         } else {
-            if let ColorOrder::Ordered(v) = &self.order {
-                self.ind += 1;
-                self.ind %= v.len();
-                self.current_color = v[self.ind];
-            } else {
-                self.current_color = RGB::random()
+            match &self.order {
+                ColorOrder::Ordered(v) => {
+                    self.ind += 1;
+                    self.ind %= v.len();
+                    self.current_color = v[self.ind];
+                }
+                ColorOrder::HilbertWalk { bits } => {
+                    self.ind += 1;
+                    self.current_color = RGB::hilbert_nth(self.ind as u64, *bits);
+                }
+                ColorOrder::Random => self.current_color = RGB::random(),
+                ColorOrder::RandomBright => self.current_color = RGB::random_bright(),
             }
 
             // Only update the frame when there's a new color
@@ -155,6 +212,7 @@ impl Animation for Cycle {
             ColorOrder::Ordered(v) => v[0],
             ColorOrder::Random => RGB::random(),
             ColorOrder::RandomBright => RGB::random_bright(),
+            ColorOrder::HilbertWalk { bits } => RGB::hilbert_nth(0, *bits),
         };
         self.cycle_time_remaining = *self.cycle_period.get();
     }
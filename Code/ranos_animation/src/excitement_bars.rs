@@ -0,0 +1,290 @@
+//! # Excitement Bars
+//!
+//! An animation that maps `ranos_audio`'s [`Excitement`] bins onto the strip,
+//! rather than hand-rolling its own FFT/banding like [`crate::Spectrum`] does.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::{
+    analysis::{BinScale, Excitement, WindowFunction},
+    player::Player,
+};
+use ranos_ds::{collections::frame::Frame, const_val::ConstVal, rgb::RGB};
+
+use crate::ColorOrder;
+
+use super::*;
+
+/// How [`ExcitementBars`] picks each bin's base color before scaling it by
+/// the bin's excitement value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ColorMap {
+    /// Sweeps hue evenly across the bins, fully saturated -- the simplest mapping.
+    Hue,
+    /// Interpolates linearly between two endpoint colors across the bins.
+    Gradient(RGB, RGB),
+    /// Reuses a [`ColorOrder`] to assign each bin a color, the same as
+    /// [`crate::Cycle`] does per step.
+    Order(ColorOrder),
+}
+
+/// Builder for the [`ExcitementBars`] animation.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ExcitementBars")]
+pub struct ExcitementBarsBuilder {
+    runtime: Duration,
+    num_bins: usize,
+    bin_range: (f32, f32),
+    scalar: f32,
+    decay: f32,
+    color_map: ColorMap,
+    #[serde(skip)]
+    player: Option<Arc<Mutex<Player>>>,
+}
+
+impl std::fmt::Debug for ExcitementBarsBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExcitementBarsBuilder")
+            .field("runtime", &self.runtime)
+            .field("num_bins", &self.num_bins)
+            .field("bin_range", &self.bin_range)
+            .field("scalar", &self.scalar)
+            .field("decay", &self.decay)
+            .field("color_map", &self.color_map)
+            .finish()
+    }
+}
+
+impl ExcitementBarsBuilder {
+    /// Sets the length of time the animation should run for.
+    pub fn runtime(mut self: Box<Self>, runtime: Duration) -> Box<Self> {
+        self.runtime = runtime;
+
+        self
+    }
+
+    /// Sets the number of bins the spectrum is grouped into; see [`Excitement`].
+    pub fn num_bins(mut self: Box<Self>, num_bins: usize) -> Box<Self> {
+        self.num_bins = num_bins.max(1);
+
+        self
+    }
+
+    /// Sets the range of the spectrum, as fractions of Nyquist, that's
+    /// binned; see [`Excitement`].
+    pub fn bin_range(mut self: Box<Self>, bin_range: (f32, f32)) -> Box<Self> {
+        self.bin_range = bin_range;
+
+        self
+    }
+
+    /// Sets the scalar each bin's magnitude is multiplied by before curving; see [`Excitement`].
+    pub fn scalar(mut self: Box<Self>, scalar: f32) -> Box<Self> {
+        self.scalar = scalar;
+
+        self
+    }
+
+    /// Sets how much each bin's value decays per frame, in `[0, 1)`, once
+    /// its energy falls; see [`Excitement`].
+    pub fn decay(mut self: Box<Self>, decay: f32) -> Box<Self> {
+        self.decay = decay.clamp(0.0, 0.999);
+
+        self
+    }
+
+    /// Sets how each bin's base color is chosen; see [`ColorMap`].
+    pub fn color_map(mut self: Box<Self>, color_map: ColorMap) -> Box<Self> {
+        self.color_map = color_map;
+
+        self
+    }
+
+    /// Sets the shared [`Player`] this animation should pull fresh samples from each frame.
+    pub fn player(mut self: Box<Self>, player: Arc<Mutex<Player>>) -> Box<Self> {
+        self.player = Some(player);
+
+        self
+    }
+
+    /// Constructs an [`ExcitementBars`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Player`] was supplied via [`Self::player`].
+    pub fn build(self: Box<Self>) -> ExcitementBars {
+        ExcitementBars::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for ExcitementBarsBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+/// Struct for an animation that maps a live [`Excitement`] analysis onto the
+/// LED strip.
+///
+/// Each frame, the most recent samples from a shared [`Player`] are fed into
+/// an owned [`Excitement`] analyzer; each resulting bin is assigned a
+/// contiguous span of LEDs, whose base color comes from [`ColorMap`] and
+/// whose `value` in HSV comes from the bin's excitement level, so louder
+/// bins shine brighter rather than simply changing hue.
+#[derive(Debug)]
+pub struct ExcitementBars {
+    runtime: ConstVal<Duration>,
+    time_remaining: Duration,
+
+    player: Arc<Mutex<Player>>,
+    excitement: Excitement,
+    num_bins: ConstVal<usize>,
+    color_map: ColorMap,
+}
+
+impl ExcitementBars {
+    /// Constructs a builder object with safe default values. A [`Player`]
+    /// must still be supplied via [`ExcitementBarsBuilder::player`] before building.
+    pub fn builder() -> Box<ExcitementBarsBuilder> {
+        Box::new(ExcitementBarsBuilder {
+            runtime: Duration::from_secs(16),
+            num_bins: 32,
+            bin_range: (0.0, 1.0),
+            scalar: 1.0,
+            decay: 0.9,
+            color_map: ColorMap::Hue,
+            player: None,
+        })
+    }
+
+    fn from_builder(builder: Box<ExcitementBarsBuilder>) -> Self {
+        Self::new(
+            builder.runtime,
+            builder.num_bins,
+            builder.bin_range,
+            builder.scalar,
+            builder.decay,
+            builder.color_map,
+            builder
+                .player
+                .expect("ExcitementBars animation requires a Player, set via ExcitementBarsBuilder::player"),
+        )
+    }
+
+    fn new(
+        runtime: Duration,
+        num_bins: usize,
+        bin_range: (f32, f32),
+        scalar: f32,
+        decay: f32,
+        color_map: ColorMap,
+        player: Arc<Mutex<Player>>,
+    ) -> Self {
+        let sample_rate = player.lock().unwrap().sample_rate();
+
+        Self {
+            runtime: ConstVal::new(runtime),
+            time_remaining: runtime,
+
+            player,
+            excitement: Excitement::new(
+                scalar,
+                0.0,
+                decay,
+                bin_range,
+                num_bins,
+                WindowFunction::Hann,
+                BinScale::Mel,
+                sample_rate,
+            ),
+            num_bins: ConstVal::new(num_bins),
+            color_map,
+        }
+    }
+
+    /// Copies the player's most recent samples and feeds them into the analyzer.
+    fn update_bins(&mut self) {
+        let samples: Vec<f32> = {
+            let player = self.player.lock().unwrap();
+            player.most_recent_data().to_vec()
+        };
+
+        self.excitement.update(&samples);
+    }
+
+    /// Returns bin `i`'s (of `num_bins`) base color, per [`ColorMap`].
+    fn base_color(&self, i: usize, num_bins: usize) -> RGB {
+        match &self.color_map {
+            ColorMap::Hue => RGB::from_hsv(i as f32 / num_bins as f32 * 360.0, 1.0, 1.0),
+            ColorMap::Gradient(start, end) => {
+                let t = i as f32 / (num_bins - 1).max(1) as f32;
+                let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+                RGB::from_tuple(
+                    (
+                        lerp(start.red(), end.red()),
+                        lerp(start.green(), end.green()),
+                        lerp(start.blue(), end.blue()),
+                    ),
+                    ranos_ds::rgb::RGBOrder::RGB,
+                )
+            }
+            ColorMap::Order(order) => match order {
+                ColorOrder::Random => RGB::random(),
+                ColorOrder::RandomBright => RGB::random_bright(),
+                ColorOrder::Ordered(colors) => {
+                    if colors.is_empty() {
+                        RGB::new()
+                    } else {
+                        colors[i % colors.len()]
+                    }
+                }
+                ColorOrder::HilbertWalk { bits } => RGB::hilbert_nth(i as u64, *bits),
+            },
+        }
+    }
+}
+
+impl Animation for ExcitementBars {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        self.update_bins();
+
+        let num_bins = *self.num_bins.get();
+        let len = frame.len();
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let bin = (i * num_bins / len.max(1)).min(num_bins - 1);
+            let level = self.excitement.bins()[bin].clamp(0.0, 1.0);
+
+            let (hue, sat, _) = self.base_color(bin, num_bins).into_hsv();
+            *led = RGB::from_hsv(hue, sat, level);
+        }
+
+        let mut res = AnimationState::Continue;
+
+        self.time_remaining = if let Some(d) = self.time_remaining.checked_sub(dt) {
+            d
+        } else {
+            res = AnimationState::Last;
+
+            Duration::new(0, 0)
+        };
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.time_remaining = *self.runtime.get();
+        self
+    }
+}
@@ -0,0 +1,264 @@
+//! A flame animation driven by a simple bottom-up energy simulation, in the
+//! style of the classic "Fire2012" effect -- heat injected at the bottom of
+//! the strip, propagated upward, cooled, and mapped to a red-orange-yellow
+//! ramp that flashes white at the hottest spots.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    collections::frame::Frame,
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// The largest fraction of a cell's energy that a single frame's upward
+/// propagation step may pass on to the cell above it.
+const MAX_ENERGY_PROPAGATION: f32 = 0.4;
+/// Per-step multiplicative decay applied to every cell's energy, after the
+/// [`FireBuilder::cooldown`] multiply, so energy always trends toward zero
+/// even at a `cooldown` of `1.0`.
+const RM_ENERGY_MULT: f32 = 0.98;
+/// Per-step subtractive decay applied alongside [`RM_ENERGY_MULT`].
+const RM_ENERGY_SUB: f32 = 0.01;
+/// Scales energy before raising it to [`W_EXPONENT`] to produce the
+/// white-boost term, so only the hottest cells pick up any white at all.
+const W_SCALE: f32 = 1.0;
+/// Gamma applied to the scaled energy to produce the white-boost term --
+/// steep, so white only shows up at the very top of the energy range.
+const W_EXPONENT: f32 = 4.0;
+
+/// Builder for the [`Fire`] animation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Fire")]
+pub struct FireBuilder {
+    runtime: Duration,
+    new_energy: f32,
+    cooldown: f32,
+    exponent: f32,
+    overdrive: f32,
+}
+
+impl FireBuilder {
+    /// Sets the length of time the animation should run for.
+    pub fn runtime(mut self: Box<Self>, runtime: Duration) -> Box<Self> {
+        self.runtime = runtime;
+
+        self
+    }
+
+    /// Sets the maximum amount of energy injected into the bottom of the
+    /// strip each frame (actual injection is `rand::random::<f32>() * new_energy`).
+    pub fn new_energy(mut self: Box<Self>, new_energy: f32) -> Box<Self> {
+        self.new_energy = new_energy.max(0.0);
+
+        self
+    }
+
+    /// Sets the per-second multiplicative cooldown applied to every cell's
+    /// energy, e.g. `0.9995` for a slow-burning flame, lower for a flickerier one.
+    pub fn cooldown(mut self: Box<Self>, cooldown: f32) -> Box<Self> {
+        self.cooldown = cooldown.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets the gamma curve exponent mapping energy to color intensity --
+    /// higher values push more of the strip toward black before the flame shows.
+    pub fn exponent(mut self: Box<Self>, exponent: f32) -> Box<Self> {
+        self.exponent = exponent.max(0.0);
+
+        self
+    }
+
+    /// Sets the headroom multiplier applied to intensity before clamping, so
+    /// hot spots reach full red/yellow at lower energy. It does not affect
+    /// blue; the shift toward white comes from the separate white-boost term.
+    pub fn overdrive(mut self: Box<Self>, overdrive: f32) -> Box<Self> {
+        self.overdrive = overdrive.max(0.0);
+
+        self
+    }
+
+    /// Constructs a [`Fire`] object.
+    pub fn build(self: Box<Self>) -> Fire {
+        Fire::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for FireBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::FireBuilder;
+    use crate::Fire;
+    use std::time::Duration;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Fire::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected =
+            r#"(runtime:(secs:16,nanos:0),new_energy:0.8,cooldown:0.9995,exponent:1.5,overdrive:0.2)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input =
+            r#"(runtime:(secs:16,nanos:0),new_energy:0.8,cooldown:0.9995,exponent:1.5,overdrive:0.2)"#;
+
+        let data: FireBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.runtime, Duration::from_secs(16));
+        assert_eq!(data.new_energy, 0.8);
+        assert_eq!(data.cooldown, 0.9995);
+        assert_eq!(data.exponent, 1.5);
+        assert_eq!(data.overdrive, 0.2);
+    }
+}
+
+/// Struct for simulating a flame climbing a 1-D LED strip: heat is injected
+/// at LED 0, propagated upward cell by cell, cooled, and mapped to a
+/// red-orange-yellow-white ramp.
+///
+/// To create a [`Fire`], use the associated [builder](FireBuilder), accessed
+/// via [`Fire::builder()`].
+#[derive(Debug)]
+pub struct Fire {
+    runtime: ConstVal<Duration>,
+    time_remaining: Duration,
+
+    energy: Vec<f32>,
+
+    new_energy: ConstVal<f32>,
+    cooldown: ConstVal<f32>,
+    exponent: ConstVal<f32>,
+    overdrive: ConstVal<f32>,
+}
+
+impl Fire {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<FireBuilder> {
+        Box::new(FireBuilder {
+            runtime: Duration::from_secs(16),
+            new_energy: 0.8,
+            cooldown: 0.9995,
+            exponent: 1.5,
+            overdrive: 0.2,
+        })
+    }
+
+    fn from_builder(builder: Box<FireBuilder>) -> Self {
+        Self::new(
+            builder.runtime,
+            builder.new_energy,
+            builder.cooldown,
+            builder.exponent,
+            builder.overdrive,
+        )
+    }
+
+    fn new(runtime: Duration, new_energy: f32, cooldown: f32, exponent: f32, overdrive: f32) -> Self {
+        Self {
+            runtime: ConstVal::new(runtime),
+            time_remaining: runtime,
+
+            energy: Vec::new(),
+
+            new_energy: ConstVal::new(new_energy),
+            cooldown: ConstVal::new(cooldown),
+            exponent: ConstVal::new(exponent),
+            overdrive: ConstVal::new(overdrive),
+        }
+    }
+
+    /// Maps a single cell's energy (expected roughly in `[0, 1]`, though
+    /// nothing here clamps the input) to the red-orange-yellow-white flame color.
+    fn energy_to_color(&self, energy: f32) -> RGB {
+        let intensity = energy.max(0.0).powf(*self.exponent.get()) * (1.0 + self.overdrive.get());
+
+        let red = (intensity * 255.0).min(255.0);
+        let green = ((intensity - 0.4) * (255.0 / 0.6)).max(0.0).min(255.0);
+        let blue = 0.0_f32;
+
+        let white = (energy.max(0.0) * W_SCALE).powf(W_EXPONENT) * 255.0;
+
+        RGB::from_tuple(
+            (
+                (red + white).min(255.0) as u8,
+                (green + white).min(255.0) as u8,
+                (blue + white).min(255.0) as u8,
+            ),
+            RGBOrder::RGB,
+        )
+    }
+}
+
+impl Animation for Fire {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        let len = frame.len();
+        if self.energy.len() != len {
+            self.energy = vec![0.0; len];
+        }
+
+        if len > 0 {
+            // 1. Inject energy into the bottom of the strip.
+            self.energy[0] += rand::random::<f32>() * self.new_energy.get();
+
+            // 2. Propagate upward, each cell taking a random fraction --
+            // capped at `MAX_ENERGY_PROPAGATION` -- of the cell below it.
+            // Walking top-down reads each lower cell's energy before this
+            // frame's propagation has touched it.
+            for i in (1..len).rev() {
+                let frac = rand::random::<f32>().min(MAX_ENERGY_PROPAGATION);
+                self.energy[i] += self.energy[i - 1] * frac;
+            }
+
+            // 3. Cool every cell down.
+            let cooldown = self.cooldown.get().powf(dt.as_secs_f32());
+            for e in self.energy.iter_mut() {
+                *e *= cooldown;
+                *e = (*e * RM_ENERGY_MULT - RM_ENERGY_SUB).max(0.0);
+            }
+
+            // 4. Map energy to color.
+            for (led, &e) in frame.iter_mut().zip(self.energy.iter()) {
+                *led = self.energy_to_color(e);
+            }
+        }
+
+        let mut res = AnimationState::Continue;
+
+        self.time_remaining = if let Some(d) = self.time_remaining.checked_sub(dt) {
+            d
+        } else {
+            res = AnimationState::Last;
+
+            Duration::new(0, 0)
+        };
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.time_remaining = *self.runtime.get();
+        self.energy.iter_mut().for_each(|e| *e = 0.0);
+
+        self
+    }
+}
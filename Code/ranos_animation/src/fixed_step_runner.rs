@@ -0,0 +1,93 @@
+//! # FixedStepRunner
+//!
+//! A driver that advances any [`Animation`] on a fixed logical timestep,
+//! regardless of how irregular the wall-clock `dt` it's fed actually is.
+
+use std::time::Duration;
+
+use ranos_ds::collections::frame::Frame;
+
+use super::*;
+
+/// How many fixed-size sub-steps a single [`FixedStepRunner::advance`] call
+/// actually ran, and the wrapped animation's state after the last of them.
+#[derive(Debug)]
+pub struct StepReport {
+    /// How many `fixed_dt`-sized sub-steps were simulated this call. Zero
+    /// means the accumulated time hadn't yet reached a full step.
+    pub steps: usize,
+    /// The animation's state after the last sub-step run, or
+    /// [`AnimationState::Continue`] if `steps` is zero.
+    pub state: AnimationState,
+}
+
+/// Drives any [`Animation`] on a fixed logical timestep, following the
+/// classic accumulator loop: real time is banked in an accumulator, then
+/// drained in whole `fixed_dt`-sized steps, each fed to the animation in turn.
+///
+/// Both the incoming `real_dt` and the accumulator itself are clamped to
+/// `max_accumulated`, so a long stall (a paused process, a slow frame)
+/// can't force an unbounded run of catch-up steps -- the classic "spiral of
+/// death." This makes time-based animations like [`Strobe`] behave
+/// deterministically across jittery render cadences, which feeding a
+/// single raw `dt` straight to [`Animation::render_frame`] can't guarantee.
+#[derive(Debug)]
+pub struct FixedStepRunner {
+    fixed_dt: Duration,
+    max_accumulated: Duration,
+    accumulator: Duration,
+}
+
+impl FixedStepRunner {
+    /// Creates a runner that steps an animation by `fixed_dt` at a time,
+    /// never banking more than `max_accumulated` of unspent real time.
+    pub fn new(fixed_dt: Duration, max_accumulated: Duration) -> Self {
+        Self {
+            fixed_dt,
+            max_accumulated,
+            accumulator: Duration::new(0, 0),
+        }
+    }
+
+    /// Banks `real_dt` (clamped to `max_accumulated`) into the accumulator,
+    /// then renders `fixed_dt`-sized sub-steps into `frame` until less than
+    /// one full step remains, stopping early if the animation reports
+    /// [`AnimationState::Last`].
+    ///
+    /// Before stepping, reports this tick's quality-of-service proportion
+    /// (`real_dt / fixed_dt`, uncapped by `max_accumulated`) to the animation
+    /// via [`Animation::qos`], so it can shed work for the steps about to run.
+    ///
+    /// A `fixed_dt` of [`Duration::ZERO`] has no sensible step size to drain
+    /// the accumulator by, so it's treated as a no-op: `real_dt` is dropped,
+    /// no sub-steps run, and [`Animation::qos`] isn't called, rather than
+    /// dividing by zero or spinning the loop below forever.
+    pub fn advance(&mut self, anim: &mut dyn Animation, frame: &mut Frame, real_dt: Duration) -> StepReport {
+        if self.fixed_dt.is_zero() {
+            return StepReport {
+                steps: 0,
+                state: AnimationState::Continue,
+            };
+        }
+
+        anim.qos(real_dt.as_secs_f64() / self.fixed_dt.as_secs_f64());
+
+        let real_dt = real_dt.min(self.max_accumulated);
+        self.accumulator = (self.accumulator + real_dt).min(self.max_accumulated);
+
+        let mut steps = 0;
+        let mut state = AnimationState::Continue;
+
+        while self.accumulator >= self.fixed_dt {
+            state = anim.render_frame(frame, self.fixed_dt);
+            self.accumulator -= self.fixed_dt;
+            steps += 1;
+
+            if matches!(state, AnimationState::Last) {
+                break;
+            }
+        }
+
+        StepReport { steps, state }
+    }
+}
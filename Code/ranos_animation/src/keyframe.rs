@@ -0,0 +1,260 @@
+//! # Keyframe
+//!
+//! A data-driven animation built from an ordered list of keyframes, each
+//! addressing a contiguous LED range or named tag with a target color.
+//!
+//! Note: this implements the [`Animation`] trait like every other animation
+//! in this module. There's no `push_queue`-style runtime API for swapping a
+//! running app's animation in place yet — wiring one up would need to thread
+//! through however the app ends up consuming animations.
+
+use std::{collections::HashMap, ops::Range, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{collections::frame::Frame, const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// The target addressed by a single [`KeyFrameSetting`]: either a contiguous
+/// range of LED indices, or a named tag resolved against the tag table
+/// supplied to the [`KeyframeBuilder`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Target {
+    /// A contiguous range of LED indices, `start..end`.
+    Range(Range<usize>),
+    /// A named group of LED indices, resolved via the animation's tag table.
+    Tag(String),
+    /// Every LED in the frame.
+    All,
+}
+
+/// A single per-segment setting within a [`KeyFrame`]: the LEDs it addresses
+/// and the color they should reach by the keyframe's `duration`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFrameSetting {
+    /// The LEDs this setting applies to.
+    pub target: Target,
+    /// The color to interpolate towards over the keyframe's duration.
+    pub color: RGB,
+}
+
+/// A single keyframe: a duration and the per-segment settings to interpolate towards over it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFrame {
+    /// How long it takes to interpolate from the previous keyframe's colors to this one's.
+    pub duration: Duration,
+    /// The settings addressed by this keyframe. LEDs not addressed by any
+    /// setting here are left untouched.
+    pub settings: Vec<KeyFrameSetting>,
+}
+
+/// Builder for the [`Keyframe`] animation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Keyframe")]
+pub struct KeyframeBuilder {
+    keyframes: Vec<KeyFrame>,
+    tags: HashMap<String, Range<usize>>,
+    looping: bool,
+}
+
+impl KeyframeBuilder {
+    /// Appends a keyframe to the end of the timeline.
+    pub fn keyframe(mut self: Box<Self>, keyframe: KeyFrame) -> Box<Self> {
+        self.keyframes.push(keyframe);
+
+        self
+    }
+
+    /// Registers a named tag as an alias for a contiguous LED range, so
+    /// keyframes can address it via [`Target::Tag`].
+    pub fn tag(mut self: Box<Self>, name: impl Into<String>, range: Range<usize>) -> Box<Self> {
+        self.tags.insert(name.into(), range);
+
+        self
+    }
+
+    /// Sets whether the keyframe list loops back to the start once it finishes.
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.looping = looping;
+
+        self
+    }
+
+    /// Constructs a [`Keyframe`] object.
+    pub fn build(self: Box<Self>) -> Keyframe {
+        Keyframe::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for KeyframeBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{KeyFrame, KeyFrameSetting, Keyframe, Target};
+    use ranos_ds::rgb::{RGBOrder, RGB};
+    use std::time::Duration;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Keyframe::builder().keyframe(KeyFrame {
+            duration: Duration::from_secs(2),
+            settings: vec![KeyFrameSetting {
+                target: Target::Range(0..8),
+                color: RGB::from_code(0xFF0000, RGBOrder::RGB),
+            }],
+        });
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let expected = r#"(keyframes:[(duration:(secs:2,nanos:0),settings:[(target:Range((start:0,end:8)),color:(255,0,0))])],tags:{},looping:false)"#;
+        assert_eq!(data, expected);
+    }
+}
+
+/// Struct for a scripted light show: an ordered timeline of [`KeyFrame`]s that
+/// are linearly interpolated between, frame by frame.
+///
+/// Each keyframe addresses some subset of the LED strip, by contiguous range
+/// or by a named tag; LEDs not addressed by the current keyframe are left
+/// untouched. Once the last keyframe's duration elapses, the timeline either
+/// loops back to the start or the animation finishes, per
+/// [`KeyframeBuilder::looping`].
+#[derive(Debug)]
+pub struct Keyframe {
+    keyframes: ConstVal<Vec<KeyFrame>>,
+    tags: ConstVal<HashMap<String, Range<usize>>>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+    prev_colors: HashMap<usize, RGB>,
+
+    time_remaining: Duration,
+}
+
+impl Keyframe {
+    /// Constructs a builder object with no keyframes, tags, or looping by default.
+    pub fn builder() -> Box<KeyframeBuilder> {
+        Box::new(KeyframeBuilder::default())
+    }
+
+    fn from_builder(builder: Box<KeyframeBuilder>) -> Self {
+        Self::new(builder.keyframes, builder.tags, builder.looping)
+    }
+
+    fn new(keyframes: Vec<KeyFrame>, tags: HashMap<String, Range<usize>>, looping: bool) -> Self {
+        let total_runtime = keyframes.iter().map(|k| k.duration).sum();
+
+        Self {
+            keyframes: ConstVal::new(keyframes),
+            tags: ConstVal::new(tags),
+            looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+            prev_colors: HashMap::new(),
+
+            time_remaining: total_runtime,
+        }
+    }
+
+    /// Resolves a [`Target`] into the concrete range of LED indices it
+    /// addresses. `len` is the length of the frame being rendered, used to
+    /// resolve [`Target::All`].
+    fn resolve(&self, target: &Target, len: usize) -> Option<Range<usize>> {
+        match target {
+            Target::Range(r) => Some(r.clone()),
+            Target::Tag(name) => self.tags.get().get(name).cloned(),
+            Target::All => Some(0..len),
+        }
+    }
+}
+
+impl Animation for Keyframe {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        if self.keyframes.get().is_empty() {
+            return AnimationState::Last;
+        }
+
+        self.elapsed += dt;
+
+        let current = &self.keyframes.get()[self.ind];
+        let t = (self.elapsed.as_secs_f32() / current.duration.as_secs_f32()).min(1.0);
+
+        let len = frame.len();
+
+        for setting in &current.settings {
+            if let Some(range) = self.resolve(&setting.target, len) {
+                for i in range {
+                    if i >= len {
+                        continue;
+                    }
+
+                    let prev = *self.prev_colors.get(&i).unwrap_or(&RGB::new());
+                    frame.as_mut_slice()[i] = lerp_color(prev, setting.color, t);
+                }
+            }
+        }
+
+        let mut res = AnimationState::Continue;
+
+        if self.elapsed >= current.duration {
+            for setting in &current.settings {
+                if let Some(range) = self.resolve(&setting.target, len) {
+                    for i in range {
+                        self.prev_colors.insert(i, setting.color);
+                    }
+                }
+            }
+
+            self.elapsed = Duration::new(0, 0);
+            self.ind += 1;
+
+            if self.ind >= self.keyframes.get().len() {
+                if self.looping {
+                    self.ind = 0;
+                } else {
+                    res = AnimationState::Last;
+                }
+            }
+        }
+
+        self.time_remaining = self.time_remaining.checked_sub(dt).unwrap_or_default();
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+        self.prev_colors.clear();
+        self.time_remaining = self.keyframes.get().iter().map(|k| k.duration).sum();
+
+        self
+    }
+}
+
+/// Linearly interpolates between two colors by `t`, clamped to `[0, 1]`.
+fn lerp_color(from: RGB, to: RGB, t: f32) -> RGB {
+    let t = t.min(1.0).max(0.0);
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+    RGB::from_tuple(
+        (
+            lerp(from.red(), to.red()),
+            lerp(from.green(), to.green()),
+            lerp(from.blue(), to.blue()),
+        ),
+        ranos_ds::rgb::RGBOrder::RGB,
+    )
+}
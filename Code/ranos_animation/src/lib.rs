@@ -9,36 +9,53 @@ extern crate ranos_ds;
 
 use std::time::Duration;
 
+use ranos_core::Diagnostic;
 use ranos_ds::collections::frame::Frame;
 
+pub use audio_reactive::AudioReactive;
 pub use breath::Breath;
 pub use color_order::ColorOrder;
+pub use compositor::Compositor;
 pub use cycle::Cycle;
+pub use excitement_bars::{ColorMap, ExcitementBars};
+pub use fire::Fire;
+pub use fixed_step_runner::{FixedStepRunner, StepReport};
+pub use keyframe::Keyframe;
+pub use palette::Palette;
+pub use palette_rainbow::PaletteRainbow;
 pub use rainbow::Rainbow;
+pub use signal_processing::SignalProcessing;
 pub use solid::Solid;
+pub use spectrum::Spectrum;
+pub use spline::{InterpMode, Spline};
 pub use strobe::Strobe;
+pub use timeline::Timeline;
 
+pub mod audio_reactive;
 pub mod breath;
 pub mod color_order;
+pub mod compositor;
 pub mod cycle;
+pub mod excitement_bars;
+pub mod fire;
+pub mod fixed_step_runner;
+pub mod keyframe;
+pub mod palette;
+pub mod palette_rainbow;
 pub mod rainbow;
+pub mod signal_processing;
 pub mod solid;
+pub mod spectrum;
+pub mod spline;
 pub mod strobe;
+pub mod timeline;
 
 /// Enum denoting different end-states that an [`Animation`] object may return.
-///
-/// The `ErrRetry` state is given for use in statistical tracking and more
-/// complex operations that could fail, but still be able to continue (e.g. file
-/// I/O).
 pub enum AnimationState {
-    /// Denotes that the operation was successful.
-    Ok,
-    /// Denotes that an error occurred but the object can retry the operation.
-    ErrRetry,
-    /// Denotes that an error occurred that is not recoverable for this frame, but will not be fatal for following frames.
-    ErrSkip,
-    /// Denotes that an error occurred and cannot be recovered from.
-    ErrFatal,
+    /// Denotes that there's more to animate, continue rendering frames.
+    Continue,
+    /// Denotes that this was the last frame the animation will render.
+    Last,
 }
 
 /// Trait for types that implement types that animates the pixels of a frame.
@@ -46,8 +63,20 @@ pub trait Animation: std::fmt::Debug {
     /// Renders the frame with the next frame of the animation given the input `dt`.
     fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState;
 
+    /// Returns the amount of time left before the animation finishes.
+    fn time_remaining(&self) -> Duration;
+
     /// Resets the animation to its pre-run state, operating as if it were never run before.
     fn reset(self: Box<Self>) -> Box<dyn Animation>;
+
+    /// Reports a quality-of-service signal: `proportion` is how long the most
+    /// recent frame actually took versus its target duration, so `1.0` means
+    /// right on schedule and `2.0` means the frame took twice as long as
+    /// budgeted. Implementations that can shed work under load (e.g. coarsen
+    /// a step size, skip an expensive sub-pass) should do so here.
+    ///
+    /// The default implementation ignores the signal.
+    fn qos(&mut self, _proportion: f64) {}
 }
 
 /// Trait for building animation types.
@@ -55,11 +84,34 @@ pub trait Animation: std::fmt::Debug {
 pub trait AnimationBuilder: std::fmt::Debug {
     /// Creates a new animation object from the builder.
     fn build(self: Box<Self>) -> Box<dyn Animation>;
+
+    /// Checks this builder's fields for configurations that would panic or
+    /// produce nonsensical output at [`build`](Self::build), repairing
+    /// whatever it safely can and reporting one [`Diagnostic`] per issue
+    /// found.
+    ///
+    /// The default implementation has nothing to check and returns no diagnostics.
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Runs [`AnimationBuilder::validate`] on `builder`, returning it back along
+/// with whatever diagnostics were found.
+///
+/// This is the entry point tools should use to either report or auto-repair
+/// a configuration before calling [`AnimationBuilder::build`].
+pub fn lint_and_fix(
+    mut builder: Box<dyn AnimationBuilder>,
+) -> (Box<dyn AnimationBuilder>, Vec<Diagnostic>) {
+    let diagnostics = builder.validate();
+
+    (builder, diagnostics)
 }
 
 #[cfg(test)]
 mod builder_test {
-    use crate::{AnimationBuilder, Cycle};
+    use crate::{lint_and_fix, AnimationBuilder, ColorOrder, Cycle};
 
     #[test]
     fn test_serialize() {
@@ -81,4 +133,17 @@ mod builder_test {
             input
         );
     }
+
+    #[test]
+    fn test_lint_and_fix_reports_and_repairs() {
+        let builder: Box<dyn AnimationBuilder> =
+            Cycle::builder().order(ColorOrder::Ordered(Vec::new()));
+
+        let (builder, diagnostics) = lint_and_fix(builder);
+
+        assert_eq!(diagnostics.len(), 1);
+
+        // The repaired builder is usable: it no longer indexes an empty `Ordered` list.
+        let _ = builder.build();
+    }
 }
@@ -0,0 +1,151 @@
+//! # Palette
+//!
+//! Named color schemes loaded from a TOML or JSON config file -- a list of
+//! hex-string color stops per name, e.g. `solarized_dark = ["0x002b36",
+//! "0x073642", "0xd54e53"]` -- so an animation can be retargeted to a
+//! different theme without touching code, the way a terminal emulator loads
+//! its color scheme from a file.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use serde::{de, Deserialize, Deserializer};
+
+use ranos_ds::rgb::{RGBOrder, RGB};
+
+/// A single named color scheme: an ordered list of color stops an animation
+/// interpolates along.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    stops: Vec<RGB>,
+}
+
+impl Palette {
+    /// Returns the number of stops in this palette.
+    pub fn len(&self) -> usize {
+        self.stops.len()
+    }
+
+    /// Returns `true` if this palette has no stops.
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// Returns the color at normalized position `t`, linearly interpolating
+    /// between the two nearest stops. `t` outside `[0, 1]` wraps around, so a
+    /// caller sweeping a continuously advancing position doesn't need to
+    /// clamp or modulo it first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this palette has no stops.
+    pub fn at(&self, t: f32) -> RGB {
+        assert!(!self.stops.is_empty(), "Palette has no stops to sample");
+
+        if self.stops.len() == 1 {
+            return self.stops[0];
+        }
+
+        let t = t.rem_euclid(1.0);
+        let scaled = t * self.stops.len() as f32;
+        let ind = scaled.floor() as usize % self.stops.len();
+        let next = (ind + 1) % self.stops.len();
+        let frac = scaled.fract();
+
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * frac) as u8;
+
+        RGB::from_tuple(
+            (
+                lerp(self.stops[ind].red(), self.stops[next].red()),
+                lerp(self.stops[ind].green(), self.stops[next].green()),
+                lerp(self.stops[ind].blue(), self.stops[next].blue()),
+            ),
+            RGBOrder::RGB,
+        )
+    }
+}
+
+/// Errors from loading or looking up a [`Palette`].
+#[derive(Debug)]
+pub enum PaletteError {
+    /// The palette file could not be read from disk.
+    Io(io::Error),
+    /// The palette file's extension wasn't recognized as `toml` or `json`.
+    UnknownFormat,
+    /// The contents of the palette file could not be deserialized.
+    Parse(String),
+    /// The requested palette name wasn't present in the file.
+    UnknownPalette(String),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Io(e) => write!(f, "failed to read palette file: {}", e),
+            PaletteError::UnknownFormat => {
+                write!(f, "palette file must have a `.toml` or `.json` extension")
+            }
+            PaletteError::Parse(e) => write!(f, "failed to parse palette file: {}", e),
+            PaletteError::UnknownPalette(name) => {
+                write!(f, "no palette named {:?} in file", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl From<io::Error> for PaletteError {
+    fn from(e: io::Error) -> Self {
+        PaletteError::Io(e)
+    }
+}
+
+/// A single color stop, parsed from a hex string like `"0xd54e53"` (the
+/// leading `0x` is optional).
+struct HexColor(RGB);
+
+impl<'de> Deserialize<'de> for HexColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let digits = s.strip_prefix("0x").unwrap_or(&s);
+
+        u32::from_str_radix(digits, 16)
+            .map(|code| HexColor(RGB::from_code(code, RGBOrder::RGB)))
+            .map_err(|e| de::Error::custom(format!("invalid hex color {:?}: {}", s, e)))
+    }
+}
+
+/// Top-level shape of a palette config file: a map of palette name to its
+/// ordered list of hex-string color stops.
+#[derive(Deserialize)]
+struct PaletteFile(HashMap<String, Vec<HexColor>>);
+
+/// Loads the palette named `name` from the TOML or JSON file at `path`, the
+/// format being chosen by the file's extension.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, its extension is not `toml`
+/// or `json`, its contents cannot be deserialized, or it has no palette
+/// named `name`.
+pub fn load_palette<P: AsRef<Path>>(path: P, name: &str) -> Result<Palette, PaletteError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    let mut file: PaletteFile = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|e| PaletteError::Parse(e.to_string()))?,
+        Some("json") => {
+            serde_json::from_str(&contents).map_err(|e| PaletteError::Parse(e.to_string()))?
+        }
+        _ => return Err(PaletteError::UnknownFormat),
+    };
+
+    let stops = file
+        .0
+        .remove(name)
+        .ok_or_else(|| PaletteError::UnknownPalette(name.to_owned()))?;
+
+    Ok(Palette {
+        stops: stops.into_iter().map(|c| c.0).collect(),
+    })
+}
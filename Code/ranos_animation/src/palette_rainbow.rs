@@ -0,0 +1,241 @@
+//! # PaletteRainbow
+
+use std::{path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{collections::frame::Frame, const_val::ConstVal};
+
+use crate::palette::{load_palette, Palette};
+
+use super::*;
+
+/// Builder for the [`PaletteRainbow`](PaletteRainbow) animation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "PaletteRainbow")]
+pub struct PaletteRainbowBuilder {
+    runtime: Duration,
+    rainbow_length: Duration,
+    arc: f32,
+    step: usize,
+    palette_path: PathBuf,
+    palette_name: String,
+}
+
+impl PaletteRainbowBuilder {
+    /// Sets the length of time the animation should run for.
+    pub fn runtime(mut self: Box<Self>, runtime: Duration) -> Box<Self> {
+        self.runtime = runtime;
+
+        self
+    }
+
+    /// Sets the length of time it takes for the palette to fully cycle through all the LEDs.
+    pub fn rainbow_length(mut self: Box<Self>, rainbow_length: Duration) -> Box<Self> {
+        self.rainbow_length = rainbow_length;
+
+        self
+    }
+
+    /// Sets the amount of the palette's gradient (mapped to the range \[0, 1\]
+    /// for this parameter) that is displayed across all the LEDs.
+    ///
+    /// NOTE: The arc can be larger than 1, and can be as large as you like. For
+    /// example an arc value of 2 would mean that there are 2 full gradients
+    /// visible across the LEDs.
+    pub fn arc(mut self: Box<Self>, arc: f32) -> Box<Self> {
+        self.arc = arc;
+
+        self
+    }
+
+    /// Sets the number of LEDs in a row that keep the same color before moving
+    /// on to the next color. E.g if a step of 1 yields an LED array of \[1, 2,
+    /// 3, 4\], then a step of 2 yields an array of \[1, 1, 2, 2\].
+    pub fn step(mut self: Box<Self>, step: usize) -> Box<Self> {
+        self.step = step;
+
+        self
+    }
+
+    /// Sets the path to the TOML/JSON file the named palette is loaded from.
+    pub fn palette_path(mut self: Box<Self>, palette_path: PathBuf) -> Box<Self> {
+        self.palette_path = palette_path;
+
+        self
+    }
+
+    /// Sets the name of the palette to load from [`Self::palette_path`].
+    pub fn palette_name(mut self: Box<Self>, palette_name: String) -> Box<Self> {
+        self.palette_name = palette_name;
+
+        self
+    }
+
+    /// Constructs a [`PaletteRainbow`](PaletteRainbow) object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette at `palette_path` cannot be loaded, or has no
+    /// palette named `palette_name`.
+    pub fn build(self: Box<Self>) -> PaletteRainbow {
+        PaletteRainbow::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for PaletteRainbowBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::PaletteRainbowBuilder;
+    use crate::PaletteRainbow;
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn test_serialize() {
+        let builder = PaletteRainbow::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(runtime:(secs:16,nanos:0),rainbow_length:(secs:2,nanos:0),arc:1,step:1,palette_path:"",palette_name:"")"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(runtime:(secs:16,nanos:0),rainbow_length:(secs:2,nanos:0),arc:1,step:1,palette_path:"palettes.toml",palette_name:"solarized_dark")"#;
+
+        let data: PaletteRainbowBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.runtime, Duration::from_secs(16));
+        assert_eq!(data.rainbow_length, Duration::from_secs(2));
+        assert_eq!(data.arc, 1.0);
+        assert_eq!(data.step, 1);
+        assert_eq!(data.palette_path, PathBuf::from("palettes.toml"));
+        assert_eq!(data.palette_name, "solarized_dark");
+    }
+}
+
+/// Variant of [`Rainbow`](crate::Rainbow) that sweeps a position along a
+/// [`Palette`] loaded from file instead of the full 0-360° HSV hue wheel --
+/// the same `hue`/`step`/`arc` position computation, but the final color
+/// lookup samples the palette's gradient rather than calling
+/// [`RGB::from_hsv`].
+#[derive(Debug)]
+pub struct PaletteRainbow {
+    runtime: ConstVal<Duration>,
+    time_remaining: Duration,
+
+    hue: f32,
+    dh: ConstVal<f32>,
+
+    arc: ConstVal<f32>,
+    step: ConstVal<usize>,
+
+    palette: Palette,
+}
+
+impl PaletteRainbow {
+    /// Constructs a builder object with safe default values.
+    ///
+    /// Note: the default `palette_path`/`palette_name` are both empty, and
+    /// must be set before calling [`PaletteRainbowBuilder::build`].
+    pub fn builder() -> Box<PaletteRainbowBuilder> {
+        Box::new(PaletteRainbowBuilder {
+            runtime: Duration::from_secs(16),
+            rainbow_length: Duration::from_secs(2),
+            arc: 1.0,
+            step: 1,
+            palette_path: PathBuf::new(),
+            palette_name: String::new(),
+        })
+    }
+
+    fn from_builder(builder: Box<PaletteRainbowBuilder>) -> Self {
+        let palette = load_palette(&builder.palette_path, &builder.palette_name)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "failed to load palette {:?} from {:?}: {}",
+                    builder.palette_name, builder.palette_path, e
+                )
+            });
+
+        Self::new(
+            builder.runtime,
+            builder.rainbow_length,
+            builder.arc,
+            builder.step,
+            palette,
+        )
+    }
+
+    fn new(
+        runtime: Duration,
+        rainbow_length: Duration,
+        arc: f32,
+        step: usize,
+        palette: Palette,
+    ) -> Self {
+        Self {
+            runtime: ConstVal::new(runtime),
+            time_remaining: runtime,
+
+            hue: 0.0,
+            dh: ConstVal::new(360.0 / rainbow_length.as_secs_f32()),
+
+            arc: ConstVal::new(arc),
+            step: ConstVal::new(step),
+
+            palette,
+        }
+    }
+}
+
+impl Animation for PaletteRainbow {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        self.hue += self.dh.get() * dt.as_secs_f32();
+
+        if self.hue >= 360.0 {
+            self.hue -= 360.0;
+        }
+
+        let len = frame.len() as f32;
+        for (i, led) in frame.iter_mut().enumerate() {
+            let step = i as f32 / *self.step.get() as f32;
+            let step = step.floor();
+            let step = step * (*self.step.get() as f32);
+            let step = step / len;
+            let step = step * 360.0 * self.arc.get();
+            let position = (self.hue + step) / 360.0;
+            *led = self.palette.at(position);
+        }
+
+        let mut res = AnimationState::Continue;
+
+        self.time_remaining = if let Some(d) = self.time_remaining.checked_sub(dt) {
+            d
+        } else {
+            res = AnimationState::Last;
+
+            Duration::new(0, 0)
+        };
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.time_remaining = *self.runtime.get();
+        self.hue = 0.0;
+
+        self
+    }
+}
@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use ranos_core::Diagnostic;
 use ranos_ds::{collections::frame::Frame, const_val::ConstVal, rgb::RGB};
 
 use super::*;
@@ -83,6 +84,38 @@ impl AnimationBuilder for RainbowBuilder {
     fn build(self: Box<Self>) -> Box<dyn Animation> {
         Box::new(self.build())
     }
+
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let sat = self.sat.min(1.0).max(0.0);
+        if sat != self.sat {
+            diagnostics.push(Diagnostic::warning(format!(
+                "saturation was {}, outside the range [0, 1]; clamped to {}",
+                self.sat, sat
+            )));
+            self.sat = sat;
+        }
+
+        let val = self.val.min(1.0).max(0.0);
+        if val != self.val {
+            diagnostics.push(Diagnostic::warning(format!(
+                "value was {}, outside the range [0, 1]; clamped to {}",
+                self.val, val
+            )));
+            self.val = val;
+        }
+
+        if self.rainbow_length.is_zero() {
+            self.rainbow_length = Duration::from_millis(1);
+
+            diagnostics.push(Diagnostic::warning(
+                "rainbow_length was zero, which would make the hue advance infinitely fast; bumped to 1ms",
+            ));
+        }
+
+        diagnostics
+    }
 }
 
 #[cfg(test)]
@@ -114,6 +147,35 @@ mod builder_test {
         assert_eq!(data.arc, 1.0);
         assert_eq!(data.step, 1);
     }
+
+    #[test]
+    fn test_validate_fixes_out_of_range_sat_and_val() {
+        use crate::AnimationBuilder;
+
+        // Bypasses the clamps in `RainbowBuilder::saturation`/`value` to
+        // simulate a config deserialized with out-of-range values.
+        let mut builder = RainbowBuilder {
+            sat: 1.5,
+            val: -0.5,
+            ..*Rainbow::builder()
+        };
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(builder.sat, 1.0);
+        assert_eq!(builder.val, 0.0);
+    }
+
+    #[test]
+    fn test_validate_fixes_zero_rainbow_length() {
+        use crate::AnimationBuilder;
+
+        let mut builder = Rainbow::builder().rainbow_length(Duration::new(0, 0));
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!builder.rainbow_length.is_zero());
+    }
 }
 
 /// Struct for animating the classic RGB rainbow puke that we all know and love
@@ -131,6 +193,10 @@ pub struct Rainbow {
 
     arc: ConstVal<f32>,
     step: ConstVal<usize>,
+    /// The step size actually used by [`Self::render_frame`]: equal to
+    /// `step` normally, coarsened by [`Self::qos`] while the render loop
+    /// reports it's running behind, to cut down on distinct HSV conversions.
+    qos_step: usize,
 }
 
 impl Rainbow {
@@ -176,6 +242,7 @@ impl Rainbow {
 
             arc: ConstVal::new(arc),
             step: ConstVal::new(step),
+            qos_step: step,
         }
     }
 }
@@ -190,9 +257,9 @@ impl Animation for Rainbow {
 
         let len = frame.len() as f32;
         for (i, led) in frame.iter_mut().enumerate() {
-            let step = i as f32 / *self.step.get() as f32;
+            let step = i as f32 / self.qos_step as f32;
             let step = step.floor();
-            let step = step * (*self.step.get() as f32);
+            let step = step * (self.qos_step as f32);
             let step = step / len;
             let step = step * 360.0 * self.arc.get();
             *led = RGB::from_hsv(self.hue + step, *self.sat.get(), *self.val.get());
@@ -218,7 +285,16 @@ impl Animation for Rainbow {
     fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
         self.time_remaining = *self.runtime.get();
         self.hue = 0.0;
+        self.qos_step = *self.step.get();
 
         self
     }
+
+    fn qos(&mut self, proportion: f64) {
+        self.qos_step = if proportion > 1.0 {
+            *self.step.get() * 2
+        } else {
+            *self.step.get()
+        };
+    }
 }
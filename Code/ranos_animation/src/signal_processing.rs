@@ -0,0 +1,149 @@
+//! Frequency-domain signal analysis shared by audio-reactive animations.
+//!
+//! [`SignalProcessing`] buffers a window of PCM samples, runs an FFT on each
+//! [`SignalProcessing::feed`], and exposes the result either as smoothed
+//! energy per logarithmically-spaced frequency band (as [`Spectrum`](crate::Spectrum)
+//! uses it) or as raw energy over an arbitrary Hz range via
+//! [`SignalProcessing::energy_in_band`] -- so an animation that wants a
+//! fixed bass/mid/treble split doesn't need to re-derive its own FFT plumbing.
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+use ranos_audio::SIZE;
+
+/// The rate at which per-band energy is smoothed across frames, `s = alpha *
+/// new + (1 - alpha) * prev`. Chosen to tame flicker without feeling laggy.
+const SMOOTHING_ALPHA: f32 = 0.3;
+
+/// Buffers a window of PCM samples, runs an FFT on each [`Self::feed`], and
+/// exposes the result as smoothed, normalized `[0, 1]` energy, either per
+/// band (see [`Self::band_level`]) or over an arbitrary Hz range (see
+/// [`Self::energy_in_band`]).
+#[derive(Debug)]
+pub struct SignalProcessing {
+    sample_rate: f32,
+
+    hann_window: Box<[f32; SIZE]>,
+    fft: Arc<dyn Fft<f32>>,
+    spectrum: Box<[Complex<f32>; SIZE]>,
+    scratch: Box<[Complex<f32>; SIZE]>,
+
+    band_edges: Vec<usize>,
+    band_levels: Vec<f32>,
+}
+
+impl SignalProcessing {
+    /// Constructs a [`SignalProcessing`] analyzing audio sampled at
+    /// `sample_rate`, grouped into `num_bands` logarithmically-spaced bands
+    /// spanning ~20 Hz to Nyquist.
+    pub fn new(num_bands: usize, sample_rate: f32) -> Self {
+        let num_bands = num_bands.max(1);
+
+        let mut hann_window = [0.0_f32; SIZE];
+        for (n, w) in hann_window.iter_mut().enumerate() {
+            *w = 0.5 - 0.5 * (2.0 * PI * n as f32 / (SIZE as f32 - 1.0)).cos();
+        }
+
+        Self {
+            sample_rate,
+
+            hann_window: Box::new(hann_window),
+            fft: FftPlanner::new().plan_fft_forward(SIZE),
+            spectrum: Box::new([Complex::new(0.0, 0.0); SIZE]),
+            scratch: Box::new([Complex::new(0.0, 0.0); SIZE]),
+
+            band_edges: log_band_edges(num_bands, sample_rate),
+            band_levels: vec![0.0; num_bands],
+        }
+    }
+
+    /// Windows, transforms, and re-buckets `samples` into this analyzer's
+    /// smoothed per-band levels. `samples` should be the most recent (up to)
+    /// [`SIZE`] PCM samples, oldest first; shorter windows simply leave the
+    /// unfilled tail of the FFT input at its previous contents.
+    pub fn feed(&mut self, samples: &[f32]) {
+        for (s, (sample, window)) in self
+            .spectrum
+            .iter_mut()
+            .zip(samples.iter().zip(self.hann_window.iter()))
+        {
+            *s = Complex::new(sample * window, 0.0);
+        }
+
+        self.fft
+            .process_with_scratch(&mut *self.spectrum, &mut *self.scratch);
+
+        for band in 0..self.band_levels.len() {
+            let (begin, end) = (self.band_edges[band], self.band_edges[band + 1]);
+            let new_level = self.bin_range_energy(begin.max(1), end);
+
+            self.band_levels[band] =
+                SMOOTHING_ALPHA * new_level + (1.0 - SMOOTHING_ALPHA) * self.band_levels[band];
+        }
+    }
+
+    /// Returns the smoothed, normalized `[0, 1]` energy level of `band`.
+    pub fn band_level(&self, band: usize) -> f32 {
+        self.band_levels[band]
+    }
+
+    /// Returns the number of frequency bands this analyzer tracks.
+    pub fn num_bands(&self) -> usize {
+        self.band_levels.len()
+    }
+
+    /// Returns the normalized `[0, 1]` energy of the most recently [`fed`](Self::feed)
+    /// window across an arbitrary `[low_hz, high_hz)` range, independent of
+    /// the band edges used by [`Self::band_level`] -- e.g. for a fixed
+    /// bass/mid/treble split regardless of `num_bands`. This value is not smoothed.
+    pub fn energy_in_band(&self, low_hz: f32, high_hz: f32) -> f32 {
+        let nyquist = self.sample_rate / 2.0;
+        let hz_to_bin = |hz: f32| ((hz / nyquist) * (SIZE as f32 / 2.0)).round() as usize;
+
+        let begin = hz_to_bin(low_hz).max(1).min(SIZE / 2);
+        let end = hz_to_bin(high_hz).max(begin).min(SIZE / 2);
+
+        self.bin_range_energy(begin, end)
+    }
+
+    /// Average FFT-bin magnitude over `[begin, end)`, normalized by window
+    /// size and clamped to `[0, 1]`.
+    fn bin_range_energy(&self, begin: usize, end: usize) -> f32 {
+        let mut energy = 0.0;
+        for bin in begin..end {
+            energy += self.spectrum[bin].norm() / (SIZE as f32);
+        }
+
+        (energy / end.saturating_sub(begin).max(1) as f32).min(1.0)
+    }
+
+    /// Clears all smoothed band levels back to zero, as if never fed any samples.
+    pub fn reset(&mut self) {
+        for level in self.band_levels.iter_mut() {
+            *level = 0.0;
+        }
+    }
+}
+
+/// Computes `num_bands + 1` FFT bin-index edges, logarithmically spaced from
+/// ~20 Hz to Nyquist (`sample_rate / 2`), across the first `SIZE / 2` bins.
+fn log_band_edges(num_bands: usize, sample_rate: f32) -> Vec<usize> {
+    let nyquist = sample_rate / 2.0;
+    let min_freq = 20.0_f32.min(nyquist);
+
+    let log_min = min_freq.ln();
+    let log_max = nyquist.ln();
+
+    (0..=num_bands)
+        .map(|i| {
+            let t = i as f32 / num_bands as f32;
+            let freq = (log_min + t * (log_max - log_min)).exp();
+            let bin = (freq / nyquist * (SIZE as f32 / 2.0)).round() as usize;
+
+            bin.min(SIZE / 2)
+        })
+        .collect()
+}
@@ -0,0 +1,194 @@
+//! # Spectrum
+//!
+//! An animation that turns a live audio signal into a color frame by way of an FFT.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::player::Player;
+use ranos_ds::{collections::frame::Frame, const_val::ConstVal, rgb::RGB};
+
+use crate::signal_processing::SignalProcessing;
+
+use super::*;
+
+/// Builder for the [`Spectrum`] animation.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Spectrum")]
+pub struct SpectrumBuilder {
+    runtime: Duration,
+    num_bands: usize,
+    #[serde(skip)]
+    player: Option<Arc<Mutex<Player>>>,
+}
+
+impl std::fmt::Debug for SpectrumBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpectrumBuilder")
+            .field("runtime", &self.runtime)
+            .field("num_bands", &self.num_bands)
+            .finish()
+    }
+}
+
+impl SpectrumBuilder {
+    /// Sets the length of time the animation should run for.
+    pub fn runtime(mut self: Box<Self>, runtime: Duration) -> Box<Self> {
+        self.runtime = runtime;
+
+        self
+    }
+
+    /// Sets the number of logarithmically-spaced frequency bands to group the spectrum into.
+    pub fn num_bands(mut self: Box<Self>, num_bands: usize) -> Box<Self> {
+        self.num_bands = num_bands.max(1);
+
+        self
+    }
+
+    /// Sets the shared [`Player`] this animation should pull fresh samples from each frame.
+    pub fn player(mut self: Box<Self>, player: Arc<Mutex<Player>>) -> Box<Self> {
+        self.player = Some(player);
+
+        self
+    }
+
+    /// Constructs a [`Spectrum`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`Player`] was supplied via [`Self::player`].
+    pub fn build(self: Box<Self>) -> Spectrum {
+        Spectrum::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for SpectrumBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+/// Struct for an animation that maps a live frequency spectrum onto the LED
+/// strip.
+///
+/// Each frame, the most recent samples are fed into a [`SignalProcessing`]
+/// analyzer, which windows and transforms them with an FFT and groups the
+/// magnitude spectrum into logarithmically-spaced bands spanning ~20 Hz to
+/// Nyquist. Band energy is smoothed across frames to avoid flicker, and the
+/// resulting levels drive a hue sweep across the strip, where each band's
+/// level sets the `value` of its slice of LEDs.
+///
+/// Samples are pulled each frame from a shared [`Player`], which is required
+/// to build a [`Spectrum`]. [`Self::feed_samples`] feeds extra windows (e.g.
+/// from a live audio capture source) into the same analyzer; it does not
+/// replace the `Player`'s samples, which are still fed on every frame.
+#[derive(Debug)]
+pub struct Spectrum {
+    runtime: ConstVal<Duration>,
+    time_remaining: Duration,
+
+    player: Arc<Mutex<Player>>,
+    dsp: SignalProcessing,
+
+    num_bands: ConstVal<usize>,
+}
+
+impl Spectrum {
+    /// Constructs a builder object with safe default values. A [`Player`]
+    /// must still be supplied via [`SpectrumBuilder::player`] before building.
+    pub fn builder() -> Box<SpectrumBuilder> {
+        Box::new(SpectrumBuilder {
+            runtime: Duration::from_secs(16),
+            num_bands: 32,
+            player: None,
+        })
+    }
+
+    fn from_builder(builder: Box<SpectrumBuilder>) -> Self {
+        Self::new(
+            builder.runtime,
+            builder.num_bands,
+            builder
+                .player
+                .expect("Spectrum animation requires a Player, set via SpectrumBuilder::player"),
+        )
+    }
+
+    fn new(runtime: Duration, num_bands: usize, player: Arc<Mutex<Player>>) -> Self {
+        let sample_rate = player.lock().unwrap().sample_rate();
+
+        Self {
+            runtime: ConstVal::new(runtime),
+            time_remaining: runtime,
+
+            player,
+            dsp: SignalProcessing::new(num_bands, sample_rate),
+
+            num_bands: ConstVal::new(num_bands),
+        }
+    }
+
+    /// Feeds a fresh window of PCM samples into this animation's analyzer
+    /// directly. The shared [`Player`]'s most recent samples are still fed
+    /// on every [`Animation::render_frame`], and the band levels are
+    /// smoothed across both sources.
+    pub fn feed_samples(&mut self, samples: &[f32]) {
+        self.dsp.feed(samples);
+    }
+
+    /// Copies the player's most recent samples and feeds them into the analyzer.
+    fn update_bands(&mut self) {
+        let samples: Vec<f32> = {
+            let player = self.player.lock().unwrap();
+            player.most_recent_data().to_vec()
+        };
+
+        self.dsp.feed(&samples);
+    }
+}
+
+impl Animation for Spectrum {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        self.update_bands();
+
+        let num_bands = *self.num_bands.get();
+        let len = frame.len();
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let band = (i * num_bands / len.max(1)).min(num_bands - 1);
+            let level = self.dsp.band_level(band);
+            let hue = band as f32 / num_bands as f32 * 360.0;
+
+            *led = RGB::from_hsv(hue, 1.0, level);
+        }
+
+        let mut res = AnimationState::Continue;
+
+        self.time_remaining = if let Some(d) = self.time_remaining.checked_sub(dt) {
+            d
+        } else {
+            res = AnimationState::Last;
+
+            Duration::new(0, 0)
+        };
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.time_remaining = *self.runtime.get();
+        self.dsp.reset();
+
+        self
+    }
+}
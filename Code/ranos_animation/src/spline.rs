@@ -0,0 +1,319 @@
+//! # Spline
+//!
+//! An animation that smoothly interpolates the whole strip's color through
+//! an ordered list of keyframes, rather than hard-switching like [`Strobe`](crate::Strobe).
+
+use std::{f32::consts::PI, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// How [`Spline`] interpolates between two consecutive keyframes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InterpMode {
+    /// Straight linear interpolation.
+    Linear,
+    /// Half-cosine easing: `h = (1 - cos(pi * t)) / 2`, giving a gentle
+    /// ease-in/ease-out instead of a constant rate of change.
+    Cosine,
+    /// Catmull-Rom cubic interpolation, using the neighboring keyframes on
+    /// either side of a segment to curve smoothly through every keyframe
+    /// rather than just toward it.
+    CatmullRom,
+}
+
+/// Builder for the [`Spline`] animation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Spline")]
+pub struct SplineBuilder {
+    keyframes: Vec<(Duration, RGB)>,
+    mode: InterpMode,
+    looping: bool,
+}
+
+impl SplineBuilder {
+    /// Appends a keyframe to the end of the timeline: `duration` is how long
+    /// the segment leaving this keyframe (towards the next one) takes, and
+    /// `color` is the color this keyframe holds.
+    pub fn keyframe(mut self: Box<Self>, duration: Duration, color: RGB) -> Box<Self> {
+        self.keyframes.push((duration, color));
+
+        self
+    }
+
+    /// Sets the interpolation mode used between keyframes.
+    pub fn mode(mut self: Box<Self>, mode: InterpMode) -> Box<Self> {
+        self.mode = mode;
+
+        self
+    }
+
+    /// Sets whether the keyframe list wraps back to its start once it
+    /// finishes, instead of ending -- also closes the last keyframe's
+    /// segment back to the first rather than leaving it dangling, and gives
+    /// [`InterpMode::CatmullRom`] real neighbors all the way around instead
+    /// of duplicating the boundary keyframes.
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.looping = looping;
+
+        self
+    }
+
+    /// Constructs a [`Spline`] object.
+    pub fn build(self: Box<Self>) -> Spline {
+        Spline::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for SplineBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use std::time::Duration;
+
+    use ranos_ds::rgb::{RGBOrder, RGB};
+
+    use super::InterpMode;
+    use crate::Spline;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Spline::builder()
+            .keyframe(Duration::from_secs(2), RGB::from_code(0xFF0000, RGBOrder::RGB))
+            .keyframe(Duration::from_secs(2), RGB::from_code(0x0000FF, RGBOrder::RGB));
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let expected = r#"(keyframes:[((secs:2,nanos:0),(255,0,0)),((secs:2,nanos:0),(0,0,255))],mode:CatmullRom,looping:false)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(keyframes:[((secs:2,nanos:0),(255,0,0)),((secs:2,nanos:0),(0,0,255))],mode:Linear,looping:true)"#;
+
+        let data: super::SplineBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.keyframes.len(), 2);
+        assert_eq!(data.mode, InterpMode::Linear);
+        assert_eq!(data.looping, true);
+    }
+}
+
+/// Struct for an animation that smoothly interpolates the whole strip's
+/// color through an ordered list of `(Duration, RGB)` keyframes.
+///
+/// Unlike [`Keyframe`](crate::Keyframe), which addresses individual LED
+/// ranges/tags and only ever linearly interpolates, [`Spline`] drives the
+/// entire frame with one of three curves -- [`InterpMode::Linear`],
+/// [`InterpMode::Cosine`], or [`InterpMode::CatmullRom`] -- selected up front.
+///
+/// With [`InterpMode::CatmullRom`], each segment's tangents are derived from
+/// its neighboring keyframes, so the color curves smoothly through every
+/// keyframe instead of just easing toward it; the keyframes at either end of
+/// a non-looping timeline use a duplicated boundary keyframe as their
+/// missing neighbor.
+#[derive(Debug)]
+pub struct Spline {
+    keyframes: ConstVal<Vec<(Duration, RGB)>>,
+    mode: ConstVal<InterpMode>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+
+    time_remaining: Duration,
+}
+
+impl Spline {
+    /// Constructs a builder object with no keyframes, Catmull-Rom interpolation, and no looping by default.
+    pub fn builder() -> Box<SplineBuilder> {
+        Box::new(SplineBuilder {
+            keyframes: Vec::new(),
+            mode: InterpMode::CatmullRom,
+            looping: false,
+        })
+    }
+
+    fn from_builder(builder: Box<SplineBuilder>) -> Self {
+        Self::new(builder.keyframes, builder.mode, builder.looping)
+    }
+
+    fn new(keyframes: Vec<(Duration, RGB)>, mode: InterpMode, looping: bool) -> Self {
+        let time_remaining = total_runtime(&keyframes, looping);
+
+        Self {
+            keyframes: ConstVal::new(keyframes),
+            mode: ConstVal::new(mode),
+            looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+
+            time_remaining,
+        }
+    }
+
+    /// The number of interpolated segments in the timeline: one fewer than
+    /// the keyframe count, or equal to it if [`SplineBuilder::looping`] closes the loop.
+    fn num_segments(&self) -> usize {
+        num_segments(self.keyframes.get().len(), self.looping)
+    }
+
+    /// Resolves keyframe point `idx` (which may run negative or past the end
+    /// of the list), wrapping it if [`SplineBuilder::looping`] is set, or
+    /// clamping it to the nearest boundary keyframe (i.e. duplicating it) otherwise.
+    fn point(&self, idx: isize) -> RGB {
+        let keyframes = self.keyframes.get();
+        let n = keyframes.len() as isize;
+
+        let idx = if self.looping {
+            idx.rem_euclid(n)
+        } else {
+            idx.clamp(0, n - 1)
+        };
+
+        keyframes[idx as usize].1
+    }
+}
+
+/// The number of interpolated segments for `num_keyframes` keyframes: one
+/// fewer than the keyframe count, or equal to it if `looping` closes the loop.
+fn num_segments(num_keyframes: usize, looping: bool) -> usize {
+    if num_keyframes < 2 {
+        0
+    } else if looping {
+        num_keyframes
+    } else {
+        num_keyframes - 1
+    }
+}
+
+/// Sums the durations of every keyframe whose segment is actually traversed.
+fn total_runtime(keyframes: &[(Duration, RGB)], looping: bool) -> Duration {
+    keyframes
+        .iter()
+        .take(num_segments(keyframes.len(), looping))
+        .map(|(d, _)| *d)
+        .sum()
+}
+
+/// Linearly interpolates between two colors by `t`, clamped to `[0, 1]`.
+fn lerp(from: RGB, to: RGB, t: f32) -> RGB {
+    let t = t.min(1.0).max(0.0);
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+    RGB::from_tuple(
+        (
+            lerp(from.red(), to.red()),
+            lerp(from.green(), to.green()),
+            lerp(from.blue(), to.blue()),
+        ),
+        RGBOrder::RGB,
+    )
+}
+
+/// Evaluates the Catmull-Rom cubic Hermite curve between `p0` and `p1` at
+/// local parameter `t`, using `p_prev`/`p_next` to derive each endpoint's
+/// tangent, clamping each channel back into `[0, 255]`.
+fn catmull_rom(p_prev: RGB, p0: RGB, p1: RGB, p_next: RGB, t: f32) -> RGB {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t;
+
+    let channel = |prev: u8, a: u8, b: u8, next: u8| -> u8 {
+        let (prev, a, b, next) = (prev as f32, a as f32, b as f32, next as f32);
+
+        let m0 = (b - prev) / 2.0;
+        let m1 = (next - a) / 2.0;
+
+        let out = h00 * a + h10 * m0 + h01 * b + h11 * m1;
+
+        out.max(0.0).min(255.0) as u8
+    };
+
+    RGB::from_tuple(
+        (
+            channel(p_prev.red(), p0.red(), p1.red(), p_next.red()),
+            channel(p_prev.green(), p0.green(), p1.green(), p_next.green()),
+            channel(p_prev.blue(), p0.blue(), p1.blue(), p_next.blue()),
+        ),
+        RGBOrder::RGB,
+    )
+}
+
+impl Animation for Spline {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        let segments = self.num_segments();
+        if segments == 0 {
+            return AnimationState::Last;
+        }
+
+        self.elapsed += dt;
+
+        let duration = self.keyframes.get()[self.ind].0;
+        let t = (self.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0);
+
+        let i = self.ind as isize;
+        let color = match *self.mode.get() {
+            InterpMode::Linear => lerp(self.point(i), self.point(i + 1), t),
+            InterpMode::Cosine => {
+                let h = (1.0 - (PI * t).cos()) / 2.0;
+                lerp(self.point(i), self.point(i + 1), h)
+            }
+            InterpMode::CatmullRom => {
+                catmull_rom(self.point(i - 1), self.point(i), self.point(i + 1), self.point(i + 2), t)
+            }
+        };
+
+        for led in frame.iter_mut() {
+            *led = color;
+        }
+
+        let mut res = AnimationState::Continue;
+
+        if self.elapsed >= duration {
+            self.elapsed = Duration::new(0, 0);
+            self.ind += 1;
+
+            if self.ind >= segments {
+                if self.looping {
+                    self.ind = 0;
+                } else {
+                    res = AnimationState::Last;
+                }
+            }
+        }
+
+        self.time_remaining = self.time_remaining.checked_sub(dt).unwrap_or_default();
+
+        res
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.time_remaining
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+        self.time_remaining = total_runtime(self.keyframes.get(), self.looping);
+
+        self
+    }
+}
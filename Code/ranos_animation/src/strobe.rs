@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
+use ranos_core::Diagnostic;
 use ranos_ds::{
     const_val::ConstVal,
     rgb::{RGBOrder, RGB},
@@ -63,6 +64,21 @@ impl AnimationBuilder for StrobeBuilder {
     fn build(self: Box<Self>) -> Box<dyn Animation> {
         Box::new(self.build())
     }
+
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let clamped = self.duty.min(1.0).max(0.0);
+        if clamped != self.duty {
+            diagnostics.push(Diagnostic::warning(format!(
+                "duty was {}, outside the range [0, 1]; clamped to {}",
+                self.duty, clamped
+            )));
+            self.duty = clamped;
+        }
+
+        diagnostics
+    }
 }
 
 #[cfg(test)]
@@ -93,8 +109,29 @@ mod builder_test {
         assert_eq!(data.duty, 1.0 / ((1 << 2) as f64));
         assert_eq!(data.color, RGB::from_code(0xFFFFFF, RGBOrder::RGB));
     }
+
+    #[test]
+    fn test_validate_fixes_out_of_range_duty() {
+        use crate::AnimationBuilder;
+
+        // Bypasses the clamp in `StrobeBuilder::duty` to simulate a config
+        // deserialized with an out-of-range value.
+        let mut builder = StrobeBuilder {
+            duty: 1.5,
+            ..*Strobe::builder()
+        };
+        let diagnostics = builder.validate();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(builder.duty, 1.0);
+    }
 }
 
+/// The denominator `duty` is scaled against to get an exact [`u64`] numerator,
+/// so the on/off comparison in [`Strobe::render_frame`] is pure integer math
+/// instead of repeating a lossy floating-point division every frame.
+const DUTY_DENOM: u64 = 1_000_000;
+
 /// Struct for animating a flickering light similar to the strobe lights one
 /// might see at concerts or otherwise.
 ///
@@ -104,17 +141,21 @@ mod builder_test {
 /// The `period` is simply the amount of time before the strobe pattern repeats,
 /// and the `duty cycle` being a value in the range of [0, 1) representing the
 /// percentage of time that the LEDs are on within the `period`.
+///
+/// Phase is tracked as whole nanoseconds rather than fractional seconds, so
+/// it never accumulates rounding error no matter how long the animation runs
+/// -- the duty-cycle boundary stays exactly where it was configured instead of jittering.
 #[derive(Debug)]
 pub struct Strobe {
     runtime: ConstVal<Duration>,
     time_remaining: Duration,
 
-    period: ConstVal<f64>,
-    duty: ConstVal<f64>,
+    period_ns: ConstVal<u64>,
+    duty_num: ConstVal<u64>,
 
     color: ConstVal<RGB>,
 
-    time: f64,
+    phase_ns: u64,
 }
 
 impl Strobe {
@@ -136,31 +177,29 @@ impl Strobe {
 
     fn new(runtime: Duration, period: Duration, duty: f64, color: RGB, ) -> Self {
         let duty = duty.min(1.0).max(0.0);
+        let duty_num = (duty * DUTY_DENOM as f64).round() as u64;
 
         Self {
             runtime: ConstVal::new(runtime),
             time_remaining: runtime,
 
-            period: ConstVal::new(period.as_secs_f64()),
-            duty: ConstVal::new(duty),
+            period_ns: ConstVal::new(period.as_nanos() as u64),
+            duty_num: ConstVal::new(duty_num),
 
             color: ConstVal::new(color),
 
-            time: 0.0,
+            phase_ns: 0,
         }
     }
 }
 
 impl Animation for Strobe {
     fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
-        // Accumulate the time, clamping it to a range of [0, self.period)
-        self.time = (self.time + dt.as_secs_f64()) % self.period.get();
-
-        // Convert the time to a fraction in the range [0, 1)
-        let r = self.time / self.period.get();
+        // Accumulate the phase, clamping it to a range of [0, self.period_ns)
+        self.phase_ns = (self.phase_ns + dt.as_nanos() as u64) % *self.period_ns.get();
 
         // Set the current color, based on how long it's been in the current cycle
-        let color = if r < *self.duty.get() {
+        let color = if self.phase_ns * DUTY_DENOM < *self.duty_num.get() * *self.period_ns.get() {
             *self.color.get()
         } else {
             RGB::new()
@@ -190,6 +229,6 @@ impl Animation for Strobe {
 
     fn reset(&mut self) {
         self.time_remaining = *self.runtime.get();
-        self.time = 0.0;
+        self.phase_ns = 0;
     }
 }
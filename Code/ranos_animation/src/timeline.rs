@@ -0,0 +1,253 @@
+//! # Timeline
+//!
+//! Sequences multiple [`Animation`]s against a single, absolute elapsed-time
+//! origin -- each entry owns a `[start, start + duration)` window of that
+//! shared timeline -- rather than every animation tracking its own separate
+//! `time_remaining` in isolation. Entries whose windows overlap are
+//! cross-faded, so a show can be authored as e.g. a strobe from `0`-`8s`
+//! and a spline fade from `6`-`20s`, with the `6`-`8s` seam between them
+//! blended rather than cutting instantly.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// A single scheduled entry: an animation plus the window of the timeline's
+/// elapsed time during which it's active.
+struct Entry {
+    animation: Box<dyn Animation>,
+    start: Duration,
+    duration: Duration,
+}
+
+impl Entry {
+    fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+}
+
+/// Builder for the [`Timeline`] scheduler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Timeline")]
+pub struct TimelineBuilder {
+    entries: Vec<(Duration, Duration, Box<dyn AnimationBuilder>)>,
+}
+
+impl TimelineBuilder {
+    /// Schedules `builder`'s animation to run from `start` for `duration`
+    /// against the timeline's shared origin.
+    pub fn entry(mut self: Box<Self>, start: Duration, duration: Duration, builder: Box<dyn AnimationBuilder>) -> Box<Self> {
+        self.entries.push((start, duration, builder));
+
+        self
+    }
+
+    /// Constructs a [`Timeline`] object.
+    pub fn build(self: Box<Self>) -> Timeline {
+        Timeline::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl AnimationBuilder for TimelineBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Animation> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use std::time::Duration;
+
+    use crate::{Solid, Strobe, Timeline};
+
+    #[test]
+    fn test_serialize_empty() {
+        let builder = Timeline::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        assert_eq!(data, "(entries:[])");
+    }
+
+    #[test]
+    fn test_deserialize_round_trips_entry_count() {
+        let builder = Timeline::builder()
+            .entry(Duration::from_secs(0), Duration::from_secs(8), Strobe::builder())
+            .entry(Duration::from_secs(6), Duration::from_secs(12), Solid::builder());
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let data: super::TimelineBuilder = ron::de::from_str(&data).unwrap();
+
+        assert_eq!(data.entries.len(), 2);
+    }
+}
+
+/// Struct for a scheduler that drives several [`Animation`]s against one
+/// shared elapsed-time origin rather than running them one at a time.
+///
+/// Each call to [`Self::render_frame`] advances the timeline's elapsed time
+/// by `dt`, figures out which entries' `[start, start + duration)` windows
+/// that step actually crossed, and feeds each of them only the slice of
+/// `dt` that overlapped their own window -- so an entry that activates or
+/// deactivates mid-step still gets an accurate local `dt` instead of the
+/// full external one.
+///
+/// When a step crosses exactly one entry's window, that entry renders
+/// straight into the output frame. When it crosses two (an authored overlap
+/// between a finishing and a starting entry), both render into scratch
+/// frames and are cross-faded together, weighted by how far elapsed time
+/// has moved through the overlap of their two windows.
+#[derive(Debug)]
+pub struct Timeline {
+    entries: Vec<Entry>,
+    elapsed: Duration,
+}
+
+impl std::fmt::Debug for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Entry")
+            .field("start", &self.start)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl Timeline {
+    /// Constructs a builder object with no scheduled entries.
+    pub fn builder() -> Box<TimelineBuilder> {
+        Box::<TimelineBuilder>::default()
+    }
+
+    fn from_builder(builder: Box<TimelineBuilder>) -> Self {
+        let entries = builder
+            .entries
+            .into_iter()
+            .map(|(start, duration, builder)| Entry {
+                animation: builder.build(),
+                start,
+                duration,
+            })
+            .collect();
+
+        Self::new(entries)
+    }
+
+    fn new(entries: Vec<Entry>) -> Self {
+        Self {
+            entries,
+            elapsed: Duration::new(0, 0),
+        }
+    }
+
+    /// The elapsed time at which the last-finishing entry's window closes.
+    fn total_span(&self) -> Duration {
+        self.entries.iter().map(Entry::end).max().unwrap_or_default()
+    }
+}
+
+impl Animation for Timeline {
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> AnimationState {
+        let prev_elapsed = self.elapsed;
+        self.elapsed += dt;
+        let elapsed = self.elapsed;
+
+        let mut active: Vec<(usize, Duration)> = Vec::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let overlap_start = prev_elapsed.max(entry.start);
+            let overlap_end = elapsed.min(entry.end());
+
+            if overlap_end > overlap_start {
+                active.push((i, overlap_end - overlap_start));
+            }
+        }
+
+        if active.is_empty() {
+            return if elapsed >= self.total_span() {
+                AnimationState::Last
+            } else {
+                AnimationState::Continue
+            };
+        }
+
+        let size = frame.len();
+        let brightness = frame.brightness();
+        let mut rendered: Vec<(usize, Duration, Frame)> = Vec::with_capacity(active.len());
+
+        for (i, local_dt) in active {
+            let mut scratch = Frame::new(brightness, size);
+            self.entries[i].animation.render_frame(&mut scratch, local_dt);
+            rendered.push((i, local_dt, scratch));
+        }
+
+        if rendered.len() == 1 {
+            *frame = rendered.into_iter().next().unwrap().2;
+        } else {
+            rendered.sort_by_key(|(i, _, _)| self.entries[*i].start);
+            let (first_i, first_local_dt, first_frame) = rendered.remove(0);
+            let (second_i, second_local_dt, second_frame) = rendered.remove(0);
+
+            let overlap_start = self.entries[second_i].start.max(self.entries[first_i].start);
+            let overlap_end = self.entries[first_i].end().min(self.entries[second_i].end());
+
+            let t = if overlap_end > overlap_start {
+                // A genuine authored overlap: fade proportionally to how far
+                // elapsed time has moved through it.
+                (elapsed.saturating_sub(overlap_start).as_secs_f32() / (overlap_end - overlap_start).as_secs_f32())
+                    .clamp(0.0, 1.0)
+            } else {
+                // This step's `dt` merely spanned the boundary between two
+                // back-to-back, non-overlapping entries; weight each by its
+                // share of local time within this one step.
+                let total = (first_local_dt + second_local_dt).as_secs_f32();
+                if total > 0.0 {
+                    second_local_dt.as_secs_f32() / total
+                } else {
+                    0.5
+                }
+            };
+
+            for i in 0..size {
+                frame.as_mut_slice()[i] = first_frame.as_slice()[i].scale(1.0 - t).add(second_frame.as_slice()[i].scale(t));
+            }
+            frame.set_brightness(first_frame.brightness() * (1.0 - t) + second_frame.brightness() * t);
+        }
+
+        AnimationState::Continue
+    }
+
+    fn time_remaining(&self) -> Duration {
+        self.total_span().checked_sub(self.elapsed).unwrap_or_default()
+    }
+
+    fn reset(mut self: Box<Self>) -> Box<dyn Animation> {
+        self.elapsed = Duration::new(0, 0);
+
+        for entry in self.entries.iter_mut() {
+            let animation = std::mem::replace(&mut entry.animation, Box::new(NullAnimation));
+            entry.animation = animation.reset();
+        }
+
+        self
+    }
+}
+
+/// Placeholder animation used only to satisfy `std::mem::replace` while
+/// [`Timeline::reset`] swaps an entry's animation for its own reset result.
+#[derive(Debug)]
+struct NullAnimation;
+
+impl Animation for NullAnimation {
+    fn render_frame(&mut self, _frame: &mut Frame, _dt: Duration) -> AnimationState {
+        AnimationState::Last
+    }
+
+    fn time_remaining(&self) -> Duration {
+        Duration::new(0, 0)
+    }
+
+    fn reset(self: Box<Self>) -> Box<dyn Animation> {
+        self
+    }
+}
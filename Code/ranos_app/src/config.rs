@@ -1,70 +1,82 @@
-use std::ops::Deref;
-use structopt::StructOpt;
+//! Declarative, file-based configuration for building an [`App`](crate::App).
+//!
+//! A config file describes a single drawer, the displays it owns, and each
+//! display's ordered queue of generator builders, plus a handful of app-wide
+//! settings (currently just [`AppConfig::on_complete`]) that sit alongside
+//! the drawer tree rather than inside it. Since
+//! [`DrawBuilder`](ranos_draw::DrawBuilder) and
+//! [`GeneratorBuilder`](ranos_generator::GeneratorBuilder) are already
+//! `typetag`-serializable trait objects, and
+//! [`DisplayBuilder`](ranos_display::DisplayBuilder) derives `Serialize`/
+//! `Deserialize` directly, the whole tree round-trips through RON or TOML
+//! with no bespoke schema of its own: nest `(displays: [...])` in RON, or
+//! repeated `[[displays]]` / `[[displays.generator_builders]]` tables in TOML.
 
-#[derive(StructOpt)]
-/// Renders some animations through a give LED drawer
-pub struct AppOpt {
-    #[structopt(short, long, default_value = "64")]
-    /// The number of LEDs to draw to. Defaults to 64.
-    size: usize,
+use std::{fs, io, path::Path};
 
-    #[structopt(short, long)]
-    /// The upper limit of the rate of updates to the LED array (e.g. 60,
-    /// 29.97).
-    ///
-    /// If the parameter is omitted, then there will be no upper limit
-    /// to the speed and will simply run as fast as the system can support.
-    rate: Option<f64>,
+use serde::{Deserialize, Serialize};
 
-    #[structopt(short, long)]
-    /// Sets the brightness to use for the LEDs. NOTE: For APA102C and related
-    /// LEDs, the minimum possible brightness is 1/31, or approximately 0.0325.
-    brightness: f64,
+use ranos_draw::DrawBuilder;
 
-    #[structopt(name = "loop", short, long)]
-    /// Enables looping of the animations. To exit the program while looping is
-    /// enabled, send the program the `SIGTERM` signal (Ctrl + C in the
-    /// terminal) and it will exit at the end of the current loop.
-    is_looping: bool,
-
-    #[structopt(subcommand)]
-    /// The drawer to use. Possible values are NullDraw, PiDraw, & TermDraw.
-    draw: DrawOpt,
-}
-
-pub struct DrawOpt {
-    draw: DrawVariants,
-
-    anims: 
+/// Top-level shape of an [`App`](crate::App) config file: the drawer tree
+/// plus whatever app-wide settings aren't part of that tree, e.g. the
+/// [`on_complete`](Self::on_complete) hook command.
+#[derive(Serialize, Deserialize)]
+pub struct AppConfig {
+    /// The drawer (and its displays/generators) to build the app around.
+    pub drawer: Box<dyn DrawBuilder>,
+    /// Shell command to run on lifecycle events; see [`App::on_complete`](crate::App::on_complete).
+    #[serde(default)]
+    pub on_complete: Option<String>,
 }
 
-#[derive(StructOpt)]
-pub enum DrawVariants {
-    NullDraw(NullDrawOpt),
-    PiDraw(PiDrawOpt),
-    TermDraw(TermDrawOpt),
+/// Errors that can occur while loading an [`App`](crate::App) configuration file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read from disk.
+    Io(io::Error),
+    /// The config file's extension wasn't recognized as `ron` or `toml`.
+    UnknownFormat,
+    /// The contents of the config file could not be deserialized.
+    Parse(String),
 }
 
-#[derive(StructOpt)]
-pub struct NullDrawOpt {
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "failed to read config file: {}", e),
+            ConfigError::UnknownFormat => {
+                write!(f, "config file must have a `.ron` or `.toml` extension")
+            }
+            ConfigError::Parse(e) => write!(f, "failed to parse config file: {}", e),
+        }
+    }
 }
 
-#[derive(StructOpt)]
-pub struct PiDrawOpt {
-}
+impl std::error::Error for ConfigError {}
 
-#[derive(StructOpt)]
-pub struct TermDrawOpt {
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
 }
 
-pub struct AnimationOpt {
-    
-}
+/// Loads an [`AppConfig`] from the RON or TOML file at `path`, the format
+/// being chosen by the file's extension.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, its extension is not `ron`
+/// or `toml`, or its contents cannot be deserialized into an [`AppConfig`].
+pub fn load_app_config<P: AsRef<Path>>(path: P) -> Result<AppConfig, ConfigError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
 
-#[derive(StructOpt)]
-pub enum AnimationVariants {
-    Breath,
-    Cycle,
-    Rainbow,
-    Strobe,
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ron") => {
+            ron::de::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string()))
+        }
+        Some("toml") => toml::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string())),
+        _ => Err(ConfigError::UnknownFormat),
+    }
 }
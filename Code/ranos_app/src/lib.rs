@@ -2,40 +2,108 @@
 //!
 //! This module contains the application interface that controls the LEDs.
 
-// #![warn(missing_docs)]
+#![warn(missing_docs)]
 #![deny(broken_intra_doc_links)]
 #![warn(clippy::all)]
 
-#[macro_use]
-extern crate lazy_static;
+use std::{path::Path, process::Command, sync::atomic::Ordering, time::Duration};
 
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use ranos_draw::{Draw, NullDraw, SIGINT};
 
-use ranos_animation;
-use ranos_draw::{Draw, DrawStats};
-use ranos_core::{Timer, info};
+pub mod config;
 
-lazy_static! {
-    static ref SIGINT: Arc<AtomicBool> = {
-        let arc = Arc::new(AtomicBool::new(false));
+/// Top-level application object: owns the configured drawer and runs it to completion.
+///
+/// Config file makeup:
+/// * Master drawer
+///   * Vec of displays
+///     * Vec of animations
+///
+/// An [`App`] is built either from a declarative config file (see the
+/// [`config`] module) via [`App::from_config`], or with a minimal built-in
+/// default via [`App::default`] when no config file is supplied.
+pub struct App {
+    drawer: Box<dyn Draw>,
+    on_complete: Option<String>,
+}
+
+impl App {
+    /// Builds an [`App`] from the RON or TOML config file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or doesn't deserialize into
+    /// a valid [`config::AppConfig`].
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<Self, config::ConfigError> {
+        let config = config::load_app_config(path)?;
+
+        Ok(Self {
+            drawer: config.drawer.build(),
+            on_complete: config.on_complete,
+        })
+    }
+
+    /// Sets the shell command to run on lifecycle events (queue completion,
+    /// `SIGINT` exit), overriding whatever a config file set.
+    ///
+    /// The command is spawned without blocking the render loop, via `sh -c`,
+    /// with the event's context passed through the environment:
+    /// * `RANOS_EVENT` - the event name (`complete` or `sigint`).
+    /// * `RANOS_ELAPSED_SECS` - seconds elapsed over the run, from the drawer's `TimerStats`.
+    ///
+    /// Note: this only covers events visible at the drawer level. There's no
+    /// hook point yet for an individual animation finishing mid-queue, since
+    /// [`Draw`] doesn't currently surface that granularity to its caller.
+    pub fn on_complete(mut self, cmd: impl Into<String>) -> Self {
+        self.on_complete = Some(cmd.into());
+
+        self
+    }
+
+    /// Runs the drawer to completion, returning once it finishes or `SIGINT` is caught.
+    pub fn run(&mut self) {
+        self.drawer.run();
 
-        {
-            let arc = arc.clone();
-            ctrlc::set_handler(move || arc.store(true, Ordering::Relaxed)).unwrap();
+        let elapsed = self.drawer.stats().elapsed();
+
+        if SIGINT.load(Ordering::Relaxed) {
+            eprintln!("Caught SIGINT, exiting.");
+            self.fire_hook("sigint", elapsed);
+        } else {
+            self.fire_hook("complete", elapsed);
         }
+    }
 
-        arc
-    };
-}
+    /// Spawns [`Self::on_complete`]'s command, if set, passing `event` and
+    /// `elapsed` through the environment. Errors spawning the command are
+    /// logged and otherwise ignored, since a broken hook shouldn't be fatal
+    /// to an otherwise-successful run.
+    fn fire_hook(&self, event: &str, elapsed: Duration) {
+        let cmd = match &self.on_complete {
+            Some(cmd) => cmd,
+            None => return,
+        };
 
-// Config file makeup:
-// * Master drawer
-//   * Vec of displays
-//     * Vec of animations
+        let result = Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .env("RANOS_EVENT", event)
+            .env("RANOS_ELAPSED_SECS", elapsed.as_secs_f64().to_string())
+            .spawn();
 
-struct App {
+        if let Err(e) = result {
+            eprintln!("Failed to spawn on_complete hook: {}", e);
+        }
+    }
+}
 
+impl Default for App {
+    /// Builds an [`App`] around an empty [`NullDraw`], used when no config
+    /// file is supplied and there otherwise isn't a setup to run.
+    fn default() -> Self {
+        Self {
+            drawer: NullDraw::builder().build(),
+            on_complete: None,
+        }
+    }
 }
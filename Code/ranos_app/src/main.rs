@@ -1,48 +1,22 @@
-use std::time::Duration;
-
-use ranos_app::*;
-use ranos_core::*;
-use ranos_animation::*;
-use ranos_display::*;
-use ranos_draw::{Draw, DrawBuilder, TermDraw};
+use ranos_app::App;
 
 fn main() {
     let args = std::env::args().collect::<Vec<_>>();
-    if args.len() > 1 {
-        // Serialization
-        {
-            let file = std::fs::File::create(args[1].as_str()).unwrap();
 
-            ron::ser::to_writer_pretty(
-                file,
-                &(
-                    TermDraw::builder()
-                        .max_width(8)
-                        .timer(Timer::new(Some(Duration::from_secs_f64(1.0/60.0))))
-                        .display(
-                            Display::builder()
-                                .brightness(1.0)
-                                .size(64)
-                                .add_animation_builder(
-                                    Rainbow::builder()
-                                        .runtime(Duration::from_secs(8))
-                                        .rainbow_length(Duration::from_secs(2))
-                                        .saturation(1.0)
-                                        .value(1.0)
-                                        .arc(1.0)
-                                        .step(8)
-                                )
-                        )
-                    as Box<dyn DrawBuilder>
-                ),
-                ron::ser::PrettyConfig::default(),
-            ).unwrap();
+    let mut app = match args.iter().position(|a| a == "--config") {
+        Some(ind) => {
+            let path = args.get(ind + 1).expect("--config requires a file path");
+            App::from_config(path).unwrap_or_else(|e| panic!("failed to load config: {}", e))
         }
+        None => App::default(),
+    };
 
-        // Deserialization
-        {
-            let config = std::fs::File::open(args[1].as_str()).unwrap();
-            ron::de::from_reader::<_, Box<dyn DrawBuilder>>(config).unwrap().build().run();
-        }
+    if let Some(ind) = args.iter().position(|a| a == "--on-complete") {
+        let cmd = args
+            .get(ind + 1)
+            .expect("--on-complete requires a shell command");
+        app = app.on_complete(cmd.clone());
     }
+
+    app.run();
 }
@@ -1,42 +1,196 @@
 //! Type allowing immutable access to preloaded mono audio samples.
 
-use std::time::Duration;
+use std::{
+    io::{self, Read, Seek},
+    time::Duration,
+};
 
+use ranos_core::{clock_duration::FEMTOS_PER_SEC, ClockDuration};
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use crate::analysis::WindowFunction;
+use crate::util::{Resampler, ANALYSIS_SAMPLE_RATE};
 use crate::SIZE;
 
+/// A source of mono `f32` audio samples that can be read from incrementally,
+/// so analysis types like [`crate::analysis::Excitement`] don't need to care
+/// whether their samples come from a decoded file, a loop/intro pair, or a
+/// live microphone -- see [`BufferSource`] and, behind the `audio_in`
+/// feature, [`crate::capture::Capture`]'s impl of this trait.
+pub trait AudioSource {
+    /// Returns the sample rate of this source's audio, in Hz.
+    fn sample_rate(&self) -> usize;
+
+    /// Reads up to `out.len()` samples into `out`, returning how many were
+    /// actually written; fewer than `out.len()` means the source ran dry.
+    fn read(&mut self, out: &mut [f32]) -> usize;
+
+    /// Returns `true` once this source has no more samples left to read.
+    ///
+    /// Always `false` for sources with no inherent end, like live capture.
+    fn is_finished(&self) -> bool;
+}
+
+/// An [`AudioSource`] that reads sequentially through an in-memory buffer of
+/// already-decoded samples, e.g. a whole WAV or Ogg Vorbis file loaded via
+/// [`crate::util::read_audio`].
+pub struct BufferSource {
+    data: Vec<f32>,
+    sample_rate: usize,
+    pos: usize,
+}
+
+impl BufferSource {
+    /// Creates a new source from already-decoded samples and their sample rate.
+    pub fn new(sample_rate: usize, data: Vec<f32>) -> Self {
+        Self {
+            data,
+            sample_rate,
+            pos: 0,
+        }
+    }
+
+    /// Reads and decodes an audio stream via [`crate::util::read_audio`], and
+    /// wraps the result in a new source starting at its first sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream couldn't be read or its format couldn't be decoded.
+    pub fn from_reader<R>(reader: R) -> io::Result<Self>
+    where
+        R: Read + Seek,
+    {
+        let (sample_rate, data) = crate::util::read_audio(reader)?;
+
+        Ok(Self::new(sample_rate, data))
+    }
+}
+
+impl AudioSource for BufferSource {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn read(&mut self, out: &mut [f32]) -> usize {
+        let n = out.len().min(self.data.len() - self.pos);
+        out[..n].copy_from_slice(&self.data[self.pos..(self.pos + n)]);
+        self.pos += n;
+
+        n
+    }
+
+    fn is_finished(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+}
+
 /// Enables immutable access of preloaded mono audio samples.
 pub struct Accessor {
     data: Vec<f32>,
     sample_rate: f32,
-    ind: f32,
+    elapsed: ClockDuration,
 }
 
 impl Accessor {
     /// Creates a new accessor object from the given data and sampling rate.
-    pub fn new<R>(mut data: Vec<f32>, sample_rate: usize) -> Self {
+    ///
+    /// `data` is resampled to [`ANALYSIS_SAMPLE_RATE`] via [`Resampler`] if
+    /// `sample_rate` doesn't already match it, so [`Self::most_recent_data`]'s
+    /// `SIZE` samples mean the same span of frequencies no matter what rate
+    /// the source audio was recorded at.
+    pub fn new<R>(data: Vec<f32>, sample_rate: usize) -> Self {
+        let mut data = if sample_rate == ANALYSIS_SAMPLE_RATE {
+            data
+        } else {
+            let mut resampled = Vec::with_capacity(data.len() * ANALYSIS_SAMPLE_RATE / sample_rate.max(1));
+            Resampler::new(sample_rate).push(&data, &mut resampled);
+            resampled
+        };
         data.extend(vec![0.0; SIZE].iter()); // Add `SIZE` silence to the end of the data so that the last calls to `Self::most_recent_data` will always contain silence.
 
         Self {
             data,
-            sample_rate: sample_rate as f32,
-            ind: 0.0,
+            sample_rate: ANALYSIS_SAMPLE_RATE as f32,
+            elapsed: ClockDuration::ZERO,
         }
     }
 
     /// Updates the internal state of the accessor with the passage of time, ensuring [`Self::most_recent_data`] is accurate.
+    ///
+    /// `dt` is accumulated as an exact [`ClockDuration`] rather than summed
+    /// as `f32` seconds, so a long-running accessor's sample position
+    /// doesn't drift out of sync with the audio clock it's tracking.
     pub fn update(&mut self, dt: Duration) {
-        self.ind += dt.as_secs_f32() * self.sample_rate;
-        if self.ind > self.data.len() as f32 {
-            self.ind = self.data.len() as f32;
-        }
+        self.elapsed += ClockDuration::from(dt);
+    }
+
+    /// Converts the accumulated elapsed time to a sample index via exact
+    /// integer math (`elapsed_femtos * sample_rate / FEMTOS_PER_SEC`), clamped to the end of the data.
+    fn ind(&self) -> usize {
+        let ind = (self.elapsed.as_femtos() * self.sample_rate as u128) / FEMTOS_PER_SEC;
+
+        (ind as usize).min(self.data.len())
     }
 
     /// Accesses the most recent [`SIZE`] samples of audio.
     pub fn most_recent_data(&self) -> &[f32] {
-        if (self.ind - SIZE as f32) < 0.0 {
+        let ind = self.ind();
+
+        if ind < SIZE {
             &self.data[0..SIZE]
         } else {
-            &self.data[(self.ind as usize - SIZE)..(self.ind as usize)]
+            &self.data[(ind - SIZE)..ind]
         }
     }
+
+    /// Computes the magnitude spectrum of [`Self::most_recent_data`] via a
+    /// forward FFT, after applying a [`WindowFunction::Hann`] window to
+    /// reduce spectral leakage. Returns `SIZE / 2` bins, increasing in
+    /// frequency, each spanning `sample_rate / SIZE` Hz.
+    pub fn spectrum(&self) -> Vec<f32> {
+        self.windowed_spectrum(WindowFunction::Hann)
+    }
+
+    /// As [`Self::spectrum`], but lets the caller choose the window function
+    /// applied to the samples before the FFT; see [`WindowFunction`].
+    pub fn windowed_spectrum(&self, window: WindowFunction) -> Vec<f32> {
+        let (table, gain) = window.table();
+
+        let mut buf: Vec<Complex<f32>> = self
+            .most_recent_data()
+            .iter()
+            .zip(table.iter())
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+
+        FftPlanner::new().plan_fft_forward(SIZE).process(&mut buf);
+
+        buf[..SIZE / 2].iter().map(|c| c.norm() / gain).collect()
+    }
+
+    /// Sums [`Self::spectrum`]'s magnitude bins into the bands delimited by
+    /// `edges` (`edges.len() - 1` bands, each the half-open Hz range between
+    /// a consecutive pair, e.g. `&[0.0, 250.0, 2000.0, 8000.0]` for
+    /// bass/mid/treble), converting each spectrum bin's index to Hz via `self.sample_rate`.
+    pub fn bands(&self, edges: &[f32]) -> Vec<f32> {
+        let spectrum = self.spectrum();
+        let hz_per_bin = self.sample_rate / SIZE as f32;
+
+        edges
+            .windows(2)
+            .map(|w| {
+                let (lo, hi) = (w[0], w[1]);
+
+                spectrum
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| {
+                        let f_hz = i as f32 * hz_per_bin;
+                        f_hz >= lo && f_hz < hi
+                    })
+                    .map(|(_, &m)| m)
+                    .sum()
+            })
+            .collect()
+    }
 }
@@ -1,5 +1,6 @@
 //! Algorithm adapted from one I made for the Wild Holidays charity event.
 
+use std::f32::consts::PI;
 use std::sync::Arc;
 
 use ranos_core::curve::Curve;
@@ -7,15 +8,111 @@ use rustfft::{num_complex::Complex, Fft, FftPlanner};
 
 use crate::SIZE;
 
+/// Window function applied to samples before the FFT in [`Excitement::fft`],
+/// to cut the spectral leakage a rectangular (i.e. no) window produces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowFunction {
+    /// No window (implicitly rectangular) -- cheapest, but leaks the most energy across bins.
+    None,
+    /// `0.5 * (1.0 - cos(2*pi*n / (SIZE - 1)))`. The default, and a good general-purpose choice.
+    Hann,
+    /// `0.54 - 0.46 * cos(2*pi*n / (SIZE - 1))`. Slightly less spectral leakage than Hann, at the cost of a higher noise floor.
+    Hamming,
+}
+
+/// How the edges of [`Excitement`]'s bins are spaced across its spectrum
+/// range, chosen via [`Excitement::new`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinScale {
+    /// Bins of equal width in spectrum-index space. Simple, but wastes most
+    /// bins on high frequencies, where music tends to carry the least energy.
+    Linear,
+    /// Bins of equal width in `log2(f_hz)` space, giving low frequencies
+    /// proportionally more resolution than [`Self::Linear`].
+    Log,
+    /// Bins of equal width on the mel scale (`m = 2595 * log10(1 + f_hz / 700)`),
+    /// approximating how pitch is perceived -- usually the most musically
+    /// meaningful choice of the three for driving LED animations.
+    Mel,
+}
+
+impl WindowFunction {
+    /// Computes this window's `[f32; SIZE]` coefficient table and the sum of
+    /// its coefficients, used to normalize magnitude scaling back to roughly
+    /// what a rectangular window would have produced.
+    pub(crate) fn table(self) -> ([f32; SIZE], f32) {
+        let mut window = [1.0_f32; SIZE];
+
+        match self {
+            WindowFunction::None => (),
+            WindowFunction::Hann => {
+                for (n, w) in window.iter_mut().enumerate() {
+                    *w = 0.5 * (1.0 - (2.0 * PI * n as f32 / (SIZE as f32 - 1.0)).cos());
+                }
+            }
+            WindowFunction::Hamming => {
+                for (n, w) in window.iter_mut().enumerate() {
+                    *w = 0.54 - 0.46 * (2.0 * PI * n as f32 / (SIZE as f32 - 1.0)).cos();
+                }
+            }
+        }
+
+        let gain = window.iter().sum();
+
+        (window, gain)
+    }
+}
+
+/// Computes `num_bins + 1` spectrum-index edges spanning `bin_range`
+/// (expressed as fractions of Nyquist) according to `scale`, needing
+/// `sample_rate` to convert to and from Hz for [`BinScale::Log`] and
+/// [`BinScale::Mel`], whose spacing is nonlinear in absolute frequency.
+fn bin_edges(bin_range: (f32, f32), num_bins: usize, scale: BinScale, sample_rate: f32) -> Vec<usize> {
+    let nyquist = sample_rate / 2.0;
+    let to_index = |f_hz: f32| ((f_hz / nyquist) * (SIZE as f32 / 2.0)).round() as usize;
+
+    match scale {
+        BinScale::Linear => {
+            let begin = (bin_range.0 * (SIZE as f32) / 2.0).floor();
+            let end = (bin_range.1 * (SIZE as f32) / 2.0).ceil();
+
+            (0..=num_bins)
+                .map(|i| (begin + (i as f32 / num_bins as f32) * (end - begin)).round() as usize)
+                .collect()
+        }
+        BinScale::Log => {
+            let lo = (bin_range.0 * nyquist).max(1.0).log2();
+            let hi = (bin_range.1 * nyquist).max(1.0).log2();
+
+            (0..=num_bins)
+                .map(|i| to_index(2.0_f32.powf(lo + (i as f32 / num_bins as f32) * (hi - lo))))
+                .collect()
+        }
+        BinScale::Mel => {
+            let hz_to_mel = |f: f32| 2595.0 * (1.0 + f / 700.0).log10();
+            let mel_to_hz = |m: f32| 700.0 * (10.0_f32.powf(m / 2595.0) - 1.0);
+
+            let lo = hz_to_mel(bin_range.0 * nyquist);
+            let hi = hz_to_mel(bin_range.1 * nyquist);
+
+            (0..=num_bins)
+                .map(|i| to_index(mel_to_hz(lo + (i as f32 / num_bins as f32) * (hi - lo))))
+                .collect()
+        }
+    }
+}
+
 /// A type that processes audio samples and extracts info from its spectrum
 pub struct Excitement {
     scalar: f32,
     curve: Curve,
     decay: f32,
-    bin_range: (f32, f32),
     num_bins: usize,
+    bin_edges: Vec<usize>,
     bins: Vec<f32>,
     fft: Arc<dyn Fft<f32>>,
+    window: [f32; SIZE],
+    window_gain: f32,
     spectrum: [Complex<f32>; SIZE],
     scratch: [Complex<f32>; SIZE],
 }
@@ -33,24 +130,36 @@ impl Excitement {
     /// spectrum data of the audio samples. The first value of the tuple is
     /// interpreted as the minimum and the second value as the maximum. Should be in the range of \[0, 1\].
     /// * `num_bins` - The number of bins that spectrum data will fit in.
+    /// * `window` - The window function applied to samples before the FFT in
+    /// [`Self::fft`]; see [`WindowFunction`].
+    /// * `bin_scale` - How `bin_range` is subdivided into `num_bins` bins;
+    /// see [`BinScale`]. [`BinScale::Log`] and [`BinScale::Mel`] need
+    /// `sample_rate` to convert `bin_range`'s Nyquist fractions to and from Hz.
+    /// * `sample_rate` - The sample rate the analyzed audio was captured at.
     pub fn new(
         scalar: f32,
         power: f32,
         decay: f32,
         bin_range: (f32, f32),
         num_bins: usize,
+        window: WindowFunction,
+        bin_scale: BinScale,
+        sample_rate: f32,
     ) -> Self {
         let decay = decay.min(1.0).min(0.0);
         let bin_range = (bin_range.0.max(0.0).min(1.0), bin_range.1.min(1.0).max(0.0));
+        let (window, window_gain) = window.table();
 
         Self {
             scalar,
             curve: Curve::new(power),
             decay,
-            bin_range,
             num_bins,
+            bin_edges: bin_edges(bin_range, num_bins, bin_scale, sample_rate),
             bins: vec![0.0; num_bins],
             fft: FftPlanner::new().plan_fft_forward(SIZE),
+            window,
+            window_gain,
             spectrum: [Complex::new(0.0, 0.0); SIZE],
             scratch: [Complex::new(0.0, 0.0); SIZE],
         }
@@ -73,35 +182,33 @@ impl Excitement {
             *b = 0.0;
         }
 
-        let begin = (self.bin_range.0 * (SIZE as f32) / 2.0).floor() as usize;
-        let end = (self.bin_range.1 * (SIZE as f32) / 2.0).ceil() as usize;
-        let range_size = end - begin;
-        let bin_size = range_size / self.num_bins;
+        for b in 0..self.num_bins {
+            let (lo, hi) = (self.bin_edges[b], self.bin_edges[b + 1].max(self.bin_edges[b] + 1));
+            let width = (hi - lo) as f32;
 
-        for i in begin..end {
-            let bin_idx = (i - begin) / bin_size;
-            let bin = self.bins.get_mut(bin_idx).unwrap();
-            let spectrum_norm = self.spectrum[i].norm() / (SIZE as f32 / 2.0);
-            *bin += spectrum_norm / (bin_size as f32);
+            for i in lo..hi {
+                let spectrum_norm = self.spectrum[i].norm() / self.window_gain;
+                self.bins[b] += spectrum_norm / width;
+            }
         }
 
-        for (i, b) in self.bins.iter_mut().enumerate() {
-            let spec_begin = i * bin_size + begin;
-            let spec_end = (i + 1) * bin_size + begin;
+        for b in 0..self.num_bins {
+            let (lo, hi) = (self.bin_edges[b], self.bin_edges[b + 1].max(self.bin_edges[b] + 1));
+            let width = (hi - lo) as f32;
 
             let mut bin_mean = 0.0;
-            for j in spec_begin..spec_end {
+            for j in lo..hi {
                 bin_mean += self.spectrum[j].norm();
             }
-            bin_mean /= (spec_end - spec_begin) as f32;
+            bin_mean /= width;
 
-            let bin = self.scalar * (bin_mean / (SIZE as f32 / 2.0)) / (bin_size as f32);
+            let bin = self.scalar * (bin_mean / self.window_gain) / width;
             let curved_bin = self.curve.at(bin);
 
-            if curved_bin > *b {
-                *b = curved_bin;
+            if curved_bin > self.bins[b] {
+                self.bins[b] = curved_bin;
             } else {
-                *b *= self.decay;
+                self.bins[b] *= self.decay;
             }
         }
 
@@ -113,8 +220,8 @@ impl Excitement {
     fn fft(&mut self, samples: &[f32]) {
         self.spectrum
             .iter_mut()
-            .zip(samples.iter())
-            .for_each(|(o, &i)| *o = Complex::new(i, 0.0));
+            .zip(samples.iter().zip(self.window.iter()))
+            .for_each(|(o, (&i, &w))| *o = Complex::new(i * w, 0.0));
 
         self.fft
             .process_with_scratch(&mut self.spectrum, &mut self.scratch);
@@ -0,0 +1,10 @@
+//! Algorithms that turn raw audio samples into higher-level signals for
+//! animations to react to: [`excitement`] groups a spectrum into decaying
+//! excitement bins, and [`onset_detector`] picks out beats/transients from
+//! spectral flux.
+
+pub use excitement::{BinScale, Excitement, WindowFunction};
+pub use onset_detector::OnsetDetector;
+
+pub mod excitement;
+pub mod onset_detector;
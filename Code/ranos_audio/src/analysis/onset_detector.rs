@@ -0,0 +1,171 @@
+//! Spectral-flux onset detector -- see [`OnsetDetector`].
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+use crate::SIZE;
+
+use super::excitement::WindowFunction;
+
+/// Number of flux values kept in [`OnsetDetector`]'s sliding mean window by
+/// default, at `SIZE`-sample frames of 44.1kHz audio -- roughly 1 second.
+pub const DEFAULT_HISTORY_LEN: usize = 43;
+
+/// Detects onsets (beats/transients) in a stream of audio frames via
+/// spectral flux: the amount the magnitude spectrum grew, bin by bin, since
+/// the previous frame.
+///
+/// Each [`Self::update`], the flux is compared against the running mean of
+/// the last [`Self::history_len`] frames; a flux that both exceeds
+/// `mean * sensitivity` and is a local maximum against the immediately
+/// preceding frame's flux is reported as an onset via [`Self::is_onset`],
+/// after which new onsets are suppressed for `refractory_period` frames to
+/// avoid double-triggering on one transient's rising edge.
+pub struct OnsetDetector {
+    sensitivity: f32,
+    refractory_period: usize,
+    refractory_counter: usize,
+
+    begin: usize,
+    end: usize,
+
+    fft: Arc<dyn Fft<f32>>,
+    window: [f32; SIZE],
+    spectrum: [Complex<f32>; SIZE],
+    scratch: [Complex<f32>; SIZE],
+    prev_magnitudes: [f32; SIZE],
+
+    history: VecDeque<f32>,
+    history_len: usize,
+    history_sum: f32,
+
+    flux: f32,
+    prev_flux: f32,
+    is_onset: bool,
+}
+
+impl OnsetDetector {
+    /// Creates a new onset detector.
+    ///
+    /// # Parameters
+    ///
+    /// * `bin_range` - The range of the spectrum, as fractions of Nyquist,
+    /// that spectral flux is accumulated over. Should be in the range of \[0, 1\].
+    /// * `window` - The window function applied to samples before the FFT;
+    /// see [`WindowFunction`].
+    /// * `sensitivity` - How far above the running mean flux must rise to
+    /// report an onset. Higher values report fewer, more confident onsets.
+    /// * `history_len` - How many past frames' flux values the running mean
+    /// is computed over. See [`DEFAULT_HISTORY_LEN`] for a ~1 second default.
+    /// * `refractory_period` - How many frames to suppress new onsets for
+    /// after reporting one.
+    pub fn new(
+        bin_range: (f32, f32),
+        window: WindowFunction,
+        sensitivity: f32,
+        history_len: usize,
+        refractory_period: usize,
+    ) -> Self {
+        let bin_range = (bin_range.0.max(0.0).min(1.0), bin_range.1.min(1.0).max(0.0));
+        let begin = (bin_range.0 * (SIZE as f32) / 2.0).floor() as usize;
+        let end = (bin_range.1 * (SIZE as f32) / 2.0).ceil() as usize;
+        let (window, _) = window.table();
+
+        Self {
+            sensitivity,
+            refractory_period,
+            refractory_counter: 0,
+
+            begin,
+            end,
+
+            fft: FftPlanner::new().plan_fft_forward(SIZE),
+            window,
+            spectrum: [Complex::new(0.0, 0.0); SIZE],
+            scratch: [Complex::new(0.0, 0.0); SIZE],
+            prev_magnitudes: [0.0; SIZE],
+
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            history_sum: 0.0,
+
+            flux: 0.0,
+            prev_flux: 0.0,
+            is_onset: false,
+        }
+    }
+
+    /// Updates the detector with the given [`SIZE`] samples, computing the
+    /// new spectral flux and refreshing [`Self::is_onset`] and [`Self::strength`].
+    pub fn update(&mut self, samples: &[f32]) {
+        if samples.len() != SIZE {
+            return;
+        }
+
+        self.fft(samples);
+
+        let mut flux = 0.0;
+        for i in self.begin..self.end {
+            let magnitude = self.spectrum[i].norm();
+            flux += (magnitude - self.prev_magnitudes[i]).max(0.0);
+            self.prev_magnitudes[i] = magnitude;
+        }
+
+        let mean = if self.history.is_empty() {
+            flux
+        } else {
+            self.history_sum / self.history.len() as f32
+        };
+
+        if self.history.len() == self.history_len {
+            self.history_sum -= self.history.pop_front().unwrap();
+        }
+        self.history.push_back(flux);
+        self.history_sum += flux;
+
+        self.prev_flux = self.flux;
+        self.flux = flux;
+
+        let onset = self.refractory_counter == 0
+            && flux > mean * self.sensitivity
+            && flux > self.prev_flux;
+
+        if self.refractory_counter > 0 {
+            self.refractory_counter -= 1;
+        }
+
+        self.is_onset = onset;
+        if onset {
+            self.refractory_counter = self.refractory_period;
+        }
+    }
+
+    /// Returns whether the most recent [`Self::update`] reported an onset.
+    pub fn is_onset(&self) -> bool {
+        self.is_onset
+    }
+
+    /// Returns how far the most recent frame's flux rose above the running
+    /// mean, as a ratio -- `1.0` means flux equals the mean.
+    pub fn strength(&self) -> f32 {
+        let mean = self.history_sum / self.history.len().max(1) as f32;
+
+        if mean > 0.0 {
+            self.flux / mean
+        } else {
+            0.0
+        }
+    }
+
+    fn fft(&mut self, samples: &[f32]) {
+        self.spectrum
+            .iter_mut()
+            .zip(samples.iter().zip(self.window.iter()))
+            .for_each(|(o, (&i, &w))| *o = Complex::new(i * w, 0.0));
+
+        self.fft
+            .process_with_scratch(&mut self.spectrum, &mut self.scratch);
+    }
+}
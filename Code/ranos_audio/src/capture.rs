@@ -0,0 +1,209 @@
+//! Enables live audio capture from an input device using [`cpal`].
+
+use std::sync::{Arc, Mutex};
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    BuildStreamError, DefaultStreamConfigError, PlayStreamError, SampleFormat, Stream,
+};
+
+use crate::{accessor::AudioSource, util::combine_channels, SIZE};
+
+/// Errors that can occur while opening a live [`Capture`] stream.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// No default input device was available on the system.
+    NoDevice,
+    /// The input device's default configuration could not be queried.
+    Config(DefaultStreamConfigError),
+    /// The input stream could not be built.
+    Build(BuildStreamError),
+    /// The input stream could not be started.
+    Play(PlayStreamError),
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::NoDevice => write!(f, "no default input device available"),
+            CaptureError::Config(e) => write!(f, "failed to query input device config: {}", e),
+            CaptureError::Build(e) => write!(f, "failed to build input stream: {}", e),
+            CaptureError::Play(e) => write!(f, "failed to start input stream: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<DefaultStreamConfigError> for CaptureError {
+    fn from(e: DefaultStreamConfigError) -> Self {
+        CaptureError::Config(e)
+    }
+}
+
+impl From<BuildStreamError> for CaptureError {
+    fn from(e: BuildStreamError) -> Self {
+        CaptureError::Build(e)
+    }
+}
+
+impl From<PlayStreamError> for CaptureError {
+    fn from(e: PlayStreamError) -> Self {
+        CaptureError::Play(e)
+    }
+}
+
+/// A fixed-capacity ring buffer of the most recently captured mono samples.
+struct RingBuffer {
+    data: Vec<f32>,
+    write_pos: usize,
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: vec![0.0; capacity],
+            write_pos: 0,
+        }
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        for &s in samples {
+            self.data[self.write_pos] = s;
+            self.write_pos = (self.write_pos + 1) % self.data.len();
+        }
+    }
+
+    /// Copies the most recent `SIZE` samples out, oldest-first.
+    fn most_recent(&self) -> [f32; SIZE] {
+        let len = self.data.len();
+        let mut out = [0.0; SIZE];
+
+        for (i, o) in out.iter_mut().enumerate() {
+            let idx = (self.write_pos + len - SIZE + i) % len;
+            *o = self.data[idx];
+        }
+
+        out
+    }
+}
+
+/// Captures live mono audio from the system's default input device into a
+/// ring buffer, for generators that react to real-time sound rather than a
+/// preloaded [`crate::player::Player`].
+///
+/// The capture runs on a [`cpal`] callback thread for as long as this object
+/// (and the [`Stream`] it owns) is alive.
+pub struct Capture {
+    _stream: Stream,
+    buffer: Arc<Mutex<RingBuffer>>,
+    sample_rate: f32,
+}
+
+impl Capture {
+    /// Opens the system's default input device and begins capturing
+    /// immediately, downmixing all of its channels to mono as samples arrive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no input device is available, its default
+    /// configuration can't be read, or the capture stream can't be built or
+    /// started.
+    pub fn new() -> Result<Self, CaptureError> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or(CaptureError::NoDevice)?;
+        let config = device.default_input_config()?;
+
+        let sample_rate = config.sample_rate().0 as f32;
+        let channels = config.channels() as usize;
+        let sample_format = config.sample_format();
+
+        let buffer = Arc::new(Mutex::new(RingBuffer::new(SIZE * 4)));
+        let err_fn = |err| eprintln!("audio capture stream error: {}", err);
+
+        let stream_buffer = buffer.clone();
+        let stream = match sample_format {
+            SampleFormat::F32 => device.build_input_stream(
+                &config.into(),
+                move |data: &[f32], _| push_downmixed(&stream_buffer, data, channels),
+                err_fn,
+                None,
+            )?,
+            SampleFormat::I16 => device.build_input_stream(
+                &config.into(),
+                move |data: &[i16], _| {
+                    let samples: Vec<f32> = data.iter().map(|s| *s as f32 / i16::MAX as f32).collect();
+                    push_downmixed(&stream_buffer, &samples, channels)
+                },
+                err_fn,
+                None,
+            )?,
+            SampleFormat::U16 => device.build_input_stream(
+                &config.into(),
+                move |data: &[u16], _| {
+                    let samples: Vec<f32> = data
+                        .iter()
+                        .map(|s| (*s as f32 / u16::MAX as f32) * 2.0 - 1.0)
+                        .collect();
+                    push_downmixed(&stream_buffer, &samples, channels)
+                },
+                err_fn,
+                None,
+            )?,
+            format => panic!("unsupported input sample format: {:?}", format),
+        };
+
+        stream.play()?;
+
+        Ok(Self {
+            _stream: stream,
+            buffer,
+            sample_rate,
+        })
+    }
+
+    /// Returns the sample rate of the captured audio, in Hz.
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Accesses the most recent [`SIZE`] captured samples.
+    pub fn most_recent_data(&self) -> [f32; SIZE] {
+        self.buffer.lock().unwrap().most_recent()
+    }
+}
+
+impl AudioSource for Capture {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate as usize
+    }
+
+    /// Copies out the most recent [`SIZE`] captured samples (fewer if `out`
+    /// is shorter) without consuming them, so reading again before new
+    /// samples arrive returns the same window. Returns fewer than
+    /// `out.len()` whenever `out` is longer than [`SIZE`], even though a live
+    /// capture never runs dry.
+    fn read(&mut self, out: &mut [f32]) -> usize {
+        let data = self.most_recent_data();
+        let n = out.len().min(data.len());
+        out[..n].copy_from_slice(&data[..n]);
+
+        n
+    }
+
+    /// Always `false` -- a live capture stream never runs dry.
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// Downmixes an interleaved multi-channel callback buffer to mono and pushes
+/// it into the shared ring buffer.
+fn push_downmixed(buffer: &Arc<Mutex<RingBuffer>>, data: &[f32], channels: usize) {
+    let mut mono = data.to_vec();
+    combine_channels(&mut mono, channels.max(1));
+    mono.truncate(data.len() / channels.max(1));
+
+    buffer.lock().unwrap().push(&mono);
+}
@@ -11,7 +11,11 @@ pub const SIZE: usize = 1 << 10;
 
 pub mod analysis;
 pub mod accessor;
+pub mod player;
 pub mod util;
 
 #[cfg(feature = "audio_out")]
 pub mod playback;
+
+#[cfg(feature = "audio_in")]
+pub mod capture;
@@ -1,6 +1,6 @@
 //! Enables playback functionality using [`rodio`].
 
-use std::{iter::Iterator, time::Duration};
+use std::{iter::Iterator, sync::Arc, time::Duration};
 
 use rodio::{self, OutputStream, OutputStreamHandle, PlayError, StreamError};
 
@@ -23,7 +23,10 @@ impl Output {
     }
 
     /// Plays the supplied source to the default output device.
-    pub fn play(&self, source: Source) -> Result<(), PlayError> {
+    pub fn play<S>(&self, source: S) -> Result<(), PlayError>
+    where
+        S: rodio::Source<Item = f32> + Send + 'static,
+    {
         self.handle.play_raw(source)
     }
 }
@@ -81,6 +84,130 @@ impl Iterator for Source {
     }
 }
 
+/// A snapshot of an in-progress [`MultiSource`]'s playback: shared handles to
+/// its intro/loop buffers plus enough state (`playing_intro`, `position`) to
+/// resume gapless, sample-accurate playback later via [`MultiSource::resume`].
+#[derive(Debug, Clone)]
+pub struct PlaybackState {
+    intro: Arc<Vec<f32>>,
+    loop_buf: Arc<Vec<f32>>,
+    sample_rate: u32,
+    playing_intro: bool,
+    position: usize,
+}
+
+/// A gapless source that plays an intro buffer once, then seamlessly repeats
+/// a loop buffer forever, for installations that should keep playing music
+/// (and the audio-reactive visuals driven by it) indefinitely.
+///
+/// The read position wraps by subtracting the loop's length rather than
+/// resetting to `0`, so any overshoot past the loop's end carries into the
+/// next lap instead of being dropped -- the seam stays sample-accurate and click-free.
+pub struct MultiSource {
+    intro: Arc<Vec<f32>>,
+    loop_buf: Arc<Vec<f32>>,
+    sample_rate: u32,
+    playing_intro: bool,
+    pos: usize,
+}
+
+impl MultiSource {
+    /// Creates a source that plays `intro` once, then seamlessly repeats `loop_buf` forever.
+    pub fn start_multi(intro: Vec<f32>, loop_buf: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            intro: Arc::new(intro),
+            loop_buf: Arc::new(loop_buf),
+            sample_rate,
+            playing_intro: true,
+            pos: 0,
+        }
+    }
+
+    /// Creates a source that repeats `loop_buf` forever, with no intro.
+    pub fn start_single(loop_buf: Vec<f32>, sample_rate: u32) -> Self {
+        Self {
+            intro: Arc::new(Vec::new()),
+            loop_buf: Arc::new(loop_buf),
+            sample_rate,
+            playing_intro: false,
+            pos: 0,
+        }
+    }
+
+    /// Snapshots this source's current playback position, so it can be
+    /// restored later via [`Self::resume`] without keeping this source alive.
+    pub fn state(&self) -> PlaybackState {
+        PlaybackState {
+            intro: self.intro.clone(),
+            loop_buf: self.loop_buf.clone(),
+            sample_rate: self.sample_rate,
+            playing_intro: self.playing_intro,
+            position: self.pos,
+        }
+    }
+
+    /// Reconstructs a source from a previously saved [`PlaybackState`],
+    /// resuming gapless playback from exactly where it was saved.
+    pub fn resume(state: PlaybackState) -> Self {
+        Self {
+            intro: state.intro,
+            loop_buf: state.loop_buf,
+            sample_rate: state.sample_rate,
+            playing_intro: state.playing_intro,
+            pos: state.position,
+        }
+    }
+}
+
+impl rodio::Source for MultiSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl Iterator for MultiSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.playing_intro {
+            if self.pos < self.intro.len() {
+                let sam = self.intro[self.pos];
+                self.pos += 1;
+
+                return Some(sam);
+            }
+
+            self.playing_intro = false;
+            self.pos -= self.intro.len();
+        }
+
+        if self.loop_buf.is_empty() {
+            return None;
+        }
+
+        if self.pos >= self.loop_buf.len() {
+            self.pos -= self.loop_buf.len();
+        }
+
+        let sam = self.loop_buf[self.pos];
+        self.pos += 1;
+
+        Some(sam)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -2,9 +2,12 @@
 
 use std::{
     io::{self, Read, Seek},
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
+use ranos_core::timer::ClockSource;
+
 use crate::SIZE;
 
 /// Enables playback and immutable access of preloaded audio samples.
@@ -50,6 +53,11 @@ impl Player {
         }
     }
 
+    /// Returns the sample rate of the loaded audio, in Hz.
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
     /// Accesses the most recent [`SIZE`] samples of audio.
     pub fn most_recent_data(&self) -> &[f32] {
         if (self.ind - SIZE as f32) < 0.0 {
@@ -97,6 +105,41 @@ impl std::iter::Iterator for Player {
     }
 }
 
+/// A [`ClockSource`] that derives `dt` from a shared [`Player`]'s advancing
+/// sample index rather than wall-clock time.
+///
+/// Binding a [`ranos_core::Timer`] to one of these keeps animations
+/// frame-locked to the player's playback even if the renderer itself stutters,
+/// and, since each tick deterministically steps the player forward instead of
+/// waiting in real time, it also enables offline (non-real-time) rendering.
+#[derive(Debug)]
+pub struct PlayerClock {
+    player: Arc<Mutex<Player>>,
+    samples_per_tick: f32,
+}
+
+impl PlayerClock {
+    /// Creates a new clock that steps the given `player` forward by
+    /// `samples_per_tick` samples every time it's ticked.
+    pub fn new(player: Arc<Mutex<Player>>, samples_per_tick: f32) -> Self {
+        Self {
+            player,
+            samples_per_tick,
+        }
+    }
+}
+
+impl ClockSource for PlayerClock {
+    fn tick(&mut self) -> Duration {
+        let mut player = self.player.lock().unwrap();
+
+        let dt = Duration::from_secs_f32(self.samples_per_tick / player.sample_rate());
+        player.update(dt);
+
+        dt
+    }
+}
+
 fn u8_to_sample(x: &u8) -> f32 {
     let x = *x as i16 - 128;
     x as f32 / 128.0
@@ -53,3 +53,166 @@ where
 
     Ok((header.sampling_rate as usize, data))
 }
+
+/// Reads an Ogg Vorbis stream from the disk, returning a tuple of the
+/// sampling rate and the audio data itself converted to mono, the same as
+/// [`read_wav`] does for PCM WAV.
+pub fn read_ogg<R>(mut reader: R) -> io::Result<(usize, Vec<f32>)>
+where
+    R: Read + Seek,
+{
+    let mut ogg = lewton::inside_ogg::OggStreamReader::new(&mut reader)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let channels = ogg.ident_hdr.audio_channels as usize;
+    let sample_rate = ogg.ident_hdr.audio_sample_rate as usize;
+
+    let mut data = Vec::new();
+    while let Some(packet) = ogg
+        .read_dec_packet_itl()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+    {
+        data.extend(packet.iter().map(i16_to_sample));
+    }
+
+    combine_channels(data.as_mut_slice(), channels);
+    data.truncate(data.len() / channels);
+
+    Ok((sample_rate, data))
+}
+
+/// Reads an audio stream of an unknown format, dispatching to [`read_wav`] or
+/// [`read_ogg`] by sniffing the stream's first 4 magic bytes (`RIFF` for WAV,
+/// `OggS` for Ogg Vorbis) and seeking back to the start before handing it to
+/// the matching decoder.
+///
+/// # Errors
+///
+/// Returns an error if the stream couldn't be read, or if its magic bytes
+/// match neither format.
+pub fn read_audio<R>(mut reader: R) -> io::Result<(usize, Vec<f32>)>
+where
+    R: Read + Seek,
+{
+    let mut magic = [0_u8; 4];
+    reader.read_exact(&mut magic)?;
+    reader.seek(std::io::SeekFrom::Start(0))?;
+
+    match &magic {
+        b"RIFF" => read_wav(reader),
+        b"OggS" => read_ogg(reader),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unrecognized audio format: expected a WAV (RIFF) or Ogg Vorbis (OggS) stream",
+        )),
+    }
+}
+
+/// Computes a per-sample amplitude envelope from raw samples: an RMS level
+/// over each `chunk_size`-sample window, repeated back out to per-sample
+/// resolution, then normalized so the loudest window reads `1.0`.
+///
+/// Mirrors the gain-tracking pipeline the legacy `gain-test` binary uses to
+/// write its `gain.wav` debug output, but returns the normalized envelope
+/// directly instead of writing it back out as audio.
+pub fn envelope(data: &[f32], chunk_size: usize) -> Vec<f32> {
+    let chunk_size = chunk_size.max(1);
+
+    let levels: Vec<f32> = data
+        .chunks(chunk_size)
+        .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect();
+
+    let peak = levels.iter().cloned().fold(0.0_f32, f32::max).max(f32::EPSILON);
+
+    levels
+        .iter()
+        .flat_map(|&level| std::iter::repeat(level / peak).take(chunk_size))
+        .take(data.len())
+        .collect()
+}
+
+/// Sample rate every [`Resampler`] converts to, and the rate [`crate::accessor::Accessor`]
+/// stores its data at, so the frequency-to-bin mapping in
+/// [`crate::analysis::Excitement::update`] means the same thing regardless
+/// of a source file's own sampling rate.
+pub const ANALYSIS_SAMPLE_RATE: usize = 44_100;
+
+/// Interpolates a single output sample at fractional position `frac` between
+/// `x1` and `x2`, using `x0` and `x3` as the neighbors on either side, via the
+/// four-point Catmull-Rom cubic.
+fn cubic_interp(x0: f32, x1: f32, x2: f32, x3: f32, frac: f32) -> f32 {
+    x1 + 0.5
+        * frac
+        * ((x2 - x0)
+            + frac * ((2.0 * x0 - 5.0 * x1 + 4.0 * x2 - x3) + frac * (3.0 * (x1 - x2) + x3 - x0)))
+}
+
+/// Streaming sample-rate converter from an arbitrary source rate to
+/// [`ANALYSIS_SAMPLE_RATE`], via Catmull-Rom cubic interpolation.
+///
+/// Feed it consecutive chunks of a stream via [`Self::push`] as they arrive
+/// (e.g. straight off [`read_wav`]/[`read_ogg`], or piecemeal from a
+/// decoder); the fractional read position and the trailing samples needed to
+/// interpolate across a chunk boundary are carried over between calls, so the
+/// resampled output is continuous across them.
+pub struct Resampler {
+    /// Source samples per output sample, i.e. `src_hz / ANALYSIS_SAMPLE_RATE`.
+    step: f32,
+    /// Fractional read position into the chunk passed to the next [`Self::push`] call.
+    pos: f32,
+    /// The last 3 samples of the previous chunk, used to interpolate the
+    /// first few output samples of the next one; starts at silence.
+    history: [f32; 3],
+}
+
+impl Resampler {
+    /// Creates a new resampler converting from `src_hz` to [`ANALYSIS_SAMPLE_RATE`].
+    pub fn new(src_hz: usize) -> Self {
+        Self {
+            step: src_hz as f32 / ANALYSIS_SAMPLE_RATE as f32,
+            pos: 0.0,
+            history: [0.0; 3],
+        }
+    }
+
+    /// Resamples `chunk`, appending the result onto `out`.
+    ///
+    /// `chunk` is treated as directly following whatever was passed to the
+    /// previous call to this method, so streaming chunks of one source join seamlessly.
+    pub fn push(&mut self, chunk: &[f32], out: &mut Vec<f32>) {
+        if chunk.is_empty() {
+            return;
+        }
+
+        let last = chunk.len() - 1;
+        let at = |i: isize| -> f32 {
+            if i < 0 {
+                self.history[(3 + i).max(0) as usize]
+            } else {
+                chunk[(i as usize).min(last)]
+            }
+        };
+
+        while (self.pos as usize) < chunk.len() {
+            let base = self.pos.floor() as isize;
+            let frac = self.pos - self.pos.floor();
+
+            out.push(cubic_interp(
+                at(base - 1),
+                at(base),
+                at(base + 1),
+                at(base + 2),
+                frac,
+            ));
+
+            self.pos += self.step;
+        }
+
+        self.pos -= chunk.len() as f32;
+
+        for &sample in chunk {
+            self.history = [self.history[1], self.history[2], sample];
+        }
+    }
+}
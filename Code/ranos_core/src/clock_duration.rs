@@ -0,0 +1,187 @@
+//! Fixed-point duration type for drift-free accumulation of frame deltas
+//! over long-running animations.
+
+use std::{
+    ops::{Add, AddAssign, Div, Mul, Sub, SubAssign},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Number of femtoseconds (10^-15 seconds) in one second.
+pub const FEMTOS_PER_SEC: u128 = 1_000_000_000_000_000;
+
+/// A duration stored as an exact count of femtoseconds, rather than
+/// [`Duration`]'s `(secs, nanos)` pair or a lossy `f32`/`f64` seconds count.
+///
+/// Intended for code that accumulates many small `dt`s over a long-running
+/// animation (see [`Timer::elapsed_exact`](crate::Timer::elapsed_exact)):
+/// summing integer femtoseconds avoids the rounding error that creeps in
+/// from repeatedly converting a running total to and from floating point,
+/// and converting to `f64` only once -- at the point a value is actually
+/// needed -- keeps playback phase-accurate and reproducible across platforms.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ClockDuration(u128);
+
+impl ClockDuration {
+    /// A zero-length duration.
+    pub const ZERO: ClockDuration = ClockDuration(0);
+
+    /// Constructs a [`ClockDuration`] from a raw femtosecond count.
+    pub fn from_femtos(femtos: u128) -> Self {
+        Self(femtos)
+    }
+
+    /// Returns the raw femtosecond count.
+    pub fn as_femtos(&self) -> u128 {
+        self.0
+    }
+
+    /// Constructs a [`ClockDuration`] from a floating-point seconds count.
+    ///
+    /// This is the one place precision can be lost -- once converted to
+    /// femtoseconds, every further `+`/`-` on the value is exact.
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self((secs * FEMTOS_PER_SEC as f64) as u128)
+    }
+
+    /// Converts to a floating-point seconds count. Prefer calling this only
+    /// at the point a value is actually needed (e.g. to feed a `powf`), not
+    /// as an intermediate step in further accumulation.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / FEMTOS_PER_SEC as f64
+    }
+
+    /// Subtracts `rhs` from `self`, returning `None` on underflow rather than panicking.
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Returns `self` modulo `modulus`, e.g. how far into a repeating
+    /// `modulus`-length cycle `self` falls. Returns `self` unchanged if
+    /// `modulus` is zero.
+    pub fn rem(&self, modulus: Self) -> Self {
+        if modulus.0 == 0 {
+            *self
+        } else {
+            Self(self.0 % modulus.0)
+        }
+    }
+
+    /// Returns `floor(self / period)`, i.e. how many whole `period`-length
+    /// intervals have elapsed -- the exact integer counterpart to dividing
+    /// two [`Duration`]s as `f64` seconds, with no rounding error to
+    /// accumulate across a long-running cycle. Returns `0` if `period` is zero.
+    pub fn periods(&self, period: Self) -> u128 {
+        if period.0 == 0 {
+            0
+        } else {
+            self.0 / period.0
+        }
+    }
+}
+
+impl From<Duration> for ClockDuration {
+    /// Lossless, since a [`Duration`]'s nanosecond resolution always
+    /// converts to a whole number of femtoseconds (`1ns == 1_000_000fs`).
+    fn from(d: Duration) -> Self {
+        Self(d.as_nanos() * 1_000_000)
+    }
+}
+
+impl From<ClockDuration> for Duration {
+    /// Lossy only below nanosecond resolution, which no platform's clock
+    /// actually produces `dt`s finer than anyway.
+    fn from(c: ClockDuration) -> Self {
+        Duration::from_nanos((c.0 / 1_000_000) as u64)
+    }
+}
+
+impl Add for ClockDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for ClockDuration {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for ClockDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for ClockDuration {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Mul<u32> for ClockDuration {
+    type Output = Self;
+
+    fn mul(self, rhs: u32) -> Self {
+        Self(self.0 * rhs as u128)
+    }
+}
+
+impl Div<u32> for ClockDuration {
+    type Output = Self;
+
+    fn div(self, rhs: u32) -> Self {
+        Self(self.0 / rhs as u128)
+    }
+}
+
+#[cfg(test)]
+mod clock_duration_test {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_duration() {
+        let d = Duration::new(3, 500_000_000);
+        assert_eq!(Duration::from(ClockDuration::from(d)), d);
+    }
+
+    #[test]
+    fn accumulates_exactly() {
+        let dt = ClockDuration::from_secs_f64(1.0 / 3.0);
+        let mut total = ClockDuration::ZERO;
+        for _ in 0..3 {
+            total += dt;
+        }
+
+        assert!((total.as_secs_f64() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rem_wraps_into_cycle() {
+        let period = ClockDuration::from_secs_f64(2.0);
+        let elapsed = ClockDuration::from_secs_f64(5.0);
+
+        assert!((elapsed.rem(period).as_secs_f64() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn periods_counts_whole_intervals() {
+        let period = ClockDuration::from_secs_f64(2.0);
+        let elapsed = ClockDuration::from_secs_f64(5.0);
+
+        assert_eq!(elapsed.periods(period), 2);
+    }
+
+    #[test]
+    fn periods_is_zero_for_zero_length_period() {
+        let elapsed = ClockDuration::from_secs_f64(5.0);
+
+        assert_eq!(elapsed.periods(ClockDuration::ZERO), 0);
+    }
+}
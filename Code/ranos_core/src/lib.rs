@@ -6,10 +6,14 @@
 #![deny(broken_intra_doc_links)]
 #![warn(clippy::all)]
 
+pub use clock_duration::ClockDuration;
+pub use lint::{Diagnostic, Severity};
 pub use max_line::MaxLine;
 pub use timer::Timer;
 
+pub mod clock_duration;
 pub mod curve;
 pub mod id;
+pub mod lint;
 pub mod max_line;
 pub mod timer;
@@ -0,0 +1,47 @@
+//! Shared types for validating builder configurations before `build()`.
+//!
+//! A builder's `validate` method runs a handful of independent rules against
+//! its current fields, each rule optionally repairing the value it flagged in
+//! place. The rule itself (what's wrong) stays separate from how severely
+//! it's reported: a rule that found and fixed a problem is a [`Warning`][Severity::Warning],
+//! while one that found a problem it can't safely repair (and so left the
+//! builder as-is) is an [`Error`][Severity::Error].
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    /// The builder had an issue that was repaired automatically; `build()`
+    /// will now succeed, but the resulting configuration differs from what
+    /// was asked for.
+    Warning,
+    /// The builder has an issue that couldn't be repaired automatically;
+    /// `build()` may panic or produce nonsensical output if called as-is.
+    Error,
+}
+
+/// One issue found (and possibly fixed) while validating a builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious this issue is.
+    pub severity: Severity,
+    /// A human-readable description of the issue, and what was done about it.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Constructs a [`Diagnostic`] reporting an issue that was repaired automatically.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    /// Constructs a [`Diagnostic`] reporting an issue that could not be repaired automatically.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
@@ -2,11 +2,41 @@
 
 use std::{
     fmt::{self, Display, Formatter},
+    thread,
     time::{Duration, Instant},
 };
 
 use serde::{Deserialize, Serialize};
 
+use crate::clock_duration::ClockDuration;
+
+/// The amount of the remaining interval before a target `dt` that [`Timer::ping`]
+/// will busy-spin through rather than sleep, to absorb OS scheduler jitter
+/// around [`thread::sleep`]'s imprecision.
+const SPIN_MARGIN: Duration = Duration::from_micros(200);
+
+/// The largest number of `target_dt`-sized logical steps [`Timer::ping`]
+/// will ever report as elapsed in one call. If a stall (GC pause, suspended
+/// process, slow frame) leaves more than this many steps banked in the
+/// accumulator, the excess is dropped and counted via
+/// [`TimerStats::dropped_steps`] instead of being reported as steps to catch
+/// up on, so a long stall can't force an unbounded burst of catch-up work --
+/// the classic "spiral of death."
+const MAX_CATCH_UP_STEPS: u32 = 5;
+
+/// An alternate source of time for a [`Timer`] to be driven by, in place of
+/// wall-clock time.
+///
+/// The canonical use is locking a [`Timer`] to an audio player's advancing
+/// sample index (`Δind / sample_rate`) rather than [`Instant::now`], so that
+/// audio-reactive animations stay frame-locked to playback even when the
+/// renderer stutters, and so that rendering can be driven deterministically
+/// offline without waiting in real time.
+pub trait ClockSource: fmt::Debug {
+    /// Advances the clock by one tick, returning the `dt` elapsed for this tick.
+    fn tick(&mut self) -> Duration;
+}
+
 /// Statistical tracker for the [`Timer`] struct. Tracks start and end
 /// time as well as the number of pings encountered by the timer.
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
@@ -14,6 +44,7 @@ pub struct TimerStats {
     start: Instant,
     end: Instant,
     pings: usize,
+    dropped_steps: usize,
 }
 
 impl TimerStats {
@@ -23,6 +54,7 @@ impl TimerStats {
             start: Instant::now(),
             end: Instant::now(),
             pings: 0,
+            dropped_steps: 0,
         }
     }
 
@@ -41,6 +73,30 @@ impl TimerStats {
         self.pings += 1;
     }
 
+    /// Notifies the stat tracker that [`Timer::ping`] had to drop `dropped`
+    /// whole `target_dt`-sized steps of accumulated catch-up time rather
+    /// than report them, because the timer had fallen more than
+    /// `MAX_CATCH_UP_STEPS` steps behind.
+    pub fn record_dropped_steps(&mut self, dropped: usize) {
+        self.dropped_steps += dropped;
+    }
+
+    /// Returns the running total of catch-up steps [`Timer::ping`] has had
+    /// to drop since the last [`Self::reset`], i.e. this run's accumulated frame debt.
+    pub fn dropped_steps(&self) -> usize {
+        self.dropped_steps
+    }
+
+    /// Returns the duration between the last calls to [`Self::start`] and [`Self::end`].
+    pub fn elapsed(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Returns the number of times this tracker has been [`ping`](Self::ping)ed.
+    pub fn pings(&self) -> usize {
+        self.pings
+    }
+
     /// Resets the timer.
     pub fn reset(&mut self) {
         *self = TimerStats::new();
@@ -56,7 +112,8 @@ impl Display for TimerStats {
             f,
             "Average ping rate: {} pings/s\n",
             self.pings as f32 / duration
-        )
+        )?;
+        write!(f, "Dropped steps: {}\n", self.dropped_steps)
     }
 }
 
@@ -65,7 +122,7 @@ fn default_instant() -> Instant {
 }
 
 /// Timer struct that will keep track of the time spent between pings.
-#[derive(Debug, Copy, Clone, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Timer {
     #[serde(skip, default = "TimerStats::new")]
     stats: TimerStats,
@@ -75,7 +132,32 @@ pub struct Timer {
     ptime: Instant,
     #[serde(skip)]
     dt: Duration,
+    /// Running total of every `dt` this timer has ever `ping`ed, accumulated
+    /// as exact femtoseconds rather than by repeatedly summing `Duration`s
+    /// converted to/from float, so long runtimes stay phase-accurate. See
+    /// [`Self::elapsed_exact`].
+    #[serde(skip)]
+    exact_elapsed: ClockDuration,
+    /// Real time banked since the last whole `target_dt` step was reported,
+    /// i.e. the fixed-timestep accumulator. Only meaningful when `target_dt`
+    /// is set.
+    #[serde(skip)]
+    accumulator: Duration,
     target_dt: Option<Duration>,
+    /// Alternate clock source, e.g. one bound to an audio player. When set,
+    /// `target_dt` is ignored and `ping`'s `dt` comes from this source instead.
+    #[serde(skip)]
+    clock: Option<Box<dyn ClockSource>>,
+}
+
+impl Clone for Timer {
+    /// Clones the timer's configuration. Note that a bound [`ClockSource`] is
+    /// *not* cloned, as clock sources are generally not duplicable (e.g. they
+    /// may hold a handle to shared, stateful audio playback); the clone falls
+    /// back to wall-clock/`target_dt` pacing.
+    fn clone(&self) -> Self {
+        Timer::new(self.target_dt)
+    }
 }
 
 impl Timer {
@@ -86,38 +168,120 @@ impl Timer {
             ctime: Instant::now(),
             ptime: Instant::now(),
             dt: Duration::new(0, 0),
+            exact_elapsed: ClockDuration::ZERO,
+            accumulator: Duration::new(0, 0),
             target_dt,
+            clock: None,
         }
     }
 
+    /// Binds an alternate [`ClockSource`] to this timer, so that subsequent
+    /// calls to [`Self::ping`] derive `dt` from it instead of wall-clock time.
+    pub fn bind_clock(mut self, clock: Box<dyn ClockSource>) -> Self {
+        self.clock = Some(clock);
+
+        self
+    }
+
     /// Allows immutable access to the internal stat tracker, typically for display purposes.
     pub fn stats(&self) -> &TimerStats {
         &self.stats
     }
 
+    /// Returns the configured target delta time, if any, that [`Self::ping`]
+    /// paces itself against.
+    pub fn target_dt(&self) -> Option<Duration> {
+        self.target_dt
+    }
+
+    /// Returns the exact total of every `dt` this timer has `ping`ed since
+    /// the last [`Self::reset`], as a [`ClockDuration`].
+    ///
+    /// Unlike summing `Duration`s converted to `f32`/`f64` seconds along the
+    /// way, this total is accumulated as exact integer femtoseconds, so
+    /// long-running callers (e.g. an animation computing its phase from
+    /// total elapsed time) can convert to float once, at the point a value
+    /// is actually needed, instead of compounding rounding error every frame.
+    pub fn elapsed_exact(&self) -> ClockDuration {
+        self.exact_elapsed
+    }
+
     /// Resets the [`Timer`] to a brand-new state, as if it were just initialized.
+    ///
+    /// Note: as with [`Clone`], a bound [`ClockSource`] is dropped by a reset.
     pub fn reset(&mut self) {
         *self = Timer::new(self.target_dt);
         self.stats.reset();
     }
 
-    /// Pings the timer, returning the amount of time that has passed since the
-    /// last ping, optionally waiting for the `target_dt` duration to pass.
+    /// Pings the timer, returning the amount of time to advance by.
+    ///
+    /// If a [`ClockSource`] is bound via [`Self::bind_clock`], `dt` comes from
+    /// it directly. Otherwise, if a `target_dt` was configured, this sleeps
+    /// for most of the remaining interval and only busy-spins through the
+    /// final [`SPIN_MARGIN`] to land precisely on the target without pegging
+    /// a core, then banks the real elapsed time in an accumulator and reports
+    /// back whole `target_dt`-sized steps (`accumulator / target_dt`),
+    /// leaving any remainder banked for next time. This keeps playback a
+    /// fixed-timestep simulation driven by real elapsed time, rather than one
+    /// tied directly to however long the previous frame took to render.
+    ///
+    /// If rendering has fallen more than [`MAX_CATCH_UP_STEPS`] steps behind
+    /// (a stalled process, a slow frame, ...), the excess is dropped rather
+    /// than returned as steps to catch up on -- see [`TimerStats::dropped_steps`]
+    /// -- so a long stall can't force an unbounded burst of catch-up work.
+    ///
+    /// A `target_dt` of [`Duration::ZERO`] is treated the same as `None`
+    /// (uncapped, reporting real elapsed time every ping) rather than
+    /// dividing by it.
     pub fn ping(&mut self) -> Duration {
         self.stats.ping();
         self.stats.end();
 
         self.ptime = self.ctime;
 
-        if let Some(target_dt) = self.target_dt {
-            while (self.ctime - self.ptime) < target_dt {
+        if let Some(clock) = self.clock.as_mut() {
+            self.dt = clock.tick();
+            self.ctime = self.ptime + self.dt;
+            self.exact_elapsed += ClockDuration::from(self.dt);
+
+            return self.dt;
+        }
+
+        if let Some(target_dt) = self.target_dt.filter(|dt| !dt.is_zero()) {
+            loop {
                 self.ctime = Instant::now();
+                let elapsed = self.ctime - self.ptime;
+
+                if elapsed >= target_dt {
+                    break;
+                }
+
+                let remaining = target_dt - elapsed;
+                if remaining > SPIN_MARGIN {
+                    thread::sleep(remaining - SPIN_MARGIN);
+                }
             }
+
+            self.accumulator += self.ctime - self.ptime;
+
+            let mut steps = (self.accumulator.as_nanos() / target_dt.as_nanos()) as u32;
+            self.accumulator -= target_dt * steps;
+
+            if steps > MAX_CATCH_UP_STEPS {
+                self.stats
+                    .record_dropped_steps((steps - MAX_CATCH_UP_STEPS) as usize);
+                steps = MAX_CATCH_UP_STEPS;
+            }
+
+            self.dt = target_dt * steps;
         } else {
             self.ctime = Instant::now();
+            self.dt = self.ctime - self.ptime;
         }
 
-        self.dt = self.ctime - self.ptime;
+        self.exact_elapsed += ClockDuration::from(self.dt);
+
         self.dt
     }
 }
@@ -2,6 +2,13 @@
 //!
 //! Provides a level of abstraction between objects that draw and generators that get drawn.
 //!
+//! After the current generator renders into the frame each tick, the
+//! display's ordered [`Filter`] pipeline runs over that same frame -- gamma
+//! correction, an intensity cap, spatial reordering, temporal resampling,
+//! and so on -- before a [`Draw`](ranos_draw::Draw) ever reads it. This is
+//! what decouples effect authoring (generators) from device-specific
+//! correction (filters).
+//!
 //! May become more generic in the future to facilitate different uses.
 
 #![warn(missing_docs)]
@@ -11,13 +18,15 @@
 use std::{
     collections::{HashMap, VecDeque},
     iter::Iterator,
+    ops::Range,
     time::Duration,
 };
 
-use ranos_filter::{Filter, FilterBuilder};
+use ranos_core::{ClockDuration, Diagnostic};
+use ranos_filter::{Filter, FilterBuilder, FilterState};
 use serde::{Deserialize, Serialize};
 
-use ranos_ds::{collections::Frame, const_val::ConstVal};
+use ranos_ds::{collections::Frame, const_val::ConstVal, rgb::BlendMode};
 use ranos_generator::{Generator, GeneratorBuilder, GeneratorState};
 
 /// Sets the type of runtime a generator has within the display. Can be a configured time, or an event trigger.
@@ -47,6 +56,12 @@ pub struct DisplayBuilder {
     looping: bool,
     generator_builders: Vec<Box<dyn GeneratorBuilder>>,
     generator_runtimes: Vec<Runtime>,
+    segments: HashMap<String, Range<usize>>,
+    segment_generator_builders: Vec<(String, Box<dyn GeneratorBuilder>)>,
+    segment_generator_runtimes: Vec<Runtime>,
+    compositing: bool,
+    layer_builders: Vec<(Box<dyn GeneratorBuilder>, u32, BlendMode)>,
+    layer_runtimes: Vec<Runtime>,
     filter_builders: Vec<Box<dyn FilterBuilder>>,
 }
 
@@ -105,6 +120,66 @@ impl DisplayBuilder {
         self
     }
 
+    /// Registers `name` as an alias for the contiguous LED range `start..end`,
+    /// so a generator can be bound to it via [`Self::segment_generator`]
+    /// instead of running over the whole strip.
+    pub fn segment(mut self, name: impl Into<String>, start: usize, end: usize) -> Self {
+        self.segments.insert(name.into(), start..end);
+
+        self
+    }
+
+    /// Add a builder for a generator that runs concurrently with the
+    /// display's main generator queue, writing only into the LED range
+    /// registered under `name` via [`Self::segment`].
+    ///
+    /// Multiple generators can be bound to the same segment name; they run
+    /// in sequence within that segment, the same way [`Self::generator`]
+    /// sequences generators over the whole strip. A generator bound to an
+    /// unregistered segment name is simply never run.
+    pub fn segment_generator(
+        mut self,
+        name: impl Into<String>,
+        builder: Box<dyn GeneratorBuilder>,
+        runtime: Runtime,
+    ) -> Self {
+        self.segment_generator_builders.push((name.into(), builder));
+        self.segment_generator_runtimes.push(runtime);
+
+        self
+    }
+
+    /// Sets whether the display renders its generators strictly one-at-a-time
+    /// in the main queue (the default, `false`), or layers them concurrently
+    /// by priority every tick (see [`Self::layer`]).
+    pub fn compositing(mut self, compositing: bool) -> Self {
+        self.compositing = compositing;
+
+        self
+    }
+
+    /// Add a builder for a generator that, in compositing mode (see
+    /// [`Self::compositing`]), renders every tick into its own layer instead
+    /// of waiting its turn in the main queue. Layers are composited into the
+    /// frame in ascending `priority` order (lowest first), each via `blend`
+    /// -- [`BlendMode::Replace`] overwrites, [`BlendMode::Additive`] sums and
+    /// clamps, [`BlendMode::Max`] takes the brighter channel, and
+    /// [`BlendMode::AlphaOver`] blends by the layer's own brightness.
+    ///
+    /// Has no effect unless [`Self::compositing`] is set to `true`.
+    pub fn layer(
+        mut self,
+        builder: Box<dyn GeneratorBuilder>,
+        runtime: Runtime,
+        priority: u32,
+        blend: BlendMode,
+    ) -> Self {
+        self.layer_builders.push((builder, priority, blend));
+        self.layer_runtimes.push(runtime);
+
+        self
+    }
+
     /// Add a builder for a filter that will be built at the same time as this builder.
     ///
     /// Note: Multiple [`FilterBuilder`]s can be added.
@@ -129,6 +204,55 @@ impl DisplayBuilder {
     pub fn build(self) -> Display {
         Display::from_builder(self)
     }
+
+    /// Checks this builder's fields for configurations that would panic or
+    /// produce nonsensical output at [`build`](Self::build) -- an
+    /// out-of-range `brightness`, or a `size` of zero -- repairing whatever
+    /// it safely can and reporting one [`Diagnostic`] per issue found.
+    pub fn validate(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let brightness = self.brightness.min(1.0).max(0.0);
+        if brightness != self.brightness {
+            diagnostics.push(Diagnostic::warning(format!(
+                "brightness was {}, outside the range [0, 1]; clamped to {}",
+                self.brightness, brightness
+            )));
+            self.brightness = brightness;
+        }
+
+        if self.size == 0 {
+            self.size = 1;
+
+            diagnostics.push(Diagnostic::warning(
+                "size was 0, which would leave nothing to draw and divide by zero in some generators; bumped to 1",
+            ));
+        }
+
+        for (name, range) in self.segments.iter_mut() {
+            if range.end > self.size {
+                let end = self.size.max(range.start);
+                diagnostics.push(Diagnostic::warning(format!(
+                    "segment \"{}\" end {} is past the display's size {}; clamped to {}",
+                    name, range.end, self.size, end
+                )));
+                range.end = end;
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Runs [`DisplayBuilder::validate`] on `builder`, returning it back along
+/// with whatever diagnostics were found.
+///
+/// This is the entry point tools should use to either report or auto-repair
+/// a configuration before calling [`DisplayBuilder::build`].
+pub fn lint_and_fix(mut builder: DisplayBuilder) -> (DisplayBuilder, Vec<Diagnostic>) {
+    let diagnostics = builder.validate();
+
+    (builder, diagnostics)
 }
 
 #[cfg(test)]
@@ -142,14 +266,14 @@ mod builder_test {
         let data = ron::ser::to_string(&builder).unwrap();
 
         let expected =
-            r#"(brightness:1,size:64,looping:false,generator_builders:[],generator_runtimes:[])"#;
+            r#"(brightness:1,size:64,looping:false,generator_builders:[],generator_runtimes:[],segments:{},segment_generator_builders:[],segment_generator_runtimes:[],compositing:false,layer_builders:[],layer_runtimes:[],filter_builders:[])"#;
         assert_eq!(data, expected);
     }
 
     #[test]
     fn test_deserializer() {
         let input =
-            r#"(brightness:1,size:64,looping:false,generator_builders:[],generator_runtimes:[])"#;
+            r#"(brightness:1,size:64,looping:false,generator_builders:[],generator_runtimes:[],segments:{},segment_generator_builders:[],segment_generator_runtimes:[],compositing:false,layer_builders:[],layer_runtimes:[],filter_builders:[])"#;
 
         let data: DisplayBuilder = ron::de::from_str(input).unwrap();
 
@@ -157,7 +281,67 @@ mod builder_test {
         assert_eq!(data.size, 64);
         assert_eq!(data.generator_builders.len(), 0);
         assert_eq!(data.generator_runtimes.len(), 0);
+        assert_eq!(data.segments.len(), 0);
+        assert_eq!(data.segment_generator_builders.len(), 0);
+        assert_eq!(data.compositing, false);
+        assert_eq!(data.layer_builders.len(), 0);
     }
+
+    #[test]
+    fn test_segment_registers_a_named_range() {
+        let builder = Display::builder().segment("ring", 0, 16);
+
+        assert_eq!(builder.segments.get("ring"), Some(&(0..16)));
+    }
+
+    #[test]
+    fn test_validate_clamps_segment_past_display_size() {
+        use crate::lint_and_fix;
+
+        let builder = Display::builder().size(8).segment("ring", 0, 16);
+
+        let (builder, diagnostics) = lint_and_fix(builder);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(builder.segments.get("ring"), Some(&(0..8)));
+    }
+
+    #[test]
+    fn test_compositing_defaults_to_false() {
+        let builder = Display::builder();
+
+        assert_eq!(builder.compositing, false);
+    }
+
+    #[test]
+    fn test_validate_fixes_out_of_range_brightness_and_zero_size() {
+        use crate::lint_and_fix;
+
+        // Bypasses the clamp in `DisplayBuilder::brightness` to simulate a
+        // config deserialized with an out-of-range value.
+        let mut builder = Display::builder();
+        builder.brightness = 2.0;
+        let builder = builder.size(0);
+
+        let (builder, diagnostics) = lint_and_fix(builder);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(builder.brightness, 1.0);
+        assert_eq!(builder.size, 1);
+    }
+}
+
+/// A single priority-ordered layer in [`Display`]'s compositing mode (see
+/// [`DisplayBuilder::compositing`]): a one-generator queue (reusing the same
+/// queue/runtime bookkeeping as [`Display`]'s segments) that renders into its
+/// own `scratch` frame every tick, then gets folded into the master frame via
+/// `blend`.
+#[derive(Debug)]
+struct Layer {
+    queue: VecDeque<(Box<dyn Generator>, Runtime)>,
+    priority: u32,
+    blend: BlendMode,
+    scratch: Frame,
 }
 
 /// Provides a level of abstraction between objects that draw and objects that generate the pixel data.
@@ -174,6 +358,12 @@ pub struct Display {
     filters: Vec<Box<dyn Filter>>,
 
     original_runtimes: ConstVal<HashMap<usize, Runtime>>,
+
+    segments: ConstVal<HashMap<String, Range<usize>>>,
+    segment_queues: HashMap<String, VecDeque<(Box<dyn Generator>, Runtime)>>,
+
+    compositing: bool,
+    layers: Vec<Layer>,
 }
 
 impl Display {
@@ -185,11 +375,29 @@ impl Display {
             looping: false,
             generator_builders: Vec::new(),
             generator_runtimes: Vec::new(),
+            segments: HashMap::new(),
+            segment_generator_builders: Vec::new(),
+            segment_generator_runtimes: Vec::new(),
+            compositing: false,
+            layer_builders: Vec::new(),
+            layer_runtimes: Vec::new(),
             filter_builders: Vec::new(),
         }
     }
 
     fn from_builder(mut builder: DisplayBuilder) -> Self {
+        let segment_generators = builder
+            .segment_generator_builders
+            .drain(0..)
+            .zip(builder.segment_generator_runtimes.drain(0..))
+            .map(|((name, gb), rt)| (name, gb.build(), rt));
+
+        let layers = builder
+            .layer_builders
+            .drain(0..)
+            .zip(builder.layer_runtimes.drain(0..))
+            .map(|((gb, priority, blend), rt)| (gb.build(), rt, priority, blend));
+
         Self::new(
             builder.brightness,
             builder.size,
@@ -200,22 +408,65 @@ impl Display {
                 .zip(builder.generator_runtimes.drain(0..))
                 .map(|(ab, rt)| (ab.build(), rt)),
             builder.filter_builders.drain(0..).map(|fb| fb.build()),
+            builder.segments.drain().collect(),
+            segment_generators,
+            builder.compositing,
+            layers,
         )
     }
 
-    fn new<G, F>(
+    fn new<G, F, SG, L>(
         brightness: f32,
         size: usize,
         looping: bool,
         generator_iter: G,
         filter_iter: F,
+        segments: HashMap<String, Range<usize>>,
+        segment_generator_iter: SG,
+        compositing: bool,
+        layer_iter: L,
     ) -> Self
     where
         G: Iterator<Item = (Box<dyn Generator>, Runtime)>,
         F: Iterator<Item = Box<dyn Filter>>,
+        SG: Iterator<Item = (String, Box<dyn Generator>, Runtime)>,
+        L: Iterator<Item = (Box<dyn Generator>, Runtime, u32, BlendMode)>,
     {
         let generators: VecDeque<_> = generator_iter.collect();
-        let runtimes = generators.iter().map(|(g, rt)| (g.id(), *rt)).collect();
+
+        let mut segment_queues: HashMap<String, VecDeque<(Box<dyn Generator>, Runtime)>> =
+            HashMap::new();
+        for (name, gen, rt) in segment_generator_iter {
+            segment_queues
+                .entry(name)
+                .or_insert_with(VecDeque::new)
+                .push_back((gen, rt));
+        }
+
+        let mut layers: Vec<Layer> = layer_iter
+            .map(|(gen, rt, priority, blend)| Layer {
+                queue: VecDeque::from(vec![(gen, rt)]),
+                priority,
+                blend,
+                scratch: Frame::new(brightness, size),
+            })
+            .collect();
+        layers.sort_by_key(|l| l.priority);
+
+        let runtimes = generators
+            .iter()
+            .map(|(g, rt)| (g.id(), *rt))
+            .chain(
+                segment_queues
+                    .values()
+                    .flat_map(|q| q.iter().map(|(g, rt)| (g.id(), *rt))),
+            )
+            .chain(
+                layers
+                    .iter()
+                    .flat_map(|l| l.queue.iter().map(|(g, rt)| (g.id(), *rt))),
+            )
+            .collect();
 
         Display {
             id: ranos_core::id::generate(),
@@ -227,6 +478,12 @@ impl Display {
             filters: filter_iter.collect(),
 
             original_runtimes: ConstVal::new(runtimes),
+
+            segments: ConstVal::new(segments),
+            segment_queues,
+
+            compositing,
+            layers,
         }
     }
 
@@ -250,21 +507,59 @@ impl Display {
         self.generators.pop_front();
     }
 
-    /// Renders a frame from the current generator.
+    /// Renders a frame from the current generator, then runs it through this
+    /// display's [`Filter`] pipeline, in the order they were added via
+    /// [`DisplayBuilder::filter`]/[`DisplayBuilder::filter_iter`], before
+    /// anything downstream (e.g. a [`Draw`](ranos_draw::Draw)) reads the frame.
+    ///
+    /// When a [`Runtime::Time`] generator's remaining time is shorter than
+    /// `dt`, the leftover is not dropped: the next generator is driven
+    /// immediately with the remainder, so a long playlist's total elapsed
+    /// time stays exact instead of drifting by up to one tick per generator
+    /// boundary. The remainder is tracked as a [`ClockDuration`] rather than
+    /// repeatedly `checked_sub`ing [`Duration`]s, so chaining through many
+    /// short-lived generators in a single tick accumulates no rounding error.
     pub fn render_frame(&mut self, dt: Duration) -> DisplayState {
+        self.render_frame_step(dt, dt)
+    }
+
+    /// Implementation of [`Self::render_frame`], threading the tick's true
+    /// total elapsed time (`total_dt`) separately from `dt` -- the slice
+    /// actually driving this recursive step once a `Runtime::Time` generator
+    /// boundary is crossed mid-tick. Segments and filters always advance by
+    /// `total_dt` via [`Self::finish_frame`], regardless of how many
+    /// generator boundaries were crossed getting there, so they never see
+    /// less than the tick's real wall-clock `dt`.
+    fn render_frame_step(&mut self, dt: Duration, total_dt: Duration) -> DisplayState {
+        if self.compositing {
+            return self.render_layers_tick(total_dt);
+        }
+
         if let Some((mut anim, rt)) = self.generators.pop_front() {
             match anim.render_frame(&mut self.frame, dt) {
                 GeneratorState::Ok => {
                     match rt {
                         Runtime::Time(t) => {
-                            if let Some(t) = t.checked_sub(dt) {
-                                self.generators.push_front((anim, Runtime::Time(t)));
-                            } else {
-                                if self.looping {
-                                    self.generators.push_back((anim, rt));
+                            let remaining = ClockDuration::from(t).checked_sub(ClockDuration::from(dt));
+
+                            match remaining {
+                                Some(remaining) => {
+                                    self.generators.push_front((anim, Runtime::Time(remaining.into())));
+                                }
+                                None => {
+                                    if self.looping {
+                                        self.generators.push_back((anim, rt));
+                                    }
+
+                                    // The current generator finished mid-tick; drive the
+                                    // next one with the leftover time immediately instead
+                                    // of waiting until the following tick, so total
+                                    // elapsed time stays exact.
+                                    let leftover = ClockDuration::from(dt) - ClockDuration::from(t);
+                                    if leftover.as_femtos() > 0 && !self.generators.is_empty() {
+                                        return self.render_frame_step(leftover.into(), total_dt);
+                                    }
                                 }
-                                // // Render the next frame with the remaining `dt` of the current frame.
-                                // self.render_frame(dt.checked_sub(t).unwrap());
                             }
                         }
                         Runtime::Trigger => {
@@ -272,18 +567,151 @@ impl Display {
                         }
                     };
 
-                    DisplayState::Ok
+                    self.finish_frame(total_dt)
                 }
-                GeneratorState::ErrRetry => self.render_frame(dt),
+                GeneratorState::ErrRetry => self.render_frame_step(dt, total_dt),
                 GeneratorState::ErrSkip => {
                     self.generators.push_front((anim, rt));
 
-                    DisplayState::Ok
+                    self.finish_frame(total_dt)
                 }
                 GeneratorState::ErrFatal => DisplayState::Err,
             }
+        } else if let DisplayState::Err = self.render_segments(total_dt) {
+            DisplayState::Err
         } else {
-            DisplayState::Done
+            let state = self.apply_filters(total_dt);
+
+            if self.segment_queues.values().all(|q| q.is_empty()) {
+                DisplayState::Done
+            } else {
+                state
+            }
+        }
+    }
+
+    /// Advances every segment-bound generator queue by one tick, then runs
+    /// the display's [`Filter`] pipeline over the composited result.
+    ///
+    /// This is the shared tail of [`Self::render_frame`]'s `Ok`/`ErrSkip`
+    /// branches: the main generator queue has already rendered into
+    /// `self.frame` for this tick by the time this runs.
+    fn finish_frame(&mut self, dt: Duration) -> DisplayState {
+        if let DisplayState::Err = self.render_segments(dt) {
+            return DisplayState::Err;
+        }
+
+        self.apply_filters(dt)
+    }
+
+    /// Renders one tick of compositing mode (see
+    /// [`DisplayBuilder::compositing`]): every [`Layer`] renders into its own
+    /// scratch frame, then the layers are folded into the master frame in
+    /// ascending `priority` order (lowest first), each via its own
+    /// [`BlendMode`]. Unlike the main queue, a finished (non-looping) layer's
+    /// scratch frame is left as-is and keeps contributing its last rendered
+    /// frame to every subsequent tick, rather than being removed.
+    fn render_layers_tick(&mut self, dt: Duration) -> DisplayState {
+        self.frame = Frame::new(self.frame.brightness(), self.frame.len());
+
+        for layer in self.layers.iter_mut() {
+            if !layer.queue.is_empty() {
+                let state =
+                    advance_segment_generator(&mut layer.queue, &mut layer.scratch, dt, self.looping);
+
+                if let DisplayState::Err = state {
+                    return DisplayState::Err;
+                }
+            }
+
+            for (dst, src) in self
+                .frame
+                .as_mut_slice()
+                .iter_mut()
+                .zip(layer.scratch.as_slice().iter())
+            {
+                *dst = dst.blend(*src, layer.blend);
+            }
+        }
+
+        self.apply_filters(dt)
+    }
+
+    /// Advances every segment-bound generator queue by one tick, rendering
+    /// each into a scratch [`Frame`] sized to its segment's range before
+    /// compositing the result back into the corresponding window of the
+    /// master frame. Runs alongside, not instead of, the main generator
+    /// queue, so a segment and the whole-strip queue can animate the same
+    /// tick independently.
+    ///
+    /// A segment's registered range is clamped against the frame's actual
+    /// length before it's sliced, in case the display was built without
+    /// routing its builder through [`lint_and_fix`] first -- `build()` alone
+    /// doesn't validate, so a segment registered past the display's `size`
+    /// would otherwise panic here instead of just being harmlessly clipped.
+    fn render_segments(&mut self, dt: Duration) -> DisplayState {
+        for (name, queue) in self.segment_queues.iter_mut() {
+            if queue.is_empty() {
+                continue;
+            }
+
+            let range = match self.segments.get().get(name) {
+                Some(range) => range.clone(),
+                None => continue,
+            };
+
+            let len = self.frame.len();
+            if range.start >= len {
+                continue;
+            }
+            let range = range.start..range.end.min(len);
+
+            let mut scratch = Frame::new(self.frame.brightness(), range.len());
+            scratch
+                .as_mut_slice()
+                .copy_from_slice(&self.frame.as_slice()[range.clone()]);
+
+            let state = advance_segment_generator(queue, &mut scratch, dt, self.looping);
+
+            self.frame.as_mut_slice()[range].copy_from_slice(scratch.as_slice());
+
+            if let DisplayState::Err = state {
+                return DisplayState::Err;
+            }
+        }
+
+        DisplayState::Ok
+    }
+
+    /// Runs each configured filter, in order, over the frame the current
+    /// generator just rendered.
+    ///
+    /// A filter's [`FilterState::ErrSkip`] keeps whatever that filter left in
+    /// the frame and moves on to the next filter; [`FilterState::ErrRetry`]
+    /// re-runs the same filter against its own output; [`FilterState::ErrFatal`]
+    /// aborts the render entirely.
+    fn apply_filters(&mut self, dt: Duration) -> DisplayState {
+        for filter in self.filters.iter_mut() {
+            loop {
+                match filter.filter_frame(&mut self.frame, dt) {
+                    FilterState::Ok | FilterState::ErrSkip => break,
+                    FilterState::ErrRetry => continue,
+                    FilterState::ErrFatal => return DisplayState::Err,
+                }
+            }
+        }
+
+        DisplayState::Ok
+    }
+
+    /// Notifies every configured [`Filter`] of this run's latest
+    /// quality-of-service proportion (see [`Filter::qos`]), typically
+    /// computed by the owning [`Draw`](ranos_draw::Draw) from how long the
+    /// last [`Self::render_frame`] actually took versus its `target_dt`, so
+    /// filters that can shed work under load do so before the next frame.
+    pub fn qos(&mut self, proportion: f64) {
+        for filter in self.filters.iter_mut() {
+            filter.qos(proportion);
         }
     }
 
@@ -293,5 +721,67 @@ impl Display {
             g.reset();
             *rt = *self.original_runtimes.get().get(&g.id()).unwrap();
         }
+
+        for queue in self.segment_queues.values_mut() {
+            for (g, rt) in queue.iter_mut() {
+                g.reset();
+                *rt = *self.original_runtimes.get().get(&g.id()).unwrap();
+            }
+        }
+
+        for layer in self.layers.iter_mut() {
+            for (g, rt) in layer.queue.iter_mut() {
+                g.reset();
+                *rt = *self.original_runtimes.get().get(&g.id()).unwrap();
+            }
+        }
+    }
+}
+
+/// Advances a single segment's generator queue by one tick: renders the
+/// front generator into `frame` (a scratch buffer sized to the segment),
+/// then retires it, holds it for the remaining `Runtime::Time`, or requeues
+/// it if `looping`.
+///
+/// Unlike the main queue's [`Runtime::Trigger`] handling (see
+/// [`Display::trigger_next_generator`]), a segment has no externally-driven
+/// trigger to wait on, so a `Trigger`-runtime segment generator simply runs
+/// once and retires (or requeues, if looping).
+fn advance_segment_generator(
+    queue: &mut VecDeque<(Box<dyn Generator>, Runtime)>,
+    frame: &mut Frame,
+    dt: Duration,
+    looping: bool,
+) -> DisplayState {
+    if let Some((mut gen, rt)) = queue.pop_front() {
+        match gen.render_frame(frame, dt) {
+            GeneratorState::Ok => {
+                match rt {
+                    Runtime::Time(t) => {
+                        if let Some(t) = t.checked_sub(dt) {
+                            queue.push_front((gen, Runtime::Time(t)));
+                        } else if looping {
+                            queue.push_back((gen, rt));
+                        }
+                    }
+                    Runtime::Trigger => {
+                        if looping {
+                            queue.push_back((gen, rt));
+                        }
+                    }
+                }
+
+                DisplayState::Ok
+            }
+            GeneratorState::ErrRetry => advance_segment_generator(queue, frame, dt, looping),
+            GeneratorState::ErrSkip => {
+                queue.push_front((gen, rt));
+
+                DisplayState::Ok
+            }
+            GeneratorState::ErrFatal => DisplayState::Err,
+        }
+    } else {
+        DisplayState::Done
     }
 }
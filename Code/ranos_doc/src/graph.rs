@@ -0,0 +1,136 @@
+//! A small Graphviz DOT writer, used to visualize a configured
+//! `Draw -> Display -> Generator` pipeline alongside the `.ron` files
+//! [`write_base_rons`][crate::write_base_rons] produces.
+//!
+//! Each example's diagram is written by hand in its own `*_dot` function,
+//! mirroring the builder chain of the matching `.ron` example; it is not
+//! derived from the built `Box<dyn DrawBuilder>`, which is not walked.
+
+use std::fmt::Write as _;
+
+/// Whether a [`GraphWriter`] emits an undirected `graph` or directed `digraph`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// A directed graph, written as `digraph`, whose edges use `->`.
+    Digraph,
+    /// An undirected graph, written as `graph`, whose edges use `--`.
+    Graph,
+}
+
+impl Kind {
+    /// The DOT keyword naming this graph kind (`"digraph"` or `"graph"`).
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Kind::Digraph => "digraph",
+            Kind::Graph => "graph",
+        }
+    }
+
+    /// The DOT edge operator for this graph kind (`"->"` for a digraph, `"--"` for a graph).
+    pub fn edgeop(&self) -> &'static str {
+        match self {
+            Kind::Digraph => "->",
+            Kind::Graph => "--",
+        }
+    }
+}
+
+/// A single node in a [`GraphWriter`]: a unique id and the label drawn inside it.
+struct Node {
+    id: String,
+    label: String,
+}
+
+/// Accumulates the nodes and edges of one pipeline diagram, then renders
+/// them as a Graphviz `.dot` document via [`Self::to_dot`].
+pub struct GraphWriter {
+    kind: Kind,
+    nodes: Vec<Node>,
+    edges: Vec<(String, String)>,
+    next_id: usize,
+}
+
+impl GraphWriter {
+    /// Creates an empty writer for a graph of the given `kind`.
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds a node labeled `name` (plus `details`, on its own line, if
+    /// non-empty), returning an id to pass to [`Self::edge`].
+    pub fn node(&mut self, name: &str, details: &str) -> String {
+        let id = format!("n{}", self.next_id);
+        self.next_id += 1;
+
+        let label = if details.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}\\n{}", name, details)
+        };
+
+        self.nodes.push(Node { id: id.clone(), label });
+
+        id
+    }
+
+    /// Adds an edge from `from` to `to` (both ids previously returned by [`Self::node`]).
+    pub fn edge(&mut self, from: &str, to: &str) {
+        self.edges.push((from.to_owned(), to.to_owned()));
+    }
+
+    /// Renders the accumulated nodes and edges as a named Graphviz document.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut out = String::new();
+
+        writeln!(out, "{} {} {{", self.kind.keyword(), name).unwrap();
+        for node in &self.nodes {
+            writeln!(out, "    {} [label=\"{}\"];", node.id, node.label).unwrap();
+        }
+        for (from, to) in &self.edges {
+            writeln!(out, "    {} {} {};", from, self.kind.edgeop(), to).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod graph_test {
+    use super::*;
+
+    #[test]
+    fn test_edgeop() {
+        assert_eq!(Kind::Digraph.edgeop(), "->");
+        assert_eq!(Kind::Graph.edgeop(), "--");
+        assert_eq!(Kind::Digraph.keyword(), "digraph");
+        assert_eq!(Kind::Graph.keyword(), "graph");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("APA102CPiDraw", "Drives an APA102C/SK9822 chain over SPI");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        let rainbow = g.node("Rainbow", "");
+        g.edge(&display, &rainbow);
+
+        assert_eq!(
+            g.to_dot("pipeline"),
+            "digraph pipeline {\n    \
+             n0 [label=\"APA102CPiDraw\\nDrives an APA102C/SK9822 chain over SPI\"];\n    \
+             n1 [label=\"Display\"];\n    \
+             n2 [label=\"Rainbow\"];\n    \
+             n0 -> n1;\n    \
+             n1 -> n2;\n\
+             }\n"
+        );
+    }
+}
@@ -6,9 +6,25 @@
 #![deny(broken_intra_doc_links)]
 #![warn(clippy::all)]
 
+pub mod graph;
+
+/// Writes a Graphviz `.dot` diagram of each example `Draw -> Display ->
+/// Generator` pipeline to `Code/ignore`, one file per example next to the
+/// `.ron` file [`write_base_rons`] writes for it.
+///
+/// Note: the folder `ignore` as well as its sub-folder `draw` must exist
+/// before this is run.
+pub fn write_pipeline_dots() {
+    draw::null_dot();
+    draw::pi_dot();
+    draw::term_dot();
+    draw::file_dot();
+    draw::draw_dot();
+}
+
 /// Writes default config files to `Code/ignore`.
 ///
-/// Note: the folder `ignore` as well as its sub-folders `generator`, `display`, and `draw` must all exist before this is run.
+/// Note: the folder `ignore` as well as its sub-folders `generator`, `display`, `draw`, and `animation` must all exist before this is run.
 pub fn write_base_rons() {
     generator::breath();
     generator::cycle();
@@ -22,7 +38,11 @@ pub fn write_base_rons() {
     draw::null();
     draw::pi();
     draw::term();
+    draw::image();
+    draw::file();
     draw::draw();
+
+    animation::excitement_bars();
 }
 
 pub(self) mod generator {
@@ -267,12 +287,20 @@ pub(self) mod display {
 }
 
 pub(self) mod draw {
-    use std::{fs::File, time::Duration};
+    use std::{fs::File, io::Write, time::Duration};
 
     use ranos_generator::{Breath, ColorOrder, Rainbow};
     use ranos_core::Timer;
     use ranos_display::Display;
-    use ranos_draw::{APA102CPiDraw, DrawBuilder, NullDraw, TermDraw};
+    use ranos_draw::{APA102CPiDraw, DrawBuilder, FileDraw, ImageDraw, NullDraw, TermDraw};
+
+    use crate::graph::{GraphWriter, Kind};
+
+    fn write_dot(path: &str, name: &str, g: &GraphWriter) {
+        let mut file = File::create(path).unwrap();
+
+        file.write_all(g.to_dot(name).as_bytes()).unwrap();
+    }
 
     pub(super) fn null() {
         let pretty = ron::ser::PrettyConfig::default();
@@ -291,6 +319,15 @@ pub(self) mod draw {
         }
     }
 
+    pub(super) fn null_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("NullDraw", "Drawer with no output");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        write_dot("ignore/draw/null.dot", "null", &g);
+    }
+
     pub(super) fn pi() {
         let pretty = ron::ser::PrettyConfig::default();
 
@@ -309,6 +346,15 @@ pub(self) mod draw {
         }
     }
 
+    pub(super) fn pi_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("APA102CPiDraw", "Drives an APA102C/SK9822 chain over SPI");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        write_dot("ignore/draw/pi.dot", "pi", &g);
+    }
+
     pub(super) fn term() {
         let pretty = ron::ser::PrettyConfig::default();
 
@@ -328,6 +374,62 @@ pub(self) mod draw {
         }
     }
 
+    pub(super) fn term_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("TermDraw", "Emulates LEDs in a terminal");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        write_dot("ignore/draw/term.dot", "term", &g);
+    }
+
+    pub(super) fn image() {
+        let pretty = ron::ser::PrettyConfig::default();
+
+        // image
+        {
+            let file = File::create("ignore/draw/image.ron").unwrap();
+
+            ron::ser::to_writer_pretty(
+                file,
+                &(ImageDraw::builder()
+                    .path("capture.gif".into())
+                    .timer(Timer::new(Some(Duration::from_secs_f64(1.0 / 60.0))))
+                    as Box<dyn DrawBuilder>),
+                pretty.clone(),
+            )
+            .unwrap();
+        }
+    }
+
+    pub(super) fn file() {
+        let pretty = ron::ser::PrettyConfig::default();
+
+        // file
+        {
+            let file = File::create("ignore/draw/file.ron").unwrap();
+
+            ron::ser::to_writer_pretty(
+                file,
+                &(FileDraw::builder()
+                    .path("capture.ranrec".into())
+                    .timer(Timer::new(Some(Duration::from_secs_f64(1.0 / 60.0))))
+                    as Box<dyn DrawBuilder>),
+                pretty.clone(),
+            )
+            .unwrap();
+        }
+    }
+
+    pub(super) fn file_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("FileDraw", "Captures frames to a binary recording");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        write_dot("ignore/draw/file.dot", "file", &g);
+    }
+
     pub(super) fn draw() {
         let pretty = ron::ser::PrettyConfig::default();
 
@@ -363,4 +465,47 @@ pub(self) mod draw {
             .unwrap();
         }
     }
+
+    pub(super) fn draw_dot() {
+        let mut g = GraphWriter::new(Kind::Digraph);
+        let draw = g.node("APA102CPiDraw", "Drives an APA102C/SK9822 chain over SPI");
+        let display = g.node("Display", "");
+        g.edge(&draw, &display);
+
+        let rainbow = g.node("Rainbow", "");
+        g.edge(&display, &rainbow);
+
+        let breath = g.node("Breath", "");
+        g.edge(&display, &breath);
+
+        write_dot("ignore/draw/draw_full.dot", "draw_full", &g);
+    }
+}
+
+pub(self) mod animation {
+    use std::{fs::File, time::Duration};
+
+    use ranos_animation::{AnimationBuilder, ColorMap, ExcitementBars};
+
+    pub(super) fn excitement_bars() {
+        let pretty = ron::ser::PrettyConfig::default();
+
+        // spectrum
+        {
+            let file = File::create("ignore/animation/spectrum.ron").unwrap();
+
+            ron::ser::to_writer_pretty(
+                file,
+                &(ExcitementBars::builder()
+                    .runtime(Duration::from_secs_f64(16.0))
+                    .num_bins(32)
+                    .bin_range((0.0, 1.0))
+                    .scalar(1.0)
+                    .decay(0.9)
+                    .color_map(ColorMap::Hue) as Box<dyn AnimationBuilder>),
+                pretty,
+            )
+            .unwrap();
+        }
+    }
 }
@@ -0,0 +1,197 @@
+//! Host-agnostic implementation of the APA102C/SK9822 protocol, parameterized
+//! over any [`embedded_hal::spi::SpiBus`] rather than tied to the Raspberry
+//! Pi's GPIO pins. The same start-frame/per-LED-header/end-frame framing that
+//! [`pi_draw`][crate::pi_draw]'s bit-banged `APA102CPiDraw` used to drive by
+//! toggling a clock pin twice per bit is instead built into a single buffer
+//! and handed to the SPI peripheral, which clocks it out in hardware -- both
+//! faster and portable to any embedded-hal host (rp2040, STM32, etc.), not
+//! just `target_os = "linux"`.
+//!
+//! Because [`Apa102Draw`] is generic over its `SPI` type, it can't itself
+//! implement `#[typetag::serde]`'s [`DrawBuilder`][crate::DrawBuilder] --
+//! typetag needs a concrete, nameable type to register for discovery. A
+//! concrete host wraps it in a registrable type instead, the way
+//! [`APA102CPiDraw`][crate::pi_draw::APA102CPiDraw] wraps an
+//! [`Apa102Draw<Spi>`](Apa102Draw) around `rppal`'s hardware SPI bus.
+
+use std::collections::HashMap;
+
+use embedded_hal::spi::SpiBus;
+
+use ranos_core::{timer::TimerStats, Timer};
+use ranos_ds::{collections::Frame, rgb::RGBOrder};
+use ranos_display::{Display, DisplayBuilder, DisplayState};
+
+use crate::diagnostics::RenderDiagnostics;
+use crate::{Draw, SIGINT};
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+/// Encodes `frame`, scaled by its own brightness, into a single APA102C/SK9822
+/// wire buffer: a 4-byte all-zero start frame, one `0xE0 | brightness` header
+/// plus a BGR triplet per LED, and a trailing `len / 16` zero bytes to end the
+/// frame and fully clock out the last LED's latch.
+pub fn encode_frame(frame: &Frame, brightness: u8) -> Vec<u8> {
+    let len = frame.len();
+    let mut buf = Vec::with_capacity(4 + len * 4 + (len >> 4));
+
+    buf.extend_from_slice(&[0x00; 4]);
+
+    let header = 0xE0 | brightness;
+    for led in frame.iter() {
+        let (blue, green, red) = led.scale(frame.brightness()).as_tuple(RGBOrder::BGR);
+        buf.push(header);
+        buf.push(blue);
+        buf.push(green);
+        buf.push(red);
+    }
+
+    buf.extend(std::iter::repeat(0x00).take(len >> 4));
+
+    buf
+}
+
+/// Encodes `len` all-black, zero-brightness LEDs the same way [`encode_frame`]
+/// does, for blacking out a strip on shutdown.
+fn encode_black(len: usize) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + len * 4 + (len >> 4));
+
+    buf.extend_from_slice(&[0x00; 4]);
+    for _ in 0..len {
+        buf.extend_from_slice(&[0xE0, 0x00, 0x00, 0x00]);
+    }
+    buf.extend(std::iter::repeat(0x00).take(len >> 4));
+
+    buf
+}
+
+/// Drives the APA102C/SK9822 protocol over any [`embedded_hal::spi::SpiBus`].
+///
+/// See the [module docs](self) for why this type doesn't implement
+/// [`DrawBuilder`][crate::DrawBuilder] directly.
+#[derive(Debug)]
+pub struct Apa102Draw<SPI> {
+    spi: SPI,
+    brightness: u8,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+
+    num: usize,
+}
+
+impl<SPI: SpiBus> Apa102Draw<SPI> {
+    /// Constructs an [`Apa102Draw`] driving `spi`, at the given 5-bit global
+    /// `brightness` (should be in the range `[0, 31]`), from the displays
+    /// produced by `display_iter`.
+    pub fn new<I>(spi: SPI, brightness: u8, timer: Timer, display_iter: I) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut num = 0;
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                num += disp.frame_len();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            spi,
+            brightness,
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+
+            num,
+        }
+    }
+
+    fn write_frame(&mut self, display_id: usize) {
+        let bytes = {
+            let frame = self.displays.get(&display_id).unwrap().0.frame();
+            encode_frame(frame, self.brightness)
+        };
+
+        self.spi.write(&bytes).unwrap();
+    }
+
+    /// Sets all LEDs up to `len` to black with 0 brightness, effectively
+    /// turning the LEDs off. Used in system shutdown code, as well as
+    /// `SIGINT` handling.
+    fn stop(&mut self, len: usize) {
+        self.spi.write(&encode_black(len)).unwrap();
+    }
+}
+
+impl<SPI: SpiBus> Draw for Apa102Draw<SPI> {
+    fn run(&mut self) {
+        // Reset timer and stats to track just this run
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
+
+impl<SPI: SpiBus> Drop for Apa102Draw<SPI> {
+    /// For our eye's sake, this custom `Drop` implementation ensures that when
+    /// the LED controller is stopped, the LEDs will be set to off so they don't blind anyone.
+    fn drop(&mut self) {
+        self.stop(self.num);
+    }
+}
@@ -0,0 +1,237 @@
+//! Composites several [`Display`]s' frames into one output frame each tick,
+//! instead of a single display's queue of generators running one at a time --
+//! see [`CompositeDraw`].
+
+use std::{collections::VecDeque, time::Instant};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+use ranos_ds::{
+    collections::Frame,
+    rgb::{BlendMode, RGB},
+};
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// Builder for [`CompositeDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CompositeDraw")]
+pub struct CompositeDrawBuilder {
+    timer: Timer,
+    layer_displays: VecDeque<DisplayBuilder>,
+    layer_modes: VecDeque<BlendMode>,
+}
+
+impl CompositeDrawBuilder {
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Adds a layer: a builder for a display that will be built at the same
+    /// time as this builder, composited into the output with the given
+    /// [`BlendMode`].
+    ///
+    /// Layers are blended in the order they're added, each painted over the
+    /// layers added before it. Be sure to add generators to the display
+    /// builder before adding it as a layer, as it will be inaccessible afterwards.
+    pub fn layer(mut self: Box<Self>, display: DisplayBuilder, mode: BlendMode) -> Box<Self> {
+        self.layer_displays.push_back(display);
+        self.layer_modes.push_back(mode);
+
+        self
+    }
+
+    /// Constructs a [`CompositeDraw`] object.
+    pub fn build(self: Box<Self>) -> CompositeDraw {
+        CompositeDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for CompositeDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    /// Equivalent to [`CompositeDrawBuilder::layer`] with [`BlendMode::Additive`],
+    /// since the [`DrawBuilder`] trait has no notion of a per-layer blend
+    /// mode. Use [`CompositeDrawBuilder::layer`] directly to pick a different mode.
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.layer(display, BlendMode::Additive)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{CompositeDraw, CompositeDrawBuilder};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = CompositeDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(timer:(target_dt:None),layer_displays:[],layer_modes:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(timer:(target_dt:None),layer_displays:[],layer_modes:[])"#;
+        let data: CompositeDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.layer_displays.len(), 0);
+        assert_eq!(data.layer_modes.len(), 0);
+    }
+}
+
+/// Drawer that advances several layered [`Display`]s with the same `dt` each
+/// tick and blends their frames into one output frame, instead of running
+/// generators one after another from a single queue.
+///
+/// This is what lets, say, a `Strobe` generator be layered on top of a
+/// `Rainbow` generator instead of playing them back-to-back: give each its
+/// own [`Display`] via [`CompositeDrawBuilder::layer`], and pick the
+/// [`BlendMode`] each layer composites with. Layers are blended in the order
+/// they were added, each painted over the merge of all layers before it.
+///
+/// [`CompositeDraw`] has no output of its own -- see [`Self::frame`] for
+/// reading the merged result each tick; a further `Draw` would typically
+/// wrap this to actually stream it somewhere.
+///
+/// To create a [`CompositeDraw`] object, use the associated
+/// [builder](CompositeDrawBuilder), accessed via [`CompositeDraw::builder()`].
+#[derive(Debug)]
+pub struct CompositeDraw {
+    layers: Vec<(Display, BlendMode, bool)>,
+    frame: Frame,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl CompositeDraw {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<CompositeDrawBuilder> {
+        Box::new(CompositeDrawBuilder {
+            timer: Timer::new(None),
+            layer_displays: VecDeque::new(),
+            layer_modes: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<CompositeDrawBuilder>) -> Self {
+        Self::new(
+            builder.timer,
+            builder
+                .layer_displays
+                .drain(0..)
+                .zip(builder.layer_modes.drain(0..)),
+        )
+    }
+
+    fn new<I>(timer: Timer, layer_iter: I) -> Self
+    where
+        I: Iterator<Item = (DisplayBuilder, BlendMode)>,
+    {
+        let layers: Vec<(Display, BlendMode, bool)> = layer_iter
+            .map(|(b, mode)| (b.build(), mode, false))
+            .collect();
+
+        let size = layers.first().map(|(d, _, _)| d.frame_len()).unwrap_or(1);
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            layers,
+            frame: Frame::new(1.0, size),
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Returns the merged frame produced by the last call to [`Draw::run`]'s
+    /// render loop, after all layers have been blended together.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    /// Re-blends [`Self::frame`] from scratch: starts from black, then folds
+    /// each layer's own frame (scaled by its own brightness) over the result
+    /// so far using that layer's [`BlendMode`].
+    fn composite(&mut self) {
+        let len = self.frame.len();
+        let merged = self.frame.as_mut_slice();
+        for led in merged.iter_mut() {
+            *led = RGB::new();
+        }
+
+        for (display, mode, _) in &self.layers {
+            let layer = display.frame();
+
+            for i in 0..len.min(layer.len()) {
+                merged[i] = merged[i].blend(layer.as_slice()[i].scale(layer.brightness()), *mode);
+            }
+        }
+    }
+}
+
+impl Draw for CompositeDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.layers.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.layers.len() {
+                let frame_start = Instant::now();
+
+                let (display, _, has_finished) = self.layers.get_mut(i).unwrap();
+
+                if !*has_finished {
+                    match display.render_frame(dt) {
+                        DisplayState::Ok => (),
+                        DisplayState::Done => {
+                            *has_finished = true;
+                            num_finished += 1;
+                        }
+                        DisplayState::Err => return,
+                    }
+                }
+
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                display.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+
+            self.composite();
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -0,0 +1,172 @@
+//! Per-frame render-latency diagnostics for a [`Draw`](crate::Draw) implementation's `run` loop.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use hdrhistogram::{
+    serialization::{Deserializer as HistogramDeserializer, Serializer as HistogramSerializer, V2Serializer},
+    Histogram,
+};
+use serde::{
+    de::Error as DeError, ser::Error as SerError, Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The longest render latency the backing histogram can record, beyond which
+/// samples are clamped rather than dropped. A single frame taking this long
+/// already means the target frame rate was missed many times over, so
+/// further precision above this point isn't useful.
+const MAX_TRACKED_LATENCY_MICROS: u64 = Duration::from_secs(10).as_micros() as u64;
+
+/// Tracks how long each `render_frame` call in a [`Draw`](crate::Draw)'s
+/// `run` loop actually took versus the [`Timer`](ranos_core::Timer)'s
+/// `target_dt`, in an HDR histogram, so a run can be queried afterwards for
+/// p50/p99/max render latency and how many frames missed their deadline.
+/// Also tracks the latest quality-of-service proportion (see [`Self::record`]),
+/// the same signal fed back to the running [`Display`](ranos_display::Display)
+/// each frame so animations/filters can shed work under load.
+#[derive(Debug)]
+pub struct RenderDiagnostics {
+    histogram: Histogram<u64>,
+    missed_deadlines: usize,
+    target_dt: Option<Duration>,
+    last_proportion: f64,
+}
+
+impl RenderDiagnostics {
+    /// Constructs a new, empty diagnostics tracker. `target_dt` is the
+    /// deadline each recorded sample is compared against to count missed
+    /// deadlines; pass the same value the drawer's [`Timer`](ranos_core::Timer)
+    /// was configured with.
+    pub fn new(target_dt: Option<Duration>) -> Self {
+        Self {
+            // 3 significant figures is the usual HDR histogram default, plenty for frame-time-scale latencies.
+            histogram: Histogram::new_with_bounds(1, MAX_TRACKED_LATENCY_MICROS, 3).unwrap(),
+            missed_deadlines: 0,
+            target_dt,
+            last_proportion: 1.0,
+        }
+    }
+
+    /// Records how long a single `render_frame` call (plus any device write
+    /// that followed it) took, counting it as a missed deadline if it
+    /// exceeded `target_dt`, and returns this frame's quality-of-service
+    /// proportion: `render_time / target_dt`, or `1.0` if no `target_dt` was
+    /// configured. Callers pass this straight into [`Display::qos`](ranos_display::Display::qos)
+    /// so animations/filters can shed work once they start running behind.
+    pub fn record(&mut self, render_time: Duration) -> f64 {
+        let micros = (render_time.as_micros() as u64)
+            .max(1)
+            .min(MAX_TRACKED_LATENCY_MICROS);
+        let _ = self.histogram.record(micros);
+
+        self.last_proportion = if let Some(target_dt) = self.target_dt {
+            if render_time > target_dt {
+                self.missed_deadlines += 1;
+            }
+
+            render_time.as_secs_f64() / target_dt.as_secs_f64()
+        } else {
+            1.0
+        };
+
+        self.last_proportion
+    }
+
+    /// Returns the most recently recorded quality-of-service proportion, see
+    /// [`Self::record`].
+    pub fn last_proportion(&self) -> f64 {
+        self.last_proportion
+    }
+
+    /// Returns the render latency at the given percentile, e.g. `50.0` for
+    /// p50 or `99.0` for p99.
+    pub fn value_at_percentile(&self, percentile: f64) -> Duration {
+        Duration::from_micros(self.histogram.value_at_percentile(percentile))
+    }
+
+    /// Returns the slowest recorded render latency.
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.histogram.max())
+    }
+
+    /// Returns how many recorded frames took longer than `target_dt` to
+    /// render. Always `0` if no `target_dt` was configured.
+    pub fn missed_deadlines(&self) -> usize {
+        self.missed_deadlines
+    }
+
+    /// Returns the number of render times recorded so far.
+    pub fn len(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    /// Resets the diagnostics to a pre-run state, operating as if it were never run before.
+    pub fn reset(&mut self) {
+        self.histogram.reset();
+        self.missed_deadlines = 0;
+        self.last_proportion = 1.0;
+    }
+}
+
+impl Display for RenderDiagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "p50 render latency: {:?}\n", self.value_at_percentile(50.0))?;
+        write!(f, "p99 render latency: {:?}\n", self.value_at_percentile(99.0))?;
+        write!(f, "Max render latency: {:?}\n", self.max())?;
+        write!(f, "Missed deadlines: {}\n", self.missed_deadlines)?;
+        write!(f, "Last QoS proportion: {:.2}\n", self.last_proportion)
+    }
+}
+
+/// On-the-wire representation of a [`RenderDiagnostics`], so long simulation
+/// runs can be serialized and compared afterwards: the histogram is packed
+/// through `hdrhistogram`'s own V2 binary encoding rather than serializing
+/// its buckets field-by-field.
+#[derive(Serialize, Deserialize)]
+struct RenderDiagnosticsData {
+    histogram: Vec<u8>,
+    missed_deadlines: usize,
+    target_dt: Option<Duration>,
+    last_proportion: f64,
+}
+
+impl Serialize for RenderDiagnostics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut histogram = Vec::new();
+        V2Serializer::new()
+            .serialize(&self.histogram, &mut histogram)
+            .map_err(S::Error::custom)?;
+
+        RenderDiagnosticsData {
+            histogram,
+            missed_deadlines: self.missed_deadlines,
+            target_dt: self.target_dt,
+            last_proportion: self.last_proportion,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RenderDiagnostics {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = RenderDiagnosticsData::deserialize(deserializer)?;
+        let histogram = HistogramDeserializer::new()
+            .deserialize(&mut &data.histogram[..])
+            .map_err(D::Error::custom)?;
+
+        Ok(Self {
+            histogram,
+            missed_deadlines: data.missed_deadlines,
+            target_dt: data.target_dt,
+            last_proportion: data.last_proportion,
+        })
+    }
+}
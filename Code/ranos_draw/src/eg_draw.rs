@@ -0,0 +1,439 @@
+//! Host-agnostic `Draw` backend built on `embedded-graphics`'s `DrawTarget`
+//! trait, rendering each [`Frame`] as one filled rectangle per LED on a
+//! `rows`x`cols` pixel grid. Because [`EgDraw`] is generic over its `DrawTarget`
+//! the same rendering logic drives [`embedded-graphics-simulator`][sim]'s
+//! desktop window (wrapped here as [`EgSimDraw`], this module's registrable
+//! [`DrawBuilder`][crate::DrawBuilder]) and any real SPI/I2C panel with an
+//! embedded-graphics driver, the way [`Apa102Draw`][crate::apa102_draw::Apa102Draw]
+//! is wrapped by [`APA102CPiDraw`][crate::pi_draw::APA102CPiDraw] -- a host for
+//! a physical panel would wrap [`EgDraw`] the same way, supplying its own
+//! driver's `DrawTarget` in place of [`EgSimDraw`]'s `SimulatorDisplay`.
+//!
+//! [sim]: https://docs.rs/embedded-graphics-simulator
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window};
+use serde::{Deserialize, Serialize};
+
+use ranos_core::{timer::TimerStats, Timer};
+use ranos_display::{Display, DisplayBuilder, DisplayState};
+
+use crate::diagnostics::RenderDiagnostics;
+use crate::{Draw, DrawBuilder, SIGINT};
+
+/// Maps a linear LED index to its `(x, y)` pixel position on a `rows`x`cols`
+/// grid, `pitch` pixels apart, walking row-major or serpentine (alternating
+/// column direction each row, matching how most physical matrices are wired).
+fn led_position(i: usize, cols: usize, pitch: u32, serpentine: bool) -> Point {
+    let row = i / cols.max(1);
+    let mut col = i % cols.max(1);
+
+    if serpentine && row % 2 == 1 {
+        col = cols.max(1) - 1 - col;
+    }
+
+    Point::new(col as i32 * pitch as i32, row as i32 * pitch as i32)
+}
+
+/// Drives a 2-D pixel preview of a [`Frame`] over any `embedded-graphics`
+/// [`DrawTarget<Color = Rgb888>`].
+///
+/// See the [module docs](self) for why this type doesn't implement
+/// [`DrawBuilder`][crate::DrawBuilder] directly.
+#[derive(Debug)]
+pub struct EgDraw<D> {
+    target: D,
+
+    rows: usize,
+    cols: usize,
+    pitch: u32,
+    gap: u32,
+    serpentine: bool,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl<D: DrawTarget<Color = Rgb888>> EgDraw<D> {
+    /// Constructs an [`EgDraw`] rendering onto `target`, laying LEDs out on a
+    /// `rows`x`cols` grid `pitch` pixels apart with `gap` pixels of space
+    /// between each LED's square, from the displays produced by `display_iter`.
+    pub fn new<I>(
+        target: D,
+        rows: usize,
+        cols: usize,
+        pitch: u32,
+        gap: u32,
+        serpentine: bool,
+        timer: Timer,
+        display_iter: I,
+    ) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            target,
+
+            rows,
+            cols,
+            pitch,
+            gap,
+            serpentine,
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    fn write_frame(&mut self, display_id: usize) {
+        let size = self.pitch.saturating_sub(self.gap).max(1);
+        let rows = self.rows;
+        let cols = self.cols;
+        let style_cache: Vec<(Point, Rgb888)> = {
+            let frame = self.displays.get(&display_id).unwrap().0.frame();
+
+            frame
+                .iter()
+                .enumerate()
+                .take(rows * cols)
+                .map(|(i, led)| {
+                    let led = led.scale(frame.brightness());
+                    let point = led_position(i, cols, self.pitch, self.serpentine);
+
+                    (point, Rgb888::new(led.red(), led.green(), led.blue()))
+                })
+                .collect()
+        };
+
+        for (point, color) in style_cache {
+            let _ = Rectangle::new(point, Size::new(size, size))
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(&mut self.target);
+        }
+    }
+
+    /// Returns the number of pixels the grid occupies, for sizing a host's target.
+    pub fn pixel_size(rows: usize, cols: usize, pitch: u32) -> Size {
+        Size::new(cols as u32 * pitch, rows as u32 * pitch)
+    }
+}
+
+impl<D: DrawTarget<Color = Rgb888>> Draw for EgDraw<D> {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
+
+/// Builder for [`EgSimDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "EgSimDraw")]
+pub struct EgSimDrawBuilder {
+    rows: usize,
+    cols: usize,
+    pitch: u32,
+    gap: u32,
+    serpentine: bool,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl EgSimDrawBuilder {
+    /// Sets the number of rows in the LED grid.
+    pub fn rows(mut self: Box<Self>, rows: usize) -> Box<Self> {
+        self.rows = rows.max(1);
+
+        self
+    }
+
+    /// Sets the number of columns in the LED grid. Defaults to `8`; LEDs past
+    /// `rows * cols` aren't drawn, so for a 1-D strip set this to the
+    /// display's LED count.
+    pub fn cols(mut self: Box<Self>, cols: usize) -> Box<Self> {
+        self.cols = cols.max(1);
+
+        self
+    }
+
+    /// Sets the center-to-center pixel spacing between LEDs.
+    pub fn pitch(mut self: Box<Self>, pitch: u32) -> Box<Self> {
+        self.pitch = pitch.max(1);
+
+        self
+    }
+
+    /// Sets the pixel gap left empty between neighboring LEDs' squares.
+    pub fn gap(mut self: Box<Self>, gap: u32) -> Box<Self> {
+        self.gap = gap;
+
+        self
+    }
+
+    /// Sets whether alternating rows walk columns in reverse, matching a
+    /// serpentine-wired matrix instead of a row-major one.
+    pub fn serpentine(mut self: Box<Self>, serpentine: bool) -> Box<Self> {
+        self.serpentine = serpentine;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs an [`EgSimDraw`] object.
+    pub fn build(self: Box<Self>) -> EgSimDraw {
+        EgSimDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for EgSimDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{EgSimDraw, EgSimDrawBuilder};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = EgSimDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(rows:1,cols:8,pitch:16,gap:2,serpentine:false,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(rows:1,cols:8,pitch:16,gap:2,serpentine:false,timer:(target_dt:None),displays:[])"#;
+
+        let data: EgSimDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.rows, 1);
+        assert_eq!(data.cols, 8);
+        assert_eq!(data.pitch, 16);
+        assert_eq!(data.gap, 2);
+        assert_eq!(data.serpentine, false);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// WYSIWYG preview `Draw` backend that renders each [`Frame`] into an
+/// [`embedded-graphics-simulator`][sim] window instead of a physical strip,
+/// for developing animations without hardware -- the same config that builds
+/// an [`EgSimDraw`] here builds a real panel's `Draw` once that panel's host
+/// wraps [`EgDraw`] with its own driver's `DrawTarget`.
+///
+/// To create an [`EgSimDraw`] object, use the associated [builder](EgSimDrawBuilder)
+/// which can be accessed by calling [`EgSimDraw::builder()`].
+///
+/// [sim]: https://docs.rs/embedded-graphics-simulator
+#[derive(Debug)]
+pub struct EgSimDraw {
+    inner: EgDraw<SimulatorDisplay<Rgb888>>,
+    window: Window,
+}
+
+impl EgSimDraw {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<EgSimDrawBuilder> {
+        Box::new(EgSimDrawBuilder {
+            rows: 1,
+            cols: 8,
+            pitch: 16,
+            gap: 2,
+            serpentine: false,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<EgSimDrawBuilder>) -> Self {
+        let size = EgDraw::<SimulatorDisplay<Rgb888>>::pixel_size(
+            builder.rows,
+            builder.cols,
+            builder.pitch,
+        );
+        let target = SimulatorDisplay::<Rgb888>::new(size);
+        let window = Window::new("RanOS", &OutputSettingsBuilder::new().build());
+
+        Self {
+            inner: EgDraw::new(
+                target,
+                builder.rows,
+                builder.cols,
+                builder.pitch,
+                builder.gap,
+                builder.serpentine,
+                builder.timer,
+                builder.displays.drain(0..),
+            ),
+            window,
+        }
+    }
+}
+
+impl Draw for EgSimDraw {
+    fn run(&mut self) {
+        self.inner.timer.reset();
+        self.inner.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.inner.displays.len() {
+            let dt = self.inner.timer.ping();
+
+            for i in 0..self.inner.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self
+                        .inner
+                        .displays
+                        .get_mut(&self.inner.display_ids[i])
+                        .unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.inner.write_frame(display_id);
+                self.window.update(&self.inner.target);
+                let proportion = self.inner.diagnostics.record(frame_start.elapsed());
+                self.inner
+                    .displays
+                    .get_mut(&display_id)
+                    .unwrap()
+                    .0
+                    .qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                for event in self.window.events() {
+                    if event == SimulatorEvent::Quit {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.inner.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        self.inner.diagnostics()
+    }
+}
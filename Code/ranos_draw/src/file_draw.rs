@@ -0,0 +1,367 @@
+//! Captures a single display's rendered frames to a compact, self-describing
+//! binary file instead of presenting them anywhere, so a session captured
+//! once (e.g. on a Pi) can be replayed deterministically elsewhere. See
+//! [`ranos_generator`]'s `RecordingGenerator` for the reader half that turns
+//! a file written here back into frames for any other [`Draw`] target.
+//!
+//! ## Format
+//!
+//! ```text
+//! header: magic: [u8; 8] = b"RANOSREC", codec: u8, width: u32, height: u32, fps: f32, frame_count: u32
+//! record: len: u32, block
+//! ```
+//!
+//! All integers and floats are big-endian. `height` is always `1` for the
+//! flat, single-strip frames this crate renders today; it's part of the
+//! header so a reader doesn't have to assume a layout a future grid-shaped
+//! capture might not share.
+//!
+//! `codec` picks how each record's `block` is encoded -- see [`Compression`]
+//! -- so a reader can auto-select the matching decompressor from the header
+//! alone. Each frame is compressed independently (rather than the file as a
+//! whole) specifically so a future reader could seek straight to any frame's
+//! block via an offset table without having to decompress everything before
+//! it; this crate doesn't build that offset table yet, but per-frame blocks
+//! are what would let one be added later without re-encoding existing
+//! recordings. The trade-off: [`Compression::Deflate`] costs CPU time on
+//! whatever's capturing the stream (a Pi, typically) in exchange for a
+//! substantially smaller file across a long, slowly-changing light show; use
+//! [`Compression::None`] instead when capture-side CPU is dearer than disk.
+//!
+//! Each record's `len` is the length of its (possibly compressed) `block` in
+//! bytes, not the decompressed frame size -- a reader needs the codec to
+//! know how many pixels that decompresses to.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::Instant,
+};
+
+use flate2::{write::ZlibEncoder, Compression as ZlibLevel};
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// 8-byte magic identifying a file produced by [`FileDraw`].
+pub const MAGIC: &[u8; 8] = b"RANOSREC";
+
+/// Byte offset of the `frame_count` header field, patched in once the
+/// recording finishes so the file is self-describing without requiring the
+/// writer to know the final frame count up front.
+const FRAME_COUNT_OFFSET: u64 = 8 + 1 + 4 + 4 + 4;
+
+/// How each record's pixel data is encoded on disk. Stored as a one-byte tag
+/// in the file header (`0` = [`None`](Compression::None), `1` =
+/// [`Deflate`](Compression::Deflate)) so a reader can pick the matching
+/// decompressor without being told out of band.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Records are stored as raw `{r, g, b}` triples, uncompressed.
+    None,
+    /// Records are individually deflate/zlib-compressed.
+    ///
+    /// Costs CPU time to compress (and decompress) each frame in exchange
+    /// for a substantially smaller file on mostly-similar frame data, which
+    /// is the common case for a light show captured over minutes or hours.
+    Deflate,
+}
+
+impl Compression {
+    /// The one-byte tag this variant is written as in the file header.
+    fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Deflate => 1,
+        }
+    }
+}
+
+/// Builder for [`FileDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "FileDraw")]
+pub struct FileDrawBuilder {
+    path: PathBuf,
+    fps: f32,
+    compression: Compression,
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl FileDrawBuilder {
+    /// Sets the path the recording is written to.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets the nominal frame rate stored in the recording's header, used by
+    /// a reader to time deterministic playback. This doesn't control how
+    /// often frames are actually captured -- that's the `timer`'s job -- so
+    /// set it to whatever rate the reader should play back at.
+    pub fn fps(mut self: Box<Self>, fps: f32) -> Box<Self> {
+        self.fps = fps;
+
+        self
+    }
+
+    /// Sets the per-record compression used when writing the recording. See
+    /// [`Compression`] for the trade-off between the two options.
+    pub fn compression(mut self: Box<Self>, compression: Compression) -> Box<Self> {
+        self.compression = compression;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: unlike other [`DrawBuilder`]s, [`FileDraw`] only ever records a
+    /// single display -- if more than one is added, all but the first are
+    /// dropped without being built.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`FileDraw`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` can't be created/truncated for writing.
+    pub fn build(self: Box<Self>) -> FileDraw {
+        FileDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for FileDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(FileDraw::from_builder(self))
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{FileDraw, FileDrawBuilder};
+    use ranos_core::Timer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = FileDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected =
+            r#"(path:"",fps:30,compression:None,timer:(target_dt:None),displays:[])"#.to_owned();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"out.ranrec",fps:60,compression:Deflate,timer:(target_dt:None),displays:[])"#;
+
+        let data: FileDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("out.ranrec"));
+        assert_eq!(data.fps, 60.0);
+        assert_eq!(data.compression, super::Compression::Deflate);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drawer that captures a single display's rendered frames to a compact
+/// binary file instead of presenting them anywhere.
+///
+/// To create a [`FileDraw`] object, use the [`FileDrawBuilder`] which can be
+/// accessed by calling [`FileDraw::builder()`].
+#[derive(Debug)]
+pub struct FileDraw {
+    display: Option<(Display, bool)>,
+    width: u32,
+    compression: Compression,
+
+    writer: BufWriter<File>,
+    frame_count: u32,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl FileDraw {
+    /// Constructs a builder object with safe default values: an empty path
+    /// (must be set before building), 30 fps, no compression.
+    pub fn builder() -> Box<FileDrawBuilder> {
+        Box::new(FileDrawBuilder {
+            path: PathBuf::new(),
+            fps: 30.0,
+            compression: Compression::None,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<FileDrawBuilder>) -> Self {
+        Self::new(
+            builder.path,
+            builder.fps,
+            builder.compression,
+            builder.timer,
+            builder.displays.drain(0..).next(),
+        )
+    }
+
+    fn new(
+        path: PathBuf,
+        fps: f32,
+        compression: Compression,
+        timer: Timer,
+        display: Option<DisplayBuilder>,
+    ) -> Self {
+        let display = display.map(|b| b.build());
+        let width = display.as_ref().map_or(0, |d| d.frame_len()) as u32;
+
+        let file = File::create(&path)
+            .unwrap_or_else(|e| panic!("failed to create recording file {:?}: {}", path, e));
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(MAGIC).unwrap();
+        writer.write_all(&[compression.tag()]).unwrap();
+        writer.write_all(&width.to_be_bytes()).unwrap();
+        writer.write_all(&1_u32.to_be_bytes()).unwrap(); // height
+        writer.write_all(&fps.to_be_bytes()).unwrap();
+        writer.write_all(&0_u32.to_be_bytes()).unwrap(); // frame_count, patched in by `finish` when `run` ends
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            display: display.map(|d| (d, false)),
+            width,
+            compression,
+
+            writer,
+            frame_count: 0,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Appends the current frame of `self.display` to the recording as a new record.
+    fn write_frame(&mut self) {
+        let (display, _) = match &self.display {
+            Some(pair) => pair,
+            None => return,
+        };
+
+        let frame = display.frame();
+        let brightness = frame.brightness();
+
+        let pixel_len = self.width as usize * 3;
+        let mut raw = Vec::with_capacity(pixel_len);
+        for led in frame.as_slice() {
+            let led = led.scale(brightness);
+            raw.push(led.red());
+            raw.push(led.green());
+            raw.push(led.blue());
+        }
+
+        let block = match self.compression {
+            Compression::None => raw,
+            Compression::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), ZlibLevel::default());
+                encoder.write_all(&raw).unwrap();
+                encoder.finish().unwrap()
+            }
+        };
+
+        self.writer.write_all(&(block.len() as u32).to_be_bytes()).unwrap();
+        self.writer.write_all(&block).unwrap();
+
+        self.frame_count += 1;
+    }
+
+    /// Patches the header's `frame_count` field with the number of records
+    /// actually written, making the file self-describing regardless of how
+    /// the run ended (ran to completion, SIGINT, or a render error).
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+
+        let inner = self.writer.get_mut();
+        inner.seek(SeekFrom::Start(FRAME_COUNT_OFFSET))?;
+        inner.write_all(&self.frame_count.to_be_bytes())?;
+        inner.flush()
+    }
+}
+
+impl Draw for FileDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        loop {
+            match &self.display {
+                Some((_, has_finished)) if *has_finished => break,
+                Some(_) => (),
+                None => break,
+            }
+
+            let dt = self.timer.ping();
+            let frame_start = Instant::now();
+
+            let state = self.display.as_mut().unwrap().0.render_frame(dt);
+
+            match state {
+                DisplayState::Ok => (),
+                DisplayState::Done => self.display.as_mut().unwrap().1 = true,
+                DisplayState::Err => break,
+            }
+
+            self.write_frame();
+            let proportion = self.diagnostics.record(frame_start.elapsed());
+            self.display.as_mut().unwrap().0.qos(proportion);
+
+            let finished = self.display.as_ref().unwrap().1;
+            if finished || SIGINT.load(Ordering::Relaxed) == true {
+                break;
+            }
+        }
+
+        let _ = self.finish();
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
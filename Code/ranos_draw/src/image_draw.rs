@@ -0,0 +1,417 @@
+//! Captures a single display's rendered frames into an animated image file
+//! (GIF or APNG) instead of presenting them anywhere, so an animation can be
+//! shared or documented without hardware or a live terminal.
+//!
+//! Each [`Frame`](ranos_ds::collections::Frame)'s LEDs are wrapped into rows
+//! of [`width`](ImageDrawBuilder::width) LEDs -- the same "how many per row"
+//! parameter [`TermDraw`](crate::TermDraw) takes as `max_width` -- and each
+//! LED is rasterized as a solid [`cell_size`](ImageDrawBuilder::cell_size) x
+//! `cell_size` square of pixels, so the output is legible without a viewer
+//! that magnifies a one-pixel-per-LED image.
+//!
+//! Frames are buffered in memory as they're rendered rather than streamed to
+//! disk one at a time, since both supported containers need to know things
+//! up front that aren't available until the run ends -- an animated PNG's
+//! `acTL` chunk declares its total frame count before any frame data, and
+//! even GIF's streaming encoder benefits from knowing the run didn't blow
+//! past [`MAX_IMAGE_DRAW_PIXELS`] before a single byte is written. Before
+//! every new frame is pushed onto that buffer, its projected total size
+//! (raster width * height * frame count so far) is checked against that
+//! budget; a run that would exceed it stops capturing (logging why) and
+//! encodes whatever was already captured, rather than growing the buffer
+//! without bound.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use image::{codecs::gif::GifEncoder, Delay, Frame as GifFrame, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// Refuses to grow a capture's in-memory frame buffer past this many total
+/// pixels (raster width * height * frame count), so an animation that runs
+/// far longer than expected can't be used to exhaust memory before it's
+/// ever encoded to disk.
+pub const MAX_IMAGE_DRAW_PIXELS: u64 = 256 * 1024 * 1024;
+
+/// Which animated image container [`ImageDraw`] encodes its captured frames
+/// into.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFormat {
+    /// Encodes the capture as an animated GIF via the [`image`] crate.
+    Gif,
+    /// Encodes the capture as an animated PNG (APNG) via the [`png`] crate.
+    ///
+    /// Produces a noticeably larger file than [`Gif`](Self::Gif) for the
+    /// same content, but isn't limited to a 256-color-per-frame palette.
+    Apng,
+}
+
+/// Builder for [`ImageDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ImageDraw")]
+pub struct ImageDrawBuilder {
+    path: PathBuf,
+    width: usize,
+    cell_size: usize,
+    format: ImageFormat,
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl ImageDrawBuilder {
+    /// Sets the path the image file is written to.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets the number of LEDs wrapped per raster row.
+    ///
+    /// If this parameter is not set, the default value of `8` will be used instead.
+    pub fn width(mut self: Box<Self>, width: usize) -> Box<Self> {
+        self.width = width.max(1);
+
+        self
+    }
+
+    /// Sets the edge length, in pixels, of the solid square each LED is
+    /// rasterized as.
+    pub fn cell_size(mut self: Box<Self>, cell_size: usize) -> Box<Self> {
+        self.cell_size = cell_size.max(1);
+
+        self
+    }
+
+    /// Sets which animated image container the capture is encoded into.
+    pub fn format(mut self: Box<Self>, format: ImageFormat) -> Box<Self> {
+        self.format = format;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: unlike other [`DrawBuilder`]s, [`ImageDraw`] only ever records a
+    /// single display -- if more than one is added, all but the first are
+    /// dropped without being built.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs an [`ImageDraw`] object.
+    pub fn build(self: Box<Self>) -> ImageDraw {
+        ImageDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for ImageDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(ImageDraw::from_builder(self))
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{ImageDraw, ImageDrawBuilder};
+    use ranos_core::Timer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = ImageDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected =
+            r#"(path:"",width:8,cell_size:8,format:Gif,timer:(target_dt:None),displays:[])"#
+                .to_owned();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"out.gif",width:16,cell_size:4,format:Apng,timer:(target_dt:None),displays:[])"#;
+
+        let data: ImageDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("out.gif"));
+        assert_eq!(data.width, 16);
+        assert_eq!(data.cell_size, 4);
+        assert_eq!(data.format, super::ImageFormat::Apng);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// A single rasterized frame, paired with how long it's shown for.
+#[derive(Debug)]
+struct CapturedFrame {
+    image: RgbaImage,
+    delay: Duration,
+}
+
+/// Drawer that captures a single display's rendered frames into an animated
+/// GIF or APNG file instead of presenting them anywhere.
+///
+/// To create an [`ImageDraw`] object, use the [`ImageDrawBuilder`] which can
+/// be accessed by calling [`ImageDraw::builder()`].
+#[derive(Debug)]
+pub struct ImageDraw {
+    display: Option<(Display, bool)>,
+    path: PathBuf,
+    format: ImageFormat,
+
+    width: usize,
+    cell_size: u32,
+    raster_width: u32,
+    raster_height: u32,
+
+    frames: Vec<CapturedFrame>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl ImageDraw {
+    /// Constructs a builder object with safe default values: an empty path
+    /// (must be set before building), 8 LEDs per row, 8-pixel cells, GIF.
+    pub fn builder() -> Box<ImageDrawBuilder> {
+        Box::new(ImageDrawBuilder {
+            path: PathBuf::new(),
+            width: 8,
+            cell_size: 8,
+            format: ImageFormat::Gif,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<ImageDrawBuilder>) -> Self {
+        Self::new(
+            builder.path,
+            builder.width,
+            builder.cell_size,
+            builder.format,
+            builder.timer,
+            builder.displays.drain(0..).next(),
+        )
+    }
+
+    fn new(
+        path: PathBuf,
+        width: usize,
+        cell_size: usize,
+        format: ImageFormat,
+        timer: Timer,
+        display: Option<DisplayBuilder>,
+    ) -> Self {
+        let display = display.map(|b| b.build());
+        let len = display.as_ref().map_or(0, |d| d.frame_len());
+
+        let rows = if len == 0 { 0 } else { (len + width - 1) / width };
+        let raster_width = (width * cell_size) as u32;
+        let raster_height = (rows * cell_size) as u32;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            display: display.map(|d| (d, false)),
+            path,
+            format,
+
+            width,
+            cell_size: cell_size as u32,
+            raster_width,
+            raster_height,
+
+            frames: Vec::new(),
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Rasterizes `self.display`'s current frame into a [`CapturedFrame`] and
+    /// pushes it onto `self.frames`, unless doing so would push the
+    /// buffer's total pixel count past [`MAX_IMAGE_DRAW_PIXELS`] -- in which
+    /// case nothing is allocated.
+    ///
+    /// Returns `false` once the budget has been hit, so [`Self::run`] knows
+    /// to stop the capture there.
+    fn write_frame(&mut self, dt: Duration) -> bool {
+        let (display, _) = match &self.display {
+            Some(pair) => pair,
+            None => return false,
+        };
+
+        let pixel_budget = self.raster_width as u64 * self.raster_height as u64
+            * (self.frames.len() as u64 + 1);
+        if pixel_budget > MAX_IMAGE_DRAW_PIXELS {
+            eprintln!(
+                "ImageDraw: capture would exceed its {}-pixel budget; stopping with {} frame(s) already captured",
+                MAX_IMAGE_DRAW_PIXELS,
+                self.frames.len()
+            );
+
+            return false;
+        }
+
+        let frame = display.frame();
+        let brightness = frame.brightness();
+
+        let mut image = RgbaImage::new(self.raster_width, self.raster_height);
+        for (i, led) in frame.as_slice().iter().enumerate() {
+            let led = led.scale(brightness);
+            let col = (i % self.width) as u32;
+            let row = (i / self.width) as u32;
+
+            for y in 0..self.cell_size {
+                for x in 0..self.cell_size {
+                    image.put_pixel(
+                        col * self.cell_size + x,
+                        row * self.cell_size + y,
+                        image::Rgba([led.red(), led.green(), led.blue(), 255]),
+                    );
+                }
+            }
+        }
+
+        self.frames.push(CapturedFrame { image, delay: dt });
+
+        true
+    }
+
+    /// Encodes every frame captured so far into [`Self::path`] using
+    /// [`Self::format`].
+    fn finish(&mut self) -> image::ImageResult<()> {
+        match self.format {
+            ImageFormat::Gif => self.finish_gif(),
+            ImageFormat::Apng => self.finish_apng(),
+        }
+    }
+
+    fn finish_gif(&mut self) -> image::ImageResult<()> {
+        let file = File::create(&self.path)?;
+        let mut encoder = GifEncoder::new(BufWriter::new(file));
+
+        for captured in &self.frames {
+            let delay = Delay::from_saturating_duration(captured.delay);
+            let frame = GifFrame::from_parts(captured.image.clone(), 0, 0, delay);
+            encoder.encode_frame(frame)?;
+        }
+
+        Ok(())
+    }
+
+    fn finish_apng(&mut self) -> image::ImageResult<()> {
+        let file = File::create(&self.path)?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.raster_width, self.raster_height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(self.frames.len() as u32, 0)
+            .unwrap_or_else(|e| panic!("failed to start APNG encoding: {}", e));
+
+        let mut writer = encoder
+            .write_header()
+            .unwrap_or_else(|e| panic!("failed to write APNG header: {}", e));
+
+        for captured in &self.frames {
+            let millis = captured.delay.as_millis().min(u16::MAX as u128).max(1) as u16;
+            writer
+                .set_frame_delay(millis, 1000)
+                .unwrap_or_else(|e| panic!("failed to set APNG frame delay: {}", e));
+            writer
+                .write_image_data(captured.image.as_raw())
+                .unwrap_or_else(|e| panic!("failed to write APNG frame: {}", e));
+        }
+
+        writer
+            .finish()
+            .unwrap_or_else(|e| panic!("failed to finish APNG encoding: {}", e));
+
+        Ok(())
+    }
+}
+
+impl Draw for ImageDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        loop {
+            match &self.display {
+                Some((_, has_finished)) if *has_finished => break,
+                Some(_) => (),
+                None => break,
+            }
+
+            let dt = self.timer.ping();
+            let frame_start = Instant::now();
+
+            let state = self.display.as_mut().unwrap().0.render_frame(dt);
+
+            match state {
+                DisplayState::Ok => (),
+                DisplayState::Done => self.display.as_mut().unwrap().1 = true,
+                DisplayState::Err => break,
+            }
+
+            if !self.write_frame(dt) {
+                break;
+            }
+            let proportion = self.diagnostics.record(frame_start.elapsed());
+            self.display.as_mut().unwrap().0.qos(proportion);
+
+            let finished = self.display.as_ref().unwrap().1;
+            if finished || SIGINT.load(Ordering::Relaxed) == true {
+                break;
+            }
+        }
+
+        if let Err(e) = self.finish() {
+            eprintln!("ImageDraw: failed to encode captured frames: {}", e);
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
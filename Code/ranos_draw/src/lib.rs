@@ -2,10 +2,74 @@
 //!
 //! This module contains the types that will "draw" to the LEDs.
 //!
-//! There are two drawers defined in this module, one being the actual drawer
-//! that will draw the colors to physical LEDs connected to a Raspberry Pi, and
-//! the second is an emulated LED setup that draws to "LEDs" on the terminal
-//! with a configurable number of "LEDs" per row.
+//! There are multiple drawers defined in this module: ones that draw the
+//! colors to physical LEDs connected to a Raspberry Pi ([`pi_draw`],
+//! [`ws2812_draw`]), one that streams frames over the network to a remote
+//! pixel controller instead ([`network_draw`]), and an emulated LED setup
+//! that draws to "LEDs" on the terminal with a configurable number of "LEDs"
+//! per row ([`term_draw`]), a pixel-accurate WYSIWYG preview that renders
+//! onto an `embedded-graphics` `DrawTarget` -- a desktop simulator window out
+//! of the box, or any real SPI/I2C panel a host wraps the same way
+//! [`pi_draw`] wraps [`apa102_draw`] ([`eg_draw`]) -- and one that streams
+//! frames to a [WLED](https://kno.wled.ge/)-compatible realtime UDP receiver
+//! ([`udp_draw`]) for driving WLED-flashed ESP8266/ESP32 strips directly,
+//! rather than through Art-Net/sACN like [`network_draw`], one that
+//! captures a session to a compact binary file instead of presenting it
+//! anywhere ([`file_draw`]), for deterministic replay later via
+//! `ranos_generator`'s `RecordingGenerator`, one that does the same but
+//! records each frame's exact presentation duration instead of assuming a
+//! constant nominal fps, onto the portable
+//! [`FrameSequence`](ranos_ds::collections::FrameSequence) format
+//! ([`recorder_draw`]), for replay via `ranos_generator`'s
+//! `FrameSequenceGenerator`, and one that captures a session
+//! as an animated GIF or APNG for sharing or documentation, with no replay
+//! back into this crate needed ([`image_draw`]), one that layers several
+//! displays' frames into one merged frame instead of outputting anywhere
+//! itself, for overlaying multiple animations in a single pass
+//! ([`composite_draw`]), and one that loads a downsampled frame onto the
+//! Linux virtual console's 16-color palette, for visualizing a run on a Pi
+//! with no attached LED strip or GUI ([`vc_draw`]).
+//!
+//! Each drawer's builder is registered for discovery the same way every other
+//! polymorphic builder in this project is: via its `#[typetag::serde]` impl
+//! of [`DrawBuilder`], keyed by a `type` tag matching the builder's name, so a
+//! new drawer becomes selectable from a config file purely by implementing
+//! the trait -- there's no separate name-to-constructor registry to update.
+//!
+//! Note on hardware output: rather than one [`Draw`] type covering every
+//! wire protocol, each protocol gets its own concrete type following the
+//! same `render_frame`/`run`/`stats` loop as [`TermDraw`] -- [`APA102CPiDraw`]
+//! (aliased as [`SK9822PiDraw`]) for the clocked APA102C/SK9822 protocol, and
+//! [`Ws2812Draw`] for clockless WS2812-style strips over SPI via the
+//! [`ws2812_spi`] driver crate. [`WS2812PiDraw`] drives the same WS2812
+//! protocol but hand-encodes it onto SPI directly rather than depending on an
+//! external protocol driver, for setups that would rather not pull it in.
+//! [`Ws2812PwmDraw`] drives the same strips again, this time over the Pi's
+//! PWM+DMA peripheral via the native `rpi_ws281x` library instead of SPI --
+//! gated behind the `ws2812_pwm` cargo feature on top of the usual Pi-only
+//! restriction, so the native dependency is opt-in. All of these take a
+//! configurable pin/SPI device and per-strip LED count (via the displays they
+//! own), apply [`Frame::brightness`](ranos_ds::collections::Frame::brightness)
+//! the same way [`TermDraw`]'s `write_frame` does, and check [`SIGINT`]
+//! between frames so a run can be interrupted cleanly.
+//!
+//! [`APA102CPiDraw`] itself is a thin wrapper around [`apa102_draw::Apa102Draw`],
+//! a host-agnostic implementation of the APA102C/SK9822 protocol generic over
+//! any [`embedded_hal::spi::SpiBus`] -- since a type generic over its SPI bus
+//! can't implement `#[typetag::serde]` itself, it isn't discoverable through
+//! [`DrawBuilder`] directly, but any embedded-hal host can wrap it the same
+//! way [`pi_draw`] does to get a registrable [`Draw`] type of its own.
+//!
+//! Every drawer also tracks [`RenderDiagnostics`] alongside its [`TimerStats`]:
+//! an HDR histogram of how long each `render_frame` call actually took
+//! against the configured `target_dt`, so a run can be queried afterwards for
+//! p50/p99/max render latency and how many frames missed their deadline --
+//! see [`diagnostics`] for details. Each `run` loop also feeds that frame's
+//! render-time-to-`target_dt` proportion back into the [`Display`] it just
+//! rendered via [`Display::qos`](ranos_display::Display::qos), so filters
+//! that can shed work when running behind (e.g.
+//! [`ranos_filter::Breath`](ranos_filter::Breath) skipping its per-LED scale
+//! every other frame) get the chance to.
 
 #![warn(missing_docs)]
 #![deny(broken_intra_doc_links)]
@@ -14,28 +78,75 @@
 extern crate ranos_core;
 extern crate ranos_display;
 
+pub use apa102_draw::Apa102Draw;
+pub use composite_draw::{CompositeDraw, CompositeDrawBuilder};
+pub use diagnostics::RenderDiagnostics;
+pub use eg_draw::{EgDraw, EgSimDraw, EgSimDrawBuilder};
+pub use file_draw::{Compression, FileDraw, FileDrawBuilder};
+pub use image_draw::{ImageDraw, ImageDrawBuilder, ImageFormat};
+pub use network_draw::{NetworkDraw, NetworkDrawBuilder, NetworkProtocol};
 pub use null_draw::{NullDraw, NullDrawBuilder};
+pub use recorder_draw::{RecorderDraw, RecorderDrawBuilder};
 pub use term_draw::{TermDraw, TermDrawBuilder};
+pub use udp_draw::{UdpDraw, UdpDrawBuilder, WledProtocol};
+
+#[cfg(target_os = "linux")]
+pub use pi_draw::{
+    APA102CPiDraw, APA102CPiDrawBuilder, SK9822PiDraw, SK9822PiDrawBuilder, WS2812PiDraw,
+    WS2812PiDrawBuilder,
+};
 
 #[cfg(target_os = "linux")]
-pub use pi_draw::{APA102CPiDraw, APA102CPiDrawBuilder, SK9822PiDraw, SK9822PiDrawBuilder};
+pub use vc_draw::{VirtualConsoleDraw, VirtualConsoleDrawBuilder};
+
+#[cfg(target_os = "linux")]
+pub use ws2812_draw::{Ws2812Draw, Ws2812DrawBuilder};
+
+#[cfg(all(target_os = "linux", feature = "ws2812_pwm"))]
+pub use ws2812_pwm_draw::{Ws2812PwmDraw, Ws2812PwmDrawBuilder};
 
 use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}};
 
-use ranos_core::{Timer, timer::TimerStats};
+use ranos_core::{Diagnostic, Timer, timer::TimerStats};
 use ranos_display::{Display, DisplayBuilder};
 
+pub mod apa102_draw;
+pub mod composite_draw;
+pub mod diagnostics;
+pub mod eg_draw;
+pub mod file_draw;
+pub mod image_draw;
+pub mod network_draw;
 pub mod null_draw;
+pub mod recorder_draw;
 pub mod term_draw;
+pub mod udp_draw;
 
 #[cfg(target_os = "linux")]
 pub mod pi_draw;
 
+#[cfg(target_os = "linux")]
+pub mod vc_draw;
+
+#[cfg(target_os = "linux")]
+pub mod ws2812_draw;
+
+#[cfg(all(target_os = "linux", feature = "ws2812_pwm"))]
+pub mod ws2812_pwm_draw;
+
 #[macro_use]
 extern crate lazy_static;
 
 lazy_static! {
-    static ref SIGINT: Arc<AtomicBool> = {
+    /// Process-wide flag set by the `SIGINT` handler registered below.
+    ///
+    /// Public so that callers outside this crate (e.g. the app crate that
+    /// owns the top-level run loop) can check the same flag a running
+    /// [`Draw`] checks mid-frame, rather than registering a second `ctrlc`
+    /// handler -- `ctrlc::set_handler` can only be installed once per
+    /// process, so a second `lazy_static` doing so would panic the first
+    /// time anything outside this crate touched it.
+    pub static ref SIGINT: Arc<AtomicBool> = {
         let arc = Arc::new(AtomicBool::new(false));
 
         {
@@ -54,6 +165,9 @@ pub trait Draw {
 
     /// Returns the statistics tracking object.
     fn stats(&self) -> &TimerStats;
+
+    /// Returns the render-latency diagnostics recorded over the last `run`.
+    fn diagnostics(&self) -> &RenderDiagnostics;
 }
 
 /// Defines the behavior of a builder of a type that implements [`Draw`][crate::Draw].
@@ -75,6 +189,27 @@ pub trait DrawBuilder {
 
     /// Builds [`Draw`][crate::Draw] object, returning it boxed up.
     fn build(self: Box<Self>) -> Box<dyn Draw>;
+
+    /// Checks this builder's fields for configurations that would panic or
+    /// produce nonsensical output at [`build`](Self::build), repairing
+    /// whatever it safely can and reporting one [`Diagnostic`] per issue
+    /// found.
+    ///
+    /// The default implementation has nothing to check and returns no diagnostics.
+    fn validate(&mut self) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Runs [`DrawBuilder::validate`] on `builder`, returning it back along with
+/// whatever diagnostics were found.
+///
+/// This is the entry point tools should use to either report or auto-repair
+/// a configuration before calling [`DrawBuilder::build`].
+pub fn lint_and_fix(mut builder: Box<dyn DrawBuilder>) -> (Box<dyn DrawBuilder>, Vec<Diagnostic>) {
+    let diagnostics = builder.validate();
+
+    (builder, diagnostics)
 }
 
 #[cfg(test)]
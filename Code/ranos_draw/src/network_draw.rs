@@ -0,0 +1,516 @@
+//! Streams rendered frames to a remote lighting controller over the network,
+//! via either Art-Net (ArtDMX) or sACN (E1.31), rather than driving LEDs
+//! wired directly to this host -- the same "stream frames to a remote
+//! device" idea as [`pi_draw`][crate::pi_draw] and
+//! [`ws2812_draw`][crate::ws2812_draw], just addressed over UDP to an
+//! off-the-shelf pixel controller instead of a local GPIO/SPI peripheral.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+use ranos_ds::rgb::RGBOrder;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// The UDP port sACN (E1.31) is conventionally streamed over.
+pub const SACN_PORT: u16 = 5568;
+/// The UDP port Art-Net is conventionally streamed over.
+pub const ARTNET_PORT: u16 = 6454;
+
+/// Number of DMX512 channels in a single universe.
+const CHANNELS_PER_UNIVERSE: usize = 512;
+/// Number of whole RGB LEDs that fit in a single universe's channels.
+const LEDS_PER_UNIVERSE: usize = CHANNELS_PER_UNIVERSE / 3;
+
+/// The ACN packet identifier ("ASC-E1.17\0\0\0") that opens every root layer.
+const ACN_PACKET_IDENTIFIER: [u8; 12] = [
+    0x41, 0x53, 0x43, 0x2d, 0x45, 0x31, 0x2e, 0x31, 0x37, 0x00, 0x00, 0x00,
+];
+const ROOT_VECTOR: u32 = 0x0000_0004;
+const FRAMING_VECTOR: u32 = 0x0000_0002;
+const DMP_VECTOR: u8 = 0x02;
+
+/// The header ("Art-Net" plus a trailing nul) that opens every Art-Net packet.
+const ARTNET_ID: [u8; 8] = [0x41, 0x72, 0x74, 0x2d, 0x4e, 0x65, 0x74, 0x00];
+/// The ArtDMX OpCode, sent little-endian per the Art-Net spec.
+const ARTNET_OPCODE_DMX: u16 = 0x5000;
+/// The Art-Net protocol revision this drawer speaks.
+const ARTNET_PROTOCOL_VERSION: u16 = 14;
+
+/// Which network lighting protocol a [`NetworkDraw`] streams frames over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkProtocol {
+    /// Art-Net ArtDMX, UDP port [`ARTNET_PORT`].
+    ArtNet,
+    /// sACN (E1.31), UDP port [`SACN_PORT`].
+    Sacn,
+}
+
+/// Builder for [`NetworkDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "NetworkDraw")]
+pub struct NetworkDrawBuilder {
+    protocol: NetworkProtocol,
+    dest_ip: Ipv4Addr,
+    multicast: bool,
+    start_universe: u16,
+    pixels_per_universe: usize,
+    priority: u8,
+    source_name: String,
+    cid: Option<[u8; 16]>,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl NetworkDrawBuilder {
+    /// Sets which protocol frames are streamed as.
+    pub fn protocol(mut self: Box<Self>, protocol: NetworkProtocol) -> Box<Self> {
+        self.protocol = protocol;
+
+        self
+    }
+
+    /// Sets the destination controller's IPv4 address.
+    ///
+    /// Ignored for [`NetworkProtocol::Sacn`] when [`Self::multicast`] is enabled,
+    /// since each universe is instead sent to its own multicast group.
+    pub fn dest_ip(mut self: Box<Self>, ip: Ipv4Addr) -> Box<Self> {
+        self.dest_ip = ip;
+
+        self
+    }
+
+    /// Sets whether, for [`NetworkProtocol::Sacn`], each universe is sent to
+    /// its standard multicast group `239.255.<universe hi>.<universe lo>`
+    /// instead of to [`Self::dest_ip`]. Has no effect for [`NetworkProtocol::ArtNet`].
+    pub fn multicast(mut self: Box<Self>, multicast: bool) -> Box<Self> {
+        self.multicast = multicast;
+
+        self
+    }
+
+    /// Sets the first universe to stream to; frames larger than
+    /// [`Self::pixels_per_universe`] LEDs roll over into consecutive universes from here.
+    pub fn start_universe(mut self: Box<Self>, universe: u16) -> Box<Self> {
+        self.start_universe = universe.max(1);
+
+        self
+    }
+
+    /// Sets the number of RGB pixels packed into each universe's DMX data,
+    /// i.e. where a frame rolls over into the next universe. Clamped to
+    /// [`LEDS_PER_UNIVERSE`], the most that fits in a single universe's 512
+    /// channels.
+    pub fn pixels_per_universe(mut self: Box<Self>, pixels: usize) -> Box<Self> {
+        self.pixels_per_universe = pixels.min(LEDS_PER_UNIVERSE);
+
+        self
+    }
+
+    /// Sets the sACN priority field (0-200, higher wins on a shared universe).
+    /// Has no effect for [`NetworkProtocol::ArtNet`].
+    pub fn priority(mut self: Box<Self>, priority: u8) -> Box<Self> {
+        self.priority = priority.min(200);
+
+        self
+    }
+
+    /// Sets the source name advertised in the framing layer of each sACN
+    /// packet. Has no effect for [`NetworkProtocol::ArtNet`].
+    pub fn source_name(mut self: Box<Self>, name: impl Into<String>) -> Box<Self> {
+        self.source_name = name.into();
+
+        self
+    }
+
+    /// Sets the CID (component identifier) advertised in the root layer of
+    /// each sACN packet. Has no effect for [`NetworkProtocol::ArtNet`]. If
+    /// left unset, a random CID is generated when the drawer is built.
+    pub fn cid(mut self: Box<Self>, cid: [u8; 16]) -> Box<Self> {
+        self.cid = Some(cid);
+
+        self
+    }
+
+    /// Sets the timer, which paces how often frames are streamed out.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`NetworkDraw`] object.
+    pub fn build(self: Box<Self>) -> NetworkDraw {
+        NetworkDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for NetworkDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{NetworkDraw, NetworkDrawBuilder, NetworkProtocol};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = NetworkDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(protocol:Sacn,dest_ip:"255.255.255.255",multicast:false,start_universe:1,pixels_per_universe:170,priority:100,source_name:"RanOS",cid:None,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(protocol:ArtNet,dest_ip:"192.168.1.50",multicast:false,start_universe:1,pixels_per_universe:170,priority:100,source_name:"RanOS",cid:None,timer:(target_dt:None),displays:[])"#;
+        let data: NetworkDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.protocol, NetworkProtocol::ArtNet);
+        assert_eq!(data.dest_ip, "192.168.1.50".parse().unwrap());
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Packs each rendered frame into one or more Art-Net or sACN (E1.31)
+/// universes and streams them over UDP to a remote lighting controller.
+///
+/// Frames larger than [`NetworkDrawBuilder::pixels_per_universe`] LEDs (at
+/// most [`LEDS_PER_UNIVERSE`], 512 channels / 3 bytes per LED) roll over into
+/// consecutive universes counting up from
+/// [`NetworkDrawBuilder::start_universe`]. Each universe tracks its own
+/// sequence number, as the spec requires.
+///
+/// To create a [`NetworkDraw`] object, use the associated
+/// [builder](NetworkDrawBuilder), accessed via [`NetworkDraw::builder()`].
+#[derive(Debug)]
+pub struct NetworkDraw {
+    socket: UdpSocket,
+    protocol: NetworkProtocol,
+    dest_ip: Ipv4Addr,
+    multicast: bool,
+    start_universe: u16,
+    pixels_per_universe: usize,
+    priority: u8,
+    cid: [u8; 16],
+    source_name: String,
+    sequences: HashMap<u16, u8>,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl NetworkDraw {
+    /// Constructs a builder object with safe default values: sACN,
+    /// broadcasting to `255.255.255.255` so it reaches any listening
+    /// controller on the LAN until a real destination is set.
+    pub fn builder() -> Box<NetworkDrawBuilder> {
+        Box::new(NetworkDrawBuilder {
+            protocol: NetworkProtocol::Sacn,
+            dest_ip: Ipv4Addr::BROADCAST,
+            multicast: false,
+            start_universe: 1,
+            pixels_per_universe: LEDS_PER_UNIVERSE,
+            priority: 100,
+            source_name: "RanOS".to_owned(),
+            cid: None,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<NetworkDrawBuilder>) -> Self {
+        let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+        socket.set_broadcast(builder.dest_ip.is_broadcast()).unwrap();
+        // Don't let a full send buffer stall the render loop; a dropped
+        // frame is preferable to blocking the whole display pipeline.
+        socket.set_nonblocking(true).unwrap();
+
+        Self::new(
+            socket,
+            builder.protocol,
+            builder.dest_ip,
+            builder.multicast,
+            builder.start_universe,
+            builder.pixels_per_universe,
+            builder.priority,
+            builder.cid.unwrap_or_else(rand::random),
+            builder.source_name,
+            builder.timer,
+            builder.displays.drain(0..),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new<I>(
+        socket: UdpSocket,
+        protocol: NetworkProtocol,
+        dest_ip: Ipv4Addr,
+        multicast: bool,
+        start_universe: u16,
+        pixels_per_universe: usize,
+        priority: u8,
+        cid: [u8; 16],
+        source_name: String,
+        timer: Timer,
+        display_iter: I,
+    ) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            socket,
+            protocol,
+            dest_ip,
+            multicast,
+            start_universe,
+            pixels_per_universe: pixels_per_universe.min(LEDS_PER_UNIVERSE).max(1),
+            priority,
+            cid,
+            source_name,
+            sequences: HashMap::new(),
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Resolves the UDP destination a given universe's packet is sent to.
+    ///
+    /// For [`NetworkProtocol::Sacn`] with multicast enabled, each universe
+    /// gets its own standard multicast group (`239.255.<universe hi
+    /// byte>.<universe lo byte>`) instead of [`Self::dest_ip`].
+    fn packet_dest(&self, universe: u16) -> SocketAddr {
+        match self.protocol {
+            NetworkProtocol::ArtNet => SocketAddr::new(self.dest_ip.into(), ARTNET_PORT),
+            NetworkProtocol::Sacn if self.multicast => {
+                let [hi, lo] = universe.to_be_bytes();
+                SocketAddr::new(Ipv4Addr::new(239, 255, hi, lo).into(), SACN_PORT)
+            }
+            NetworkProtocol::Sacn => SocketAddr::new(self.dest_ip.into(), SACN_PORT),
+        }
+    }
+
+    /// Packs `dmx_data` (up to [`CHANNELS_PER_UNIVERSE`] bytes) into a single
+    /// sACN packet addressed to `universe`, advancing that universe's
+    /// sequence number.
+    fn build_sacn_packet(&mut self, universe: u16, dmx_data: &[u8]) -> Vec<u8> {
+        let sequence = self.sequences.entry(universe).or_insert(0);
+        let this_sequence = *sequence;
+        *sequence = sequence.wrapping_add(1);
+
+        let mut property_values = Vec::with_capacity(1 + CHANNELS_PER_UNIVERSE);
+        property_values.push(0x00); // DMX start code
+        property_values.extend_from_slice(dmx_data);
+        property_values.resize(1 + CHANNELS_PER_UNIVERSE, 0);
+
+        let dmp_len = 10 + property_values.len();
+        let framing_len = 77 + dmp_len;
+        let root_len = 22 + framing_len;
+
+        let mut packet = Vec::with_capacity(16 + root_len);
+
+        // Root layer.
+        packet.extend_from_slice(&0x0010u16.to_be_bytes()); // preamble size
+        packet.extend_from_slice(&0x0000u16.to_be_bytes()); // postamble size
+        packet.extend_from_slice(&ACN_PACKET_IDENTIFIER);
+        packet.extend_from_slice(&flags_and_length(root_len as u16));
+        packet.extend_from_slice(&ROOT_VECTOR.to_be_bytes());
+        packet.extend_from_slice(&self.cid);
+
+        // Framing layer.
+        packet.extend_from_slice(&flags_and_length(framing_len as u16));
+        packet.extend_from_slice(&FRAMING_VECTOR.to_be_bytes());
+        let mut source_name = [0u8; 64];
+        let name_bytes = self.source_name.as_bytes();
+        let copy_len = name_bytes.len().min(63);
+        source_name[..copy_len].copy_from_slice(&name_bytes[..copy_len]);
+        packet.extend_from_slice(&source_name);
+        packet.push(self.priority);
+        packet.extend_from_slice(&0x0000u16.to_be_bytes()); // sync address: unused
+        packet.push(this_sequence);
+        packet.push(0x00); // options
+        packet.extend_from_slice(&universe.to_be_bytes());
+
+        // DMP layer.
+        packet.extend_from_slice(&flags_and_length(dmp_len as u16));
+        packet.push(DMP_VECTOR);
+        packet.push(0xa1); // address type & data type
+        packet.extend_from_slice(&0x0000u16.to_be_bytes()); // first property address
+        packet.extend_from_slice(&0x0001u16.to_be_bytes()); // address increment
+        packet.extend_from_slice(&(property_values.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&property_values);
+
+        packet
+    }
+
+    /// Packs `dmx_data` (up to [`CHANNELS_PER_UNIVERSE`] bytes) into a single
+    /// Art-Net ArtDMX packet addressed to `universe`, advancing that
+    /// universe's sequence number.
+    ///
+    /// `universe`'s low byte becomes the Art-Net SubNet/Universe nibbles and
+    /// its high 7 bits become the Net, rather than threading separate Net/
+    /// SubNet/Universe fields through the builder.
+    fn build_artnet_packet(&mut self, universe: u16, dmx_data: &[u8]) -> Vec<u8> {
+        let sequence = self.sequences.entry(universe).or_insert(1);
+        let this_sequence = *sequence;
+        // 0x00 means "sequencing not in use" to an ArtDMX receiver, so skip it on wrap.
+        *sequence = if *sequence == 255 { 1 } else { sequence.wrapping_add(1) };
+
+        let mut data = dmx_data.to_vec();
+        if data.len() % 2 != 0 {
+            data.push(0x00);
+        }
+
+        let mut packet = Vec::with_capacity(18 + data.len());
+        packet.extend_from_slice(&ARTNET_ID);
+        packet.extend_from_slice(&ARTNET_OPCODE_DMX.to_le_bytes());
+        packet.extend_from_slice(&ARTNET_PROTOCOL_VERSION.to_be_bytes());
+        packet.push(this_sequence);
+        packet.push(0x00); // physical: not used by this drawer
+        packet.push((universe & 0x00FF) as u8); // SubNet (high nibble) / Universe (low nibble)
+        packet.push(((universe >> 8) & 0x7F) as u8); // Net
+        packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&data);
+
+        packet
+    }
+
+    /// Scales the display's frame by its brightness, splits it into
+    /// per-universe DMX chunks, and streams each as its own packet in the
+    /// configured [`NetworkProtocol`].
+    fn write_frame(&mut self, display_id: usize) {
+        let dmx_data: Vec<u8> = {
+            let frame = self.displays.get(&display_id).unwrap().0.frame();
+            frame
+                .iter()
+                .flat_map(|led| {
+                    let (r, g, b) = led.scale(frame.brightness()).as_tuple(RGBOrder::RGB);
+                    [r, g, b]
+                })
+                .collect()
+        };
+
+        for (i, chunk) in dmx_data.chunks(self.pixels_per_universe * 3).enumerate() {
+            let universe = self.start_universe + i as u16;
+            let packet = match self.protocol {
+                NetworkProtocol::ArtNet => self.build_artnet_packet(universe, chunk),
+                NetworkProtocol::Sacn => self.build_sacn_packet(universe, chunk),
+            };
+            let dest = self.packet_dest(universe);
+
+            // Best-effort: a dropped datagram on a non-blocking socket isn't
+            // worth stalling or aborting the render loop over.
+            let _ = self.socket.send_to(&packet, dest);
+        }
+    }
+}
+
+/// Builds the `flags and length` field shared by every sACN PDU: the top 4
+/// bits are always `0x7`, the bottom 12 hold the PDU's length from this field
+/// to the end of its data.
+fn flags_and_length(len: u16) -> [u8; 2] {
+    (0x7000 | (len & 0x0FFF)).to_be_bytes()
+}
+
+impl Draw for NetworkDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -1,12 +1,14 @@
 //! A drawer with no output.
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, time::Instant};
 
 use serde::{Deserialize, Serialize};
 
 use ranos_core::Timer;
 use ranos_display::DisplayState;
 
+use crate::diagnostics::RenderDiagnostics;
+
 use super::*;
 
 /// Builder for [`NullDraw`].
@@ -90,6 +92,7 @@ mod builder_test {
 pub struct NullDraw {
     displays: Vec<(Display, bool)>,
     timer: Timer,
+    diagnostics: RenderDiagnostics,
 }
 
 impl NullDraw {
@@ -109,9 +112,12 @@ impl NullDraw {
     where
         I: Iterator<Item = DisplayBuilder>,
     {
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
         Self {
             displays: display_iter.map(|b| (b.build(), false)).collect(),
             timer,
+            diagnostics,
         }
     }
 }
@@ -119,6 +125,7 @@ impl NullDraw {
 impl Draw for NullDraw {
     fn run(&mut self) {
         self.timer.reset();
+        self.diagnostics.reset();
 
         let mut num_finished = 0;
 
@@ -126,12 +133,14 @@ impl Draw for NullDraw {
             let dt = self.timer.ping();
 
             for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
                 let (d, has_finished) = self.displays.get_mut(i).unwrap();
 
                 if !*has_finished {
                     match d.render_frame(dt) {
-                        DisplayState::Continue => (),
-                        DisplayState::Last => {
+                        DisplayState::Ok => (),
+                        DisplayState::Done => {
                             *has_finished = true;
                             num_finished += 1;
                         }
@@ -139,6 +148,9 @@ impl Draw for NullDraw {
                     }
                 }
 
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                d.qos(proportion);
+
                 if SIGINT.load(Ordering::Relaxed) == true {
                     return;
                 }
@@ -149,4 +161,8 @@ impl Draw for NullDraw {
     fn stats(&self) -> &TimerStats {
         self.timer.stats()
     }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
 }
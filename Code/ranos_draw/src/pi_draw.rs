@@ -1,39 +1,50 @@
 //! This module is designed with the APA102C LEDs in mind. There are
 //! additionally aliases for the SK9822 LEDs, which have a compatible protocol
-//! to the APA102C's.
+//! to the APA102C's. [`APA102CPiDraw`] is a thin wrapper around
+//! [`Apa102Draw`][crate::apa102_draw::Apa102Draw], the host-agnostic
+//! embedded-hal implementation of the protocol, that supplies the Pi's
+//! hardware SPI bus. It also includes [`WS2812PiDraw`], a sibling
+//! implementation of the clockless WS2812/WS2811 protocol hand-encoded onto
+//! SPI, following the same structure.
 //!
-//! For more details see the [`APA102CPiDraw`] documentation.
+//! For more details see the [`APA102CPiDraw`] and [`WS2812PiDraw`] documentation.
 
 #![cfg(target_os = "linux")]
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    thread,
+    time::{Duration, Instant},
+};
 
-use rppal::gpio;
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
 use serde::{Deserialize, Serialize};
 
-use ranos_core::Timer;
+use ranos_core::{timer::TimerStats, Timer};
 use ranos_display::DisplayState;
 use ranos_ds::rgb::*;
 
+use crate::apa102_draw::Apa102Draw;
+use crate::diagnostics::RenderDiagnostics;
+use crate::ws2812_draw::{bus_serde, slave_select_serde, DEFAULT_BUS, DEFAULT_SLAVE_SELECT};
+
 use super::*;
 
-/// The default data pin to use when one isn't supplied.
+/// The default SPI clock rate [`APA102CPiDraw`] drives the bus at, unless
+/// overridden via [`APA102CPiDrawBuilder::frequency`]. The APA102C datasheet
+/// allows up to 20MHz; this is a conservative rate that's been reliable
+/// across the SK9822 clone as well.
+const DEFAULT_APA102_SPI_FREQ: u32 = 8_000_000;
+
+/// Retained only so RON configs written against the pre-SPI, GPIO-bit-banged
+/// `APA102CPiDraw` still deserialize. APA102 timing is now driven by the Pi's
+/// hardware SPI peripheral (see [`apa102_draw`][crate::apa102_draw]), whose
+/// MOSI/SCLK pins are fixed by the chosen bus, so this value no longer
+/// selects anything.
 pub const DEFAULT_DAT_PIN: u8 = 6;
-/// The default clock pin to use when one isn't supplied.
+/// See [`DEFAULT_DAT_PIN`]; no longer selects anything.
 pub const DEFAULT_CLK_PIN: u8 = 5;
 
-#[inline]
-fn bit_to_level(byte: u8, bit: u8) -> gpio::Level {
-    if byte >> bit & 1 != 0 {
-        gpio::Level::High
-    } else {
-        gpio::Level::Low
-    }
-}
-
-/// Local rename of the GPIO pin type.
-pub type Pin = gpio::OutputPin;
-
 /// Type alias of [`APA102CPiDrawBuilder`] for the compatible SK9822 LEDs
 pub type SK9822PiDrawBuilder = APA102CPiDrawBuilder;
 
@@ -50,6 +61,8 @@ pub struct APA102CPiDrawBuilder {
 
     brightness: u8, // should be in the range [0, 31].
 
+    frequency: u32,
+
     timer: Timer,
     displays: VecDeque<DisplayBuilder>,
 }
@@ -69,6 +82,15 @@ impl APA102CPiDrawBuilder {
         self
     }
 
+    /// Sets the SPI clock rate, in Hz, the bus is driven at. Defaults to
+    /// [`DEFAULT_APA102_SPI_FREQ`]; the APA102C datasheet allows up to 20MHz,
+    /// so chains that don't need the conservative default can push it higher.
+    pub fn frequency(mut self: Box<Self>, frequency: u32) -> Box<Self> {
+        self.frequency = frequency;
+
+        self
+    }
+
     /// Sets the hardware brightness value. Should be in the range \[0, 31\].
     pub fn brightness(mut self: Box<Self>, brightness: u8) -> Box<Self> {
         self.brightness = brightness.min(31);
@@ -128,23 +150,25 @@ mod builder_test {
 
         // eprintln!("{}", data);
         let expected =
-            r#"(data_pin:6,clock_pin:5,brightness:1,timer:(target_dt:None),displays:[])"#;
+            r#"(data_pin:6,clock_pin:5,brightness:1,frequency:8000000,timer:(target_dt:None),displays:[])"#;
         assert_eq!(data, expected);
     }
 
     #[test]
     fn test_deserialize() {
-        let input = r#"(data_pin:6,clock_pin:5,brightness:1,timer:(target_dt:None),displays:[])"#;
+        let input = r#"(data_pin:6,clock_pin:5,brightness:1,frequency:8000000,timer:(target_dt:None),displays:[])"#;
         let data: APA102CPiDrawBuilder = ron::de::from_str(input).unwrap();
 
         assert_eq!(data.data_pin, DEFAULT_DAT_PIN);
         assert_eq!(data.clock_pin, DEFAULT_CLK_PIN);
+        assert_eq!(data.frequency, super::DEFAULT_APA102_SPI_FREQ);
         assert_eq!(data.timer, Timer::new(None));
         assert_eq!(data.displays.len(), 0);
     }
 }
 
-/// Struct that draws [APA102C][0] LEDs through the Raspberry Pi's GPIO pins.
+/// Struct that draws [APA102C][0] LEDs over the Raspberry Pi's hardware SPI
+/// peripheral.
 ///
 /// To create a [`APA102CPiDraw`] object, use the associated [builder](APA102CPiDrawBuilder) which can be accessed by calling
 /// [`APA102CPiDraw::builder()`].
@@ -158,70 +182,287 @@ mod builder_test {
 /// handles the brightness runs at 440Hz, which can cause flicker issues on lower brightness settings. The SK9822 clone gets
 /// around this issue by current-limiting according to the brightness value instead of adjusting PWM settings.
 ///
-/// NOTE TO FUTURE SELF: As both the start and end frame both are writing 0s to the data line, why not try combining the start
-/// and end frame into 1 call, and writing n/2 + 32 0s to the data line in one shot.
-///
 /// [0]: https://cdn-shop.adafruit.com/datasheets/APA102.pdf
 /// [1]: https://cpldcpu.wordpress.com/2016/12/13/sk9822-a-clone-of-the-apa102/
 ///
 /// ## Further Info
 ///
-/// Most of the private functions include documentation relevant to their operation. You are free to take a look at it in its
-/// context, but it will also be provided here for clarity and concise-ness.
-///
-/// ### Start Frame
-///
-/// The start frame representing the start of a message to the LEDs as defined by the [datasheet][2].
-///
-/// [2]: https://cdn-shop.adafruit.com/datasheets/APA102.pdf
+/// This is a thin wrapper around [`Apa102Draw`], the host-agnostic
+/// embedded-hal implementation of the APA102C/SK9822 protocol -- see that
+/// module for how the start frame/per-LED header/end frame are encoded.
+#[derive(Debug)]
+pub struct APA102CPiDraw {
+    inner: Apa102Draw<Spi>,
+}
+
+impl APA102CPiDraw {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<APA102CPiDrawBuilder> {
+        Box::new(APA102CPiDrawBuilder {
+            data_pin: DEFAULT_DAT_PIN,
+            clock_pin: DEFAULT_CLK_PIN,
+            brightness: 1,
+            frequency: DEFAULT_APA102_SPI_FREQ,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<APA102CPiDrawBuilder>) -> Self {
+        let spi = Spi::new(DEFAULT_BUS, DEFAULT_SLAVE_SELECT, builder.frequency, Mode::Mode0)
+            .unwrap();
+
+        Self {
+            inner: Apa102Draw::new(
+                spi,
+                builder.brightness,
+                builder.timer,
+                builder.displays.drain(0..),
+            ),
+        }
+    }
+}
+
+impl Draw for APA102CPiDraw {
+    fn run(&mut self) {
+        self.inner.run()
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.inner.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        self.inner.diagnostics()
+    }
+}
+
+/// The SPI clock rate this module drives WS2812 strips at. Each SPI bit is
+/// ~417ns at this rate, so expanding every WS2812 data bit into 3 SPI bits
+/// (see [`push_byte`]) gives each data bit a ~1.25us pulse, matching the
+/// protocol's timing.
+const WS2812_SPI_FREQ: u32 = 2_400_000;
+/// The minimum low time between frames the protocol requires to latch the
+/// shifted-out colors, i.e. the reset pulse.
+const WS2812_RESET_LATCH: Duration = Duration::from_micros(50);
+
+/// Expands a single data byte, MSB first, into the SPI bytes that encode its
+/// bits as WS2812 pulse widths: a logic 0 is `0b100` (short high, long low)
+/// and a logic 1 is `0b110` (long high, short low), each occupying 3 bits of
+/// the outgoing SPI stream at [`WS2812_SPI_FREQ`].
+fn push_byte(out: &mut BitPacker, byte: u8) {
+    for i in (0..8).rev() {
+        let bit = (byte >> i) & 1 != 0;
+        let pattern: u8 = if bit { 0b110 } else { 0b100 };
+
+        out.push_bit(pattern & 0b100 != 0);
+        out.push_bit(pattern & 0b010 != 0);
+        out.push_bit(pattern & 0b001 != 0);
+    }
+}
+
+/// Packs individual bits, MSB first, into a byte-aligned buffer. Used by
+/// [`push_byte`] to expand WS2812 data bits into their SPI-encoded pulse
+/// widths ahead of a single buffered SPI transfer.
+#[derive(Default)]
+struct BitPacker {
+    bytes: Vec<u8>,
+    cur: u8,
+    cur_len: u8,
+}
+
+impl BitPacker {
+    fn push_bit(&mut self, bit: bool) {
+        self.cur = (self.cur << 1) | (bit as u8);
+        self.cur_len += 1;
+
+        if self.cur_len == 8 {
+            self.bytes.push(self.cur);
+            self.cur = 0;
+            self.cur_len = 0;
+        }
+    }
+
+    /// Flushes any partial trailing byte, padding with low bits, and returns the packed buffer.
+    fn finish(mut self) -> Vec<u8> {
+        while self.cur_len != 0 {
+            self.push_bit(false);
+        }
+
+        self.bytes
+    }
+}
+
+/// Builder for [`WS2812PiDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "WS2812PiDraw")]
+pub struct WS2812PiDrawBuilder {
+    #[serde(with = "bus_serde")]
+    bus: Bus,
+    #[serde(with = "slave_select_serde")]
+    slave_select: SlaveSelect,
+    rgbw: bool,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl WS2812PiDrawBuilder {
+    /// Sets the SPI bus the data line is wired to.
+    pub fn bus(mut self: Box<Self>, bus: Bus) -> Box<Self> {
+        self.bus = bus;
+
+        self
+    }
+
+    /// Sets the SPI chip-select slave the data line is wired to.
+    pub fn slave_select(mut self: Box<Self>, slave_select: SlaveSelect) -> Box<Self> {
+        self.slave_select = slave_select;
+
+        self
+    }
+
+    /// Sets whether the strip is wired for SK6812 RGBW LEDs rather than plain
+    /// WS2812/WS2811 ones. When set, each LED's white channel is extracted
+    /// via [`RGB::extract_white`] and clocked out as a fourth byte after the
+    /// GRB triplet, matching the GRBW wire order SK6812 RGBW strips expect.
+    pub fn rgbw(mut self: Box<Self>, rgbw: bool) -> Box<Self> {
+        self.rgbw = rgbw;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`WS2812PiDraw`] object.
+    pub fn build(self: Box<Self>) -> WS2812PiDraw {
+        WS2812PiDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for WS2812PiDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod ws2812_builder_test {
+    use super::{WS2812PiDraw, WS2812PiDrawBuilder};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = WS2812PiDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected =
+            r#"(bus:"Spi0",slave_select:"Ss0",rgbw:false,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input =
+            r#"(bus:"Spi0",slave_select:"Ss0",rgbw:false,timer:(target_dt:None),displays:[])"#;
+        let data: WS2812PiDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.rgbw, false);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drives a strip of clockless WS2812/WS2811 LEDs by hand-encoding the
+/// one-wire protocol onto the Pi's SPI peripheral, mirroring
+/// [`APA102CPiDraw`]'s builder/`Drop`-blackout structure for the clocked
+/// APA102C protocol.
 ///
-/// ### End Frame
+/// Precise sub-microsecond toggling of a GPIO pin from Linux userspace isn't
+/// reliable enough to bit-bang WS2812 timing directly, so this instead rides
+/// the SPI peripheral's own clock: every WS2812 data bit is expanded into 3
+/// SPI bits (see [`push_byte`]) and the whole frame is shifted out as one
+/// buffered SPI transfer, with [`WS2812_RESET_LATCH`] of idle line afterwards
+/// to latch it. Colors are packed 24 bits per LED in GRB order, as the
+/// protocol expects.
 ///
-/// The end frame representing the end of a message to the LEDs as defined by the [datasheet][3] with modifications as revealed
-/// in [this blog post][4], and a subsequent [follow-up post][5] discussing the APA102C clone, the SK9822.
+/// For a simpler setup that doesn't need this hand-rolled encoding (at the
+/// cost of depending on an external protocol driver crate), see
+/// [`Ws2812Draw`][crate::Ws2812Draw].
 ///
-/// [3]: https://cdn-shop.adafruit.com/datasheets/APA102.pdf
-/// [4]: https://cpldcpu.wordpress.com/2014/11/30/understanding-the-apa102-superled/
-/// [5]: https://cpldcpu.wordpress.com/2016/12/13/sk9822-a-clone-of-the-apa102/#[derive(Debug)]
-pub struct APA102CPiDraw {
-    data: Pin,
-    clock: Pin,
-
-    brightness: u8,
+/// To create a [`WS2812PiDraw`] object, use the associated
+/// [builder](WS2812PiDrawBuilder) which can be accessed by calling
+/// [`WS2812PiDraw::builder()`].
+#[derive(Debug)]
+pub struct WS2812PiDraw {
+    spi: Spi,
+    rgbw: bool,
 
     displays: HashMap<usize, (Display, bool)>,
     display_ids: Vec<usize>,
 
     timer: Timer,
+    diagnostics: RenderDiagnostics,
 
     num: usize,
 }
 
-impl APA102CPiDraw {
+impl WS2812PiDraw {
     /// Constructs a builder object with safe default values.
-    pub fn builder() -> Box<APA102CPiDrawBuilder> {
-        Box::new(APA102CPiDrawBuilder {
-            data_pin: DEFAULT_DAT_PIN,
-            clock_pin: DEFAULT_CLK_PIN,
-            brightness: 1,
+    pub fn builder() -> Box<WS2812PiDrawBuilder> {
+        Box::new(WS2812PiDrawBuilder {
+            bus: DEFAULT_BUS,
+            slave_select: DEFAULT_SLAVE_SELECT,
+            rgbw: false,
             timer: Timer::new(None),
             displays: VecDeque::new(),
         })
     }
 
-    fn from_builder(mut builder: Box<APA102CPiDrawBuilder>) -> Self {
-        let gpio = gpio::Gpio::new().unwrap();
+    fn from_builder(mut builder: Box<WS2812PiDrawBuilder>) -> Self {
+        let spi = Spi::new(
+            builder.bus,
+            builder.slave_select,
+            WS2812_SPI_FREQ,
+            Mode::Mode0,
+        )
+        .unwrap();
 
         Self::new(
-            gpio.get(builder.data_pin).unwrap().into_output(),
-            gpio.get(builder.clock_pin).unwrap().into_output(),
-            builder.brightness,
+            spi,
+            builder.rgbw,
             builder.timer,
             builder.displays.drain(0..),
         )
     }
 
-    fn new<I>(data: Pin, clock: Pin, brightness: u8, timer: Timer, display_iter: I) -> Self
+    fn new<I>(spi: Spi, rgbw: bool, timer: Timer, display_iter: I) -> Self
     where
         I: Iterator<Item = DisplayBuilder>,
     {
@@ -237,134 +478,75 @@ impl APA102CPiDraw {
             .collect();
         let display_ids = ids;
 
-        Self {
-            data: data,
-            clock: clock,
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
 
-            brightness,
+        Self {
+            spi,
+            rgbw,
 
             displays,
             display_ids,
 
             timer,
+            diagnostics,
 
             num,
         }
     }
 
-    /// The start frame representing the start of a message to the LEDs as defined by the [datasheet][0].
-    ///
-    /// [0]: https://cdn-shop.adafruit.com/datasheets/APA102.pdf
-    #[inline]
-    fn start_frame(&mut self) {
-        self.set_pins_low();
-
-        self.write_byte(0x00);
-        self.write_byte(0x00);
-        self.write_byte(0x00);
-        self.write_byte(0x00);
-    }
-
-    /// The end frame representing the end of a message to the LEDs as defined by the [datasheet][0] with modifications as
-    /// revealed in [this blog post][1], and a subsequent [follow-up post][2] discussing the APA102C clone, the SK9822.
-    ///
-    /// [0]: https://cdn-shop.adafruit.com/datasheets/APA102.pdf
-    /// [1]: https://cpldcpu.wordpress.com/2014/11/30/understanding-the-apa102-superled/
-    /// [2]: https://cpldcpu.wordpress.com/2016/12/13/sk9822-a-clone-of-the-apa102/
-    #[inline]
-    fn end_frame(&mut self, len: usize) {
-        for _ in 0..(len >> 4) {
-            self.write_byte(0x00);
+    /// Scales the display's frame by its brightness, packs it per LED with
+    /// each data bit SPI-encoded, and shifts the whole frame out in one
+    /// buffered transfer before latching it. Plain WS2812/WS2811 LEDs get 24
+    /// bits in GRB order; when [`WS2812PiDrawBuilder::rgbw`] is set, each
+    /// pixel's white channel is extracted via [`RGB::extract_white`] and an
+    /// extra 8 bits are pushed after the GRB triplet, matching the GRBW wire
+    /// order SK6812 RGBW strips expect.
+    fn write_frame(&mut self, display_id: usize) {
+        let frame = self.displays.get(&display_id).unwrap().0.frame();
+
+        let mut packer = BitPacker::default();
+        for led in frame.iter() {
+            let led = led.scale(frame.brightness());
+            let (g, r, b) = led.as_tuple(RGBOrder::GRB);
+            push_byte(&mut packer, g);
+            push_byte(&mut packer, r);
+            push_byte(&mut packer, b);
+
+            if self.rgbw {
+                push_byte(&mut packer, led.extract_white().white());
+            }
         }
-    }
-
-    /// Writes a single byte of data to the `data` pin sequentially one bit at a time starting with the MSB.
-    #[inline]
-    fn write_byte(&mut self, byte: u8) {
-        self.data.write(bit_to_level(byte, 7));
-        self.clock.toggle();
-        self.clock.toggle();
-
-        self.data.write(bit_to_level(byte, 6));
-        self.clock.toggle();
-        self.clock.toggle();
-
-        self.data.write(bit_to_level(byte, 5));
-        self.clock.toggle();
-        self.clock.toggle();
-
-        self.data.write(bit_to_level(byte, 4));
-        self.clock.toggle();
-        self.clock.toggle();
 
-        self.data.write(bit_to_level(byte, 3));
-        self.clock.toggle();
-        self.clock.toggle();
+        self.spi.write(&packer.finish()).unwrap();
 
-        self.data.write(bit_to_level(byte, 2));
-        self.clock.toggle();
-        self.clock.toggle();
-
-        self.data.write(bit_to_level(byte, 1));
-        self.clock.toggle();
-        self.clock.toggle();
-
-        self.data.write(bit_to_level(byte, 0));
-        self.clock.toggle();
-        self.clock.toggle();
+        thread::sleep(WS2812_RESET_LATCH);
     }
 
-    /// Simple function used to ensure the pins are set to low before sending a message to the LEDs.
-    #[inline]
-    fn set_pins_low(&mut self) {
-        self.data.set_low();
-        self.clock.set_low();
-    }
-
-    /// Sets all LEDs up to `len` to black with 0 brightness, effectively
-    /// turning the LEDs off. Used in system shutdown code, as well as `SIGINT`
-    /// handling.
+    /// Sets all LEDs up to `len` to black, effectively turning the LEDs off.
+    /// Used in system shutdown code, as well as `SIGINT` handling.
     fn stop(&mut self, len: usize) {
-        self.start_frame();
-
+        let mut packer = BitPacker::default();
         for _ in 0..len {
-            self.write_byte(0xE0);
-            self.write_byte(0);
-            self.write_byte(0);
-            self.write_byte(0);
-        }
-
-        self.end_frame(len);
-    }
+            push_byte(&mut packer, 0);
+            push_byte(&mut packer, 0);
+            push_byte(&mut packer, 0);
 
-    /// Writes a frame to the LEDs. Uses color order `BGR` as defined in the datasheet.
-    fn write_frame(&mut self, display_id: usize) {
-        let (brightness_mask, len) = (
-            0xE0 | self.brightness,
-            self.displays.get(&display_id).unwrap().0.frame().len(),
-        );
-
-        self.start_frame();
-
-        for i in 0..len {
-            self.write_byte(brightness_mask);
-            let color = {
-                let frame = self.displays.get(&display_id).unwrap().0.frame();
-                frame[i].scale(frame.brightness()).as_tuple(RGBOrder::BGR)
-            };
-            self.write_byte(color.0);
-            self.write_byte(color.1);
-            self.write_byte(color.2);
+            if self.rgbw {
+                push_byte(&mut packer, 0);
+            }
         }
 
-        self.end_frame(len);
+        self.spi.write(&packer.finish()).unwrap();
+
+        thread::sleep(WS2812_RESET_LATCH);
     }
 }
 
-impl Draw for APA102CPiDraw {
+impl Draw for WS2812PiDraw {
     fn run(&mut self) {
         // Reset timer and stats to track just this run
         self.timer.reset();
+        self.diagnostics.reset();
 
         let mut num_finished = 0;
 
@@ -372,13 +554,15 @@ impl Draw for APA102CPiDraw {
             let dt = self.timer.ping();
 
             for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
                 let display_id = {
                     let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
 
                     if !*has_finished {
                         match d.render_frame(dt) {
-                            DisplayState::Continue => (),
-                            DisplayState::Last => {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
                                 *has_finished = true;
                                 num_finished += 1;
                             }
@@ -390,6 +574,8 @@ impl Draw for APA102CPiDraw {
                 };
 
                 self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
 
                 if SIGINT.load(Ordering::Relaxed) == true {
                     return;
@@ -401,9 +587,13 @@ impl Draw for APA102CPiDraw {
     fn stats(&self) -> &TimerStats {
         self.timer.stats()
     }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
 }
 
-impl Drop for APA102CPiDraw {
+impl Drop for WS2812PiDraw {
     /// For our eye's sake, this custom `Drop` implementation ensures that when
     /// the LED controller is stopped, the LEDs will be set to off so they don't blind anyone.
     fn drop(&mut self) {
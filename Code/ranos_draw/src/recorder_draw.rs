@@ -0,0 +1,269 @@
+//! Captures a single display's rendered frames to a
+//! [`ranos_ds::collections::FrameSequence`] stream, writing each record as
+//! it's rendered rather than buffering the whole capture in memory, so a
+//! long-running session captured once (e.g. on a Pi) can be replayed
+//! deterministically elsewhere. See [`ranos_generator`]'s
+//! `FrameSequenceGenerator` for the reader half that turns a file written
+//! here back into frames for any other [`Draw`] target.
+//!
+//! Unlike [`file_draw`][crate::file_draw], which assumes a constant nominal
+//! frame rate on playback, each record here carries the actual [`Duration`]
+//! its frame was shown for, so drift introduced by QoS-driven frame drops or
+//! timer jitter during capture is preserved on replay instead of smoothed
+//! away to a fixed fps. The on-disk layout is exactly
+//! [`ranos_ds::collections::FrameSequence`]'s own format (`RANOSSEQ` magic, a
+//! version byte, little-endian throughout), so a finished recording can also
+//! be loaded wholesale via [`FrameSequence::read`] instead of streamed back
+//! with the generator.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+use ranos_ds::collections::frame_sequence::{MAGIC, VERSION};
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// Byte offset of the `record_count` header field, patched in once the
+/// recording finishes so the file is self-describing without requiring the
+/// writer to know the final record count up front: 8 bytes of [`MAGIC`] plus
+/// the one-byte version.
+const RECORD_COUNT_OFFSET: u64 = 8 + 1;
+
+/// Builder for [`RecorderDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "RecorderDraw")]
+pub struct RecorderDrawBuilder {
+    path: PathBuf,
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl RecorderDrawBuilder {
+    /// Sets the path the recording is written to.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: like [`FileDraw`](crate::FileDraw), [`RecorderDraw`] only ever
+    /// records a single display -- if more than one is added, all but the
+    /// first are dropped without being built.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`RecorderDraw`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` can't be created/truncated for writing.
+    pub fn build(self: Box<Self>) -> RecorderDraw {
+        RecorderDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for RecorderDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{RecorderDraw, RecorderDrawBuilder};
+    use ranos_core::Timer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = RecorderDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(path:"",timer:(target_dt:None),displays:[])"#.to_owned();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"out.ranseq",timer:(target_dt:None),displays:[])"#;
+
+        let data: RecorderDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("out.ranseq"));
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drawer that captures a single display's rendered frames, each paired with
+/// the exact [`Duration`] it was shown for, to a
+/// [`ranos_ds::collections::FrameSequence`]-format binary file instead of
+/// presenting them anywhere.
+///
+/// To create a [`RecorderDraw`] object, use the [`RecorderDrawBuilder`] which
+/// can be accessed by calling [`RecorderDraw::builder()`].
+#[derive(Debug)]
+pub struct RecorderDraw {
+    display: Option<(Display, bool)>,
+
+    writer: BufWriter<File>,
+    record_count: u32,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl RecorderDraw {
+    /// Constructs a builder object with safe default values: an empty path,
+    /// which must be set via [`RecorderDrawBuilder::path`] before building.
+    pub fn builder() -> Box<RecorderDrawBuilder> {
+        Box::new(RecorderDrawBuilder {
+            path: PathBuf::new(),
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<RecorderDrawBuilder>) -> Self {
+        Self::new(
+            builder.path,
+            builder.timer,
+            builder.displays.drain(0..).next(),
+        )
+    }
+
+    fn new(path: PathBuf, timer: Timer, display: Option<DisplayBuilder>) -> Self {
+        let display = display.map(|b| b.build());
+
+        let file = File::create(&path)
+            .unwrap_or_else(|e| panic!("failed to create recording file {:?}: {}", path, e));
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(MAGIC).unwrap();
+        writer.write_all(&[VERSION]).unwrap();
+        writer.write_all(&0_u32.to_le_bytes()).unwrap(); // record_count, patched in on finish
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            display: display.map(|d| (d, false)),
+
+            writer,
+            record_count: 0,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Appends the current frame of `self.display`, paired with `dt`, to the
+    /// recording as a new record.
+    fn write_record(&mut self, dt: Duration) {
+        let (display, _) = match &self.display {
+            Some(pair) => pair,
+            None => return,
+        };
+
+        self.writer
+            .write_all(&(dt.as_secs() as u32).to_le_bytes())
+            .unwrap();
+        self.writer
+            .write_all(&dt.subsec_nanos().to_le_bytes())
+            .unwrap();
+        display.frame().write(&mut self.writer).unwrap();
+
+        self.record_count += 1;
+    }
+
+    /// Patches the header's `record_count` field with the number of records
+    /// actually written, making the file self-describing regardless of how
+    /// the run ended (ran to completion, SIGINT, or a render error).
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+
+        let inner = self.writer.get_mut();
+        inner.seek(SeekFrom::Start(RECORD_COUNT_OFFSET))?;
+        inner.write_all(&self.record_count.to_le_bytes())?;
+        inner.flush()
+    }
+}
+
+impl Draw for RecorderDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        loop {
+            match &self.display {
+                Some((_, has_finished)) if *has_finished => break,
+                Some(_) => (),
+                None => break,
+            }
+
+            let dt = self.timer.ping();
+            let frame_start = Instant::now();
+
+            let state = self.display.as_mut().unwrap().0.render_frame(dt);
+
+            match state {
+                DisplayState::Ok => (),
+                DisplayState::Done => self.display.as_mut().unwrap().1 = true,
+                DisplayState::Err => break,
+            }
+
+            self.write_record(dt);
+            let proportion = self.diagnostics.record(frame_start.elapsed());
+            self.display.as_mut().unwrap().0.qos(proportion);
+
+            let finished = self.display.as_ref().unwrap().1;
+            if finished || SIGINT.load(Ordering::Relaxed) == true {
+                break;
+            }
+        }
+
+        let _ = self.finish();
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -1,20 +1,32 @@
 //! Enables the ability to draw pixels to a terminal window that supports the full range of RGB colors.
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+    time::Instant,
+};
 
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use terminal_size::{terminal_size, Width};
 
 use ranos_core::Timer;
 use ranos_display::DisplayState;
 
+use crate::diagnostics::RenderDiagnostics;
+
 use super::*;
 
+/// The glyph used to render two LED rows per terminal line in half-block
+/// mode: its foreground paints the top LED, its background the bottom.
+const HALF_BLOCK_GLYPH: &str = "\u{2580}";
+
 /// Builder for [`TermDraw`].
 #[derive(Serialize, Deserialize)]
 #[serde(rename = "TermDraw")]
 pub struct TermDrawBuilder {
     max_width: usize,
+    half_block: bool,
     timer: Timer,
     displays: VecDeque<DisplayBuilder>,
 }
@@ -23,12 +35,26 @@ impl TermDrawBuilder {
     /// Sets the maximum number of LEDs to draw per line.
     ///
     /// If this parameter is not set, the default value of `8` will be used instead.
+    ///
+    /// Note: if the terminal's width can be detected at render time, it takes
+    /// priority over this value; this is only the fallback used when
+    /// detection fails (e.g. stdout isn't a TTY).
     pub fn max_width(mut self: Box<Self>, width: usize) -> Box<Self> {
         self.max_width = width;
 
         self
     }
 
+    /// Toggles rendering two LED rows per terminal line with the upper-half-block
+    /// glyph `▀` (foreground = top LED, background = bottom LED), doubling
+    /// vertical resolution at the cost of halving the number of character
+    /// rows printed, versus the default of one row of two-space blocks per LED row.
+    pub fn half_block(mut self: Box<Self>, half_block: bool) -> Box<Self> {
+        self.half_block = half_block;
+
+        self
+    }
+
     /// Sets the timer.
     pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
         self.timer = timer;
@@ -80,17 +106,19 @@ mod builder_test {
         let data = ron::ser::to_string(&builder).unwrap();
 
         // eprintln!("{}", data);
-        let expected = r#"(max_width:8,timer:(target_dt:None),displays:[])"#.to_owned();
+        let expected =
+            r#"(max_width:8,half_block:false,timer:(target_dt:None),displays:[])"#.to_owned();
         assert_eq!(data, expected);
     }
 
     #[test]
     fn test_deserialize() {
-        let input = r#"(max_width:8,timer:(target_dt:None),displays:[])"#;
+        let input = r#"(max_width:8,half_block:false,timer:(target_dt:None),displays:[])"#;
 
         let data: TermDrawBuilder = ron::de::from_str(input).unwrap();
 
         assert_eq!(data.max_width, 8);
+        assert_eq!(data.half_block, false);
         assert_eq!(data.timer, Timer::new(None));
         assert_eq!(data.displays.len(), 0);
     }
@@ -98,17 +126,21 @@ mod builder_test {
 
 /// Emulates an LED display by writing whitespace with colored backgrounds to a terminal that supports full RGB colors.
 ///
-/// LEDs are displayed in a rectangular grid with 1 LED's worth of space between each column and row.
+/// LEDs are displayed in a rectangular grid with 1 LED's worth of space between each column and row, one LED row per
+/// character row by default, or two LED rows per character row (via the upper-half-block glyph `▀`) when
+/// [`TermDrawBuilder::half_block`] is set. Each frame is followed by a one-line footer reporting the live render rate.
 ///
 /// To create a [`TermDraw`] object, use the [`TermDrawBuilder`] which can be accessed by calling [`TermDraw::builder()`].
 #[derive(Debug)]
 pub struct TermDraw {
     max_width: usize,
+    half_block: bool,
 
     displays: HashMap<usize, (Display, bool)>,
     display_ids: Vec<usize>,
 
     timer: Timer,
+    diagnostics: RenderDiagnostics,
 }
 
 impl TermDraw {
@@ -116,6 +148,7 @@ impl TermDraw {
     pub fn builder() -> Box<TermDrawBuilder> {
         Box::new(TermDrawBuilder {
             max_width: 8,
+            half_block: false,
             timer: Timer::new(None),
             displays: VecDeque::new(),
         })
@@ -124,12 +157,13 @@ impl TermDraw {
     fn from_builder(mut builder: Box<TermDrawBuilder>) -> Self {
         Self::new(
             builder.max_width,
+            builder.half_block,
             builder.timer,
             builder.displays.drain(0..),
         )
     }
 
-    fn new<I>(max_width: usize, timer: Timer, display_iter: I) -> Self
+    fn new<I>(max_width: usize, half_block: bool, timer: Timer, display_iter: I) -> Self
     where
         I: Iterator<Item = DisplayBuilder>,
     {
@@ -143,48 +177,139 @@ impl TermDraw {
             .collect();
         let display_ids = ids;
 
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
         Self {
             max_width,
+            half_block,
 
             displays,
             display_ids,
 
             timer,
+            diagnostics,
         }
     }
 
+    /// Returns the number of LEDs to draw per row: the terminal's detected
+    /// column width (in cells) when available, falling back to [`Self::max_width`]
+    /// when stdout isn't a TTY or its size can't otherwise be determined.
+    fn row_width(&self) -> usize {
+        let cell_width = if self.half_block { 3 } else { 4 };
+
+        match terminal_size() {
+            Some((Width(cols), _)) => ((cols as usize) / cell_width).max(1),
+            None => self.max_width,
+        }
+    }
+
+    /// Renders one line reporting the live frame rate and total frame count,
+    /// computed from the timer's own [`TimerStats`](ranos_core::timer::TimerStats)
+    /// the same way it would be printed after a run finishes.
+    fn fps_footer(&self) -> String {
+        let stats = self.timer.stats();
+        let elapsed = stats.elapsed().as_secs_f32();
+        let fps = if elapsed > 0.0 {
+            stats.pings() as f32 / elapsed
+        } else {
+            0.0
+        };
+
+        format!("{:.1} fps ({} frames)", fps, stats.pings())
+    }
+
     fn write_frame(&mut self, display_id: usize) {
+        let width = self.row_width().max(1);
+
         let frame = self.displays.get(&display_id).unwrap().0.frame();
+        let leds = frame.as_slice();
+        let brightness = frame.brightness();
 
         // Create output string with enough capacity to minimize reallocations of memory for growing the string's capacity
-        let mut output =
-            String::with_capacity(frame.len() * 4 + (frame.len() / self.max_width) * 2 + 16);
-        output.push_str("\x1B[2J"); // ANSI clear-screen code
-        output.push_str("\x1B[1;1H"); // ANSI "move cursor to upper-left corner" code
-
-        // Loop through the enumerated RGB values
-        for (i, led) in frame.iter().enumerate() {
-            // Check if max width has been reached on the current row
-            if i % self.max_width == 0 {
-                output = format!("{}\n\n", output);
+        let mut output = String::with_capacity(leds.len() * 4 + (leds.len() / width) * 2 + 64);
+        output.push_str("\x1B[1;1H"); // ANSI "move cursor to upper-left corner" code, repainting in place rather than clearing
+
+        if self.half_block {
+            let mut row_start = 0;
+            while row_start < leds.len() {
+                output.push('\n');
+
+                for col in 0..width {
+                    let top = match leds.get(row_start + col) {
+                        Some(led) => led.scale(brightness),
+                        None => break,
+                    };
+
+                    let cell = match leds.get(row_start + width + col) {
+                        Some(bot) => {
+                            let bot = bot.scale(brightness);
+                            HALF_BLOCK_GLYPH
+                                .truecolor(top.red(), top.green(), top.blue())
+                                .on_truecolor(bot.red(), bot.green(), bot.blue())
+                        }
+                        None => HALF_BLOCK_GLYPH.truecolor(top.red(), top.green(), top.blue()),
+                    };
+
+                    output = format!("{}{}  ", output, cell);
+                }
+
+                row_start += width * 2;
             }
+        } else {
+            // Loop through the enumerated RGB values
+            for (i, led) in leds.iter().enumerate() {
+                // Check if the row width has been reached on the current row
+                if i % width == 0 {
+                    output = format!("{}\n\n", output);
+                }
 
-            // Scale the color and print it to the output
-            let led = led.scale(frame.brightness());
-            output = format!(
-                "{}{}  ",
-                output,
-                "  ".on_truecolor(led.red(), led.green(), led.blue())
-            );
+                // Scale the color and print it to the output
+                let led = led.scale(brightness);
+                output = format!(
+                    "{}{}  ",
+                    output,
+                    "  ".on_truecolor(led.red(), led.green(), led.blue())
+                );
+            }
         }
 
+        output = format!("{}\n\n{}", output, self.fps_footer());
+
         println!("{}", output);
     }
 }
 
+/// RAII guard that switches the terminal into its alternate screen buffer and
+/// hides the cursor on construction, and unconditionally restores both on
+/// drop -- including when [`TermDraw::run`] returns early after catching
+/// `SIGINT` mid-frame, since that return unwinds the scope the guard lives in
+/// the same as any other exit path.
+struct AltScreenGuard;
+
+impl AltScreenGuard {
+    fn new() -> Self {
+        print!("\x1B[?1049h"); // enter alternate screen buffer
+        print!("\x1B[?25l"); // hide cursor
+        let _ = io::stdout().flush();
+
+        Self
+    }
+}
+
+impl Drop for AltScreenGuard {
+    fn drop(&mut self) {
+        print!("\x1B[?25h"); // show cursor
+        print!("\x1B[?1049l"); // leave alternate screen buffer, restoring the prior one
+        let _ = io::stdout().flush();
+    }
+}
+
 impl Draw for TermDraw {
     fn run(&mut self) {
+        let _screen = AltScreenGuard::new();
+
         self.timer.reset();
+        self.diagnostics.reset();
 
         let mut num_finished = 0;
 
@@ -192,13 +317,15 @@ impl Draw for TermDraw {
             let dt = self.timer.ping();
 
             for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
                 let display_id = {
                     let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
 
                     if !*has_finished {
                         match d.render_frame(dt) {
-                            DisplayState::Continue => (),
-                            DisplayState::Last => {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
                                 *has_finished = true;
                                 num_finished += 1;
                             }
@@ -210,6 +337,8 @@ impl Draw for TermDraw {
                 };
 
                 self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
 
                 if SIGINT.load(Ordering::Relaxed) == true {
                     return;
@@ -221,4 +350,8 @@ impl Draw for TermDraw {
     fn stats(&self) -> &TimerStats {
         self.timer.stats()
     }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
 }
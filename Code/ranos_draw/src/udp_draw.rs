@@ -0,0 +1,358 @@
+//! Streams rendered frames to a [WLED][wled]-compatible realtime UDP
+//! receiver, so RanOS can drive physical ESP8266/ESP32 LED strips running
+//! WLED instead of only the terminal or a raw Art-Net/sACN controller -- the
+//! same "stream frames to a remote device" idea as [`network_draw`][crate::network_draw],
+//! just speaking WLED's own simpler realtime wire formats.
+//!
+//! [wled]: https://kno.wled.ge/interfaces/udp-realtime/
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+use ranos_ds::rgb::RGBOrder;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// The UDP port WLED's realtime UDP receiver listens on by default.
+pub const WLED_PORT: u16 = 21324;
+
+/// The number of LEDs' worth of RGB data that fits in a single DRGB/DRGBW
+/// datagram before a [`WledProtocol::Dnrgb`] chunked send becomes necessary
+/// for larger strips.
+const MAX_LEDS_PER_PACKET: usize = 490;
+
+/// Which WLED realtime wire format a [`UdpDraw`] streams frames as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WledProtocol {
+    /// Header byte `2`, then a timeout byte, then `R,G,B` for every LED in
+    /// order. Simplest format, but has no start index so can't be chunked --
+    /// prefer [`WledProtocol::Dnrgb`] for strips over ~490 LEDs.
+    Drgb,
+    /// Header byte `3`, then a timeout byte, then `R,G,B,W` for every LED in
+    /// order. RanOS frames carry no white channel, so `W` is always sent as `0`.
+    Drgbw,
+    /// Header byte `4`, then a timeout byte, then a big-endian `u16` start
+    /// index, then `R,G,B` triples. The only format with a start index, so
+    /// it's the one chunked into multiple datagrams for strips larger than
+    /// [`MAX_LEDS_PER_PACKET`].
+    Dnrgb,
+}
+
+/// Builder for [`UdpDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "UdpDraw")]
+pub struct UdpDrawBuilder {
+    dest: SocketAddr,
+    protocol: WledProtocol,
+    timeout_secs: u8,
+    order: RGBOrder,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl UdpDrawBuilder {
+    /// Sets the destination WLED controller's address.
+    pub fn dest(mut self: Box<Self>, dest: SocketAddr) -> Box<Self> {
+        self.dest = dest;
+
+        self
+    }
+
+    /// Sets which WLED realtime wire format frames are streamed as.
+    pub fn protocol(mut self: Box<Self>, protocol: WledProtocol) -> Box<Self> {
+        self.protocol = protocol;
+
+        self
+    }
+
+    /// Sets the number of seconds WLED should wait after the last received
+    /// packet before reverting to its own effects.
+    pub fn timeout_secs(mut self: Box<Self>, timeout_secs: u8) -> Box<Self> {
+        self.timeout_secs = timeout_secs;
+
+        self
+    }
+
+    /// Sets the byte order each LED's color is serialized in on the wire.
+    ///
+    /// Most WLED setups expect `RGB`; set this to match whatever order the
+    /// target controller/strip actually expects if colors come out swapped.
+    pub fn order(mut self: Box<Self>, order: RGBOrder) -> Box<Self> {
+        self.order = order;
+
+        self
+    }
+
+    /// Sets the timer, which paces how often frames are streamed out.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`UdpDraw`] object.
+    pub fn build(self: Box<Self>) -> UdpDraw {
+        UdpDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for UdpDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{UdpDraw, UdpDrawBuilder, WledProtocol};
+    use ranos_core::Timer;
+    use ranos_ds::rgb::RGBOrder;
+
+    #[test]
+    fn test_serialize() {
+        let builder = UdpDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(dest:"255.255.255.255:21324",protocol:Drgb,timeout_secs:2,order:RGB,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(dest:"192.168.1.50:21324",protocol:Dnrgb,timeout_secs:2,order:GRB,timer:(target_dt:None),displays:[])"#;
+        let data: UdpDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.dest, "192.168.1.50:21324".parse().unwrap());
+        assert_eq!(data.protocol, WledProtocol::Dnrgb);
+        assert_eq!(data.timeout_secs, 2);
+        assert_eq!(data.order, RGBOrder::GRB);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Streams each rendered [`Frame`](ranos_ds::collections::Frame) over UDP to
+/// a [WLED][wled]-compatible realtime receiver, in the wire format selected
+/// by [`WledProtocol`].
+///
+/// To create a [`UdpDraw`] object, use the associated [builder](UdpDrawBuilder),
+/// accessed via [`UdpDraw::builder()`].
+///
+/// [wled]: https://kno.wled.ge/interfaces/udp-realtime/
+#[derive(Debug)]
+pub struct UdpDraw {
+    socket: UdpSocket,
+    dest: SocketAddr,
+    protocol: WledProtocol,
+    timeout_secs: u8,
+    order: RGBOrder,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl UdpDraw {
+    /// Constructs a builder object with safe default values: DRGB,
+    /// broadcasting to `255.255.255.255:21324` so it reaches any listening
+    /// controller on the LAN until a real destination is set.
+    pub fn builder() -> Box<UdpDrawBuilder> {
+        Box::new(UdpDrawBuilder {
+            dest: SocketAddr::new(Ipv4Addr::BROADCAST.into(), WLED_PORT),
+            protocol: WledProtocol::Drgb,
+            timeout_secs: 2,
+            order: RGBOrder::RGB,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<UdpDrawBuilder>) -> Self {
+        let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+        let is_broadcast = matches!(builder.dest.ip(), IpAddr::V4(ip) if ip.is_broadcast());
+        socket.set_broadcast(is_broadcast).unwrap();
+        // Don't let a full send buffer stall the render loop; a dropped
+        // frame is preferable to blocking the whole display pipeline.
+        socket.set_nonblocking(true).unwrap();
+
+        Self::new(
+            socket,
+            builder.dest,
+            builder.protocol,
+            builder.timeout_secs,
+            builder.order,
+            builder.timer,
+            builder.displays.drain(0..),
+        )
+    }
+
+    fn new<I>(
+        socket: UdpSocket,
+        dest: SocketAddr,
+        protocol: WledProtocol,
+        timeout_secs: u8,
+        order: RGBOrder,
+        timer: Timer,
+        display_iter: I,
+    ) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            socket,
+            dest,
+            protocol,
+            timeout_secs,
+            order,
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Scales the display's frame by its brightness exactly like [`TermDraw`](crate::TermDraw)
+    /// does, then serializes and streams it as one or more datagrams per [`WledProtocol`].
+    fn write_frame(&mut self, display_id: usize) {
+        let rgb: Vec<(u8, u8, u8)> = {
+            let frame = self.displays.get(&display_id).unwrap().0.frame();
+            frame
+                .iter()
+                .map(|led| led.scale(frame.brightness()).as_tuple(self.order))
+                .collect()
+        };
+
+        match self.protocol {
+            WledProtocol::Drgb => {
+                let mut packet = Vec::with_capacity(2 + rgb.len() * 3);
+                packet.push(2);
+                packet.push(self.timeout_secs);
+                for (r, g, b) in &rgb {
+                    packet.extend_from_slice(&[*r, *g, *b]);
+                }
+
+                let _ = self.socket.send_to(&packet, self.dest);
+            }
+            WledProtocol::Drgbw => {
+                let mut packet = Vec::with_capacity(2 + rgb.len() * 4);
+                packet.push(3);
+                packet.push(self.timeout_secs);
+                for (r, g, b) in &rgb {
+                    packet.extend_from_slice(&[*r, *g, *b, 0]);
+                }
+
+                let _ = self.socket.send_to(&packet, self.dest);
+            }
+            WledProtocol::Dnrgb => {
+                for (i, chunk) in rgb.chunks(MAX_LEDS_PER_PACKET).enumerate() {
+                    let start = (i * MAX_LEDS_PER_PACKET) as u16;
+
+                    let mut packet = Vec::with_capacity(4 + chunk.len() * 3);
+                    packet.push(4);
+                    packet.push(self.timeout_secs);
+                    packet.extend_from_slice(&start.to_be_bytes());
+                    for (r, g, b) in chunk {
+                        packet.extend_from_slice(&[*r, *g, *b]);
+                    }
+
+                    let _ = self.socket.send_to(&packet, self.dest);
+                }
+            }
+        }
+    }
+}
+
+impl Draw for UdpDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -0,0 +1,384 @@
+//! Headless drawer that loads a frame's downsampled colors onto the Linux
+//! virtual console's 16-color palette -- see [`VirtualConsoleDraw`].
+
+#![cfg(target_os = "linux")]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    io,
+    os::unix::io::AsRawFd,
+    path::PathBuf,
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+use ranos_ds::{
+    collections::Frame,
+    rgb::{RGBOrder, RGB},
+};
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// `ioctl` request number for loading the 16-color VGA-style palette onto a
+/// virtual console, as 16 packed `(red, green, blue)` triples. See `linux/kd.h`.
+const PIO_CMAP: libc::c_ulong = 0x4B71;
+
+/// `ioctl` request number for reading the console's current palette, in the
+/// same layout as [`PIO_CMAP`]. See `linux/kd.h`.
+const GIO_CMAP: libc::c_ulong = 0x4B70;
+
+/// `ioctl` request number for setting which keyboard LEDs are lit. See `linux/kd.h`.
+const KDSETLED: libc::c_ulong = 0x4B32;
+
+/// Bit of the `KDSETLED` mask corresponding to the caps lock LED, used as the
+/// [`VirtualConsoleDrawBuilder::blink_keyboard_led`] heartbeat.
+const LED_CAP: libc::c_ulong = 0b100;
+
+/// Virtual console device file this drawer opens when none is given via
+/// [`VirtualConsoleDrawBuilder::device`].
+const DEFAULT_DEVICE: &str = "/dev/tty0";
+
+/// Number of entries in the virtual console's palette.
+const PALETTE_SIZE: usize = 16;
+
+/// Retries `f` -- an `ioctl` call returning libc's raw `-1`-on-error,
+/// errno-set convention -- across `EINTR`, surfacing any other failure as an
+/// [`io::Error`] built from `errno`.
+fn retry_ioctl(f: impl Fn() -> libc::c_int) -> io::Result<()> {
+    loop {
+        if f() == 0 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EINTR) {
+            return Err(err);
+        }
+    }
+}
+
+/// Builder for [`VirtualConsoleDraw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "VirtualConsoleDraw")]
+pub struct VirtualConsoleDrawBuilder {
+    device: PathBuf,
+    blink_keyboard_led: bool,
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl VirtualConsoleDrawBuilder {
+    /// Sets which virtual console device file palette updates are sent to.
+    ///
+    /// Defaults to `/dev/tty0`; writing to it requires the permissions of
+    /// the console itself (root, or membership in the `tty` group on most distros).
+    pub fn device(mut self: Box<Self>, device: impl Into<PathBuf>) -> Box<Self> {
+        self.device = device.into();
+
+        self
+    }
+
+    /// Toggles flashing the caps lock keyboard LED once per frame via
+    /// `KDSETLED`, giving a heartbeat independent of the palette so a live
+    /// run can be told apart from a hung one at a glance.
+    pub fn blink_keyboard_led(mut self: Box<Self>, blink: bool) -> Box<Self> {
+        self.blink_keyboard_led = blink;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`VirtualConsoleDraw`] object, opening [`Self::device`]'s
+    /// device file for writing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device file can't be opened for writing.
+    pub fn build(self: Box<Self>) -> VirtualConsoleDraw {
+        VirtualConsoleDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for VirtualConsoleDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{VirtualConsoleDraw, VirtualConsoleDrawBuilder};
+    use ranos_core::Timer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = VirtualConsoleDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected =
+            r#"(device:"/dev/tty0",blink_keyboard_led:false,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input =
+            r#"(device:"/dev/tty1",blink_keyboard_led:true,timer:(target_dt:None),displays:[])"#;
+
+        let data: VirtualConsoleDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.device, PathBuf::from("/dev/tty1"));
+        assert_eq!(data.blink_keyboard_led, true);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drawer that downsamples a frame to the Linux virtual console's 16-color
+/// palette and loads it with the `PIO_CMAP` console `ioctl`, so a run can be
+/// visualized headless on a Pi with no attached LED strip and no GUI -- just
+/// a monitor showing a text console, whose whole screen repaints through the
+/// 16 palette entries each frame.
+///
+/// Each frame, [`Self::downsample`] buckets the frame's LEDs into at most
+/// [`PALETTE_SIZE`] consecutive spans and averages each bucket's brightness-scaled
+/// [`RGB`] value into one palette entry; the console itself only ever shows
+/// those 16 solid colors; finer detail than that is lost by design, the same
+/// tradeoff as [`crate::TermDraw`]'s terminal grid, just coarser.
+///
+/// The console's palette is whatever it was before this drawer started --
+/// snapshotted via `GIO_CMAP` when it's built -- is restored on `Drop`, so a
+/// run doesn't leave the console's colors permanently changed.
+///
+/// To create a [`VirtualConsoleDraw`] object, use the associated
+/// [builder](VirtualConsoleDrawBuilder), accessed via [`VirtualConsoleDraw::builder()`].
+#[derive(Debug)]
+pub struct VirtualConsoleDraw {
+    console: File,
+    blink_keyboard_led: bool,
+    led_lit: bool,
+    original_cmap: [u8; PALETTE_SIZE * 3],
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl VirtualConsoleDraw {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<VirtualConsoleDrawBuilder> {
+        Box::new(VirtualConsoleDrawBuilder {
+            device: PathBuf::from(DEFAULT_DEVICE),
+            blink_keyboard_led: false,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<VirtualConsoleDrawBuilder>) -> Self {
+        Self::new(
+            builder.device,
+            builder.blink_keyboard_led,
+            builder.timer,
+            builder.displays.drain(0..),
+        )
+    }
+
+    fn new<I>(device: PathBuf, blink_keyboard_led: bool, timer: Timer, display_iter: I) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let console = OpenOptions::new()
+            .write(true)
+            .open(&device)
+            .unwrap_or_else(|e| panic!("failed to open console device {:?}: {}", device, e));
+
+        let mut original_cmap = [0_u8; PALETTE_SIZE * 3];
+        let fd = console.as_raw_fd();
+        let cmap_ptr = original_cmap.as_mut_ptr();
+        retry_ioctl(|| unsafe { libc::ioctl(fd, GIO_CMAP, cmap_ptr) })
+            .unwrap_or_else(|e| panic!("failed to read console palette: {}", e));
+
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            console,
+            blink_keyboard_led,
+            led_lit: false,
+            original_cmap,
+
+            displays,
+            display_ids: ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Buckets `frame`'s LEDs into consecutive spans of
+    /// `ceil(len / PALETTE_SIZE)` LEDs (the last span may be shorter) and
+    /// averages each bucket's brightness-scaled [`RGB`] value into one palette
+    /// entry. A frame that yields fewer than [`PALETTE_SIZE`] spans leaves the
+    /// remaining entries black.
+    fn downsample(frame: &Frame) -> [RGB; PALETTE_SIZE] {
+        let leds = frame.as_slice();
+        let brightness = frame.brightness();
+        let mut palette = [RGB::new(); PALETTE_SIZE];
+
+        if leds.is_empty() {
+            return palette;
+        }
+
+        let bucket_size = (leds.len() + PALETTE_SIZE - 1) / PALETTE_SIZE;
+        for (entry, bucket) in palette.iter_mut().zip(leds.chunks(bucket_size.max(1))) {
+            let (mut r, mut g, mut b) = (0_u32, 0_u32, 0_u32);
+            for led in bucket {
+                let led = led.scale(brightness);
+                r += led.red() as u32;
+                g += led.green() as u32;
+                b += led.blue() as u32;
+            }
+
+            let n = bucket.len() as u32;
+            *entry = RGB::from_tuple((
+                (r / n) as u8,
+                (g / n) as u8,
+                (b / n) as u8,
+            ), RGBOrder::RGB);
+        }
+
+        palette
+    }
+
+    /// Downsamples the given display's current frame and loads it onto the
+    /// console's palette, additionally flashing the caps lock keyboard LED
+    /// when [`VirtualConsoleDrawBuilder::blink_keyboard_led`] is set.
+    fn write_frame(&mut self, display_id: usize) {
+        let frame = self.displays.get(&display_id).unwrap().0.frame();
+        let palette = Self::downsample(frame);
+
+        let mut cmap = [0_u8; PALETTE_SIZE * 3];
+        for (i, color) in palette.iter().enumerate() {
+            let (r, g, b) = color.as_tuple(RGBOrder::RGB);
+            cmap[i * 3] = r;
+            cmap[i * 3 + 1] = g;
+            cmap[i * 3 + 2] = b;
+        }
+
+        let fd = self.console.as_raw_fd();
+        let cmap_ptr = cmap.as_mut_ptr();
+        retry_ioctl(|| unsafe { libc::ioctl(fd, PIO_CMAP, cmap_ptr) }).unwrap();
+
+        if self.blink_keyboard_led {
+            self.led_lit = !self.led_lit;
+            let mask: libc::c_ulong = if self.led_lit { LED_CAP } else { 0 };
+            retry_ioctl(|| unsafe { libc::ioctl(fd, KDSETLED, mask) }).unwrap();
+        }
+    }
+}
+
+impl Drop for VirtualConsoleDraw {
+    /// Restores the console's palette to what it was before this drawer
+    /// loaded its own colors onto it, so the console is left usable once the
+    /// run ends rather than stuck showing the last frame's 16 colors.
+    fn drop(&mut self) {
+        let fd = self.console.as_raw_fd();
+        let cmap_ptr = self.original_cmap.as_mut_ptr();
+        let _ = retry_ioctl(|| unsafe { libc::ioctl(fd, PIO_CMAP, cmap_ptr) });
+    }
+}
+
+impl Draw for VirtualConsoleDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
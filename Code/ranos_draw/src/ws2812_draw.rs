@@ -0,0 +1,326 @@
+//! Drives WS2812/NeoPixel LEDs over SPI through the [`ws2812_spi`]/[`smart_leds`] crates,
+//! alongside [`pi_draw`][crate::pi_draw]'s APA102C/SK9822 support for the clocked protocol.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+use serde::{Deserialize, Serialize};
+use smart_leds::{SmartLedsWrite, RGB8};
+use ws2812_spi::Ws2812;
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// The default SPI bus to drive the data line over.
+pub const DEFAULT_BUS: Bus = Bus::Spi0;
+/// The default SPI chip-select slave to use.
+pub const DEFAULT_SLAVE_SELECT: SlaveSelect = SlaveSelect::Ss0;
+
+/// Builder for [`Ws2812Draw`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Ws2812Draw")]
+pub struct Ws2812DrawBuilder {
+    #[serde(with = "bus_serde")]
+    bus: Bus,
+    #[serde(with = "slave_select_serde")]
+    slave_select: SlaveSelect,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl Ws2812DrawBuilder {
+    /// Sets the SPI bus the data line is wired to.
+    pub fn bus(mut self: Box<Self>, bus: Bus) -> Box<Self> {
+        self.bus = bus;
+
+        self
+    }
+
+    /// Sets the SPI chip-select slave the data line is wired to.
+    pub fn slave_select(mut self: Box<Self>, slave_select: SlaveSelect) -> Box<Self> {
+        self.slave_select = slave_select;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`Ws2812Draw`] object.
+    pub fn build(self: Box<Self>) -> Ws2812Draw {
+        Ws2812Draw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for Ws2812DrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use crate::{Ws2812Draw, Ws2812DrawBuilder};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Ws2812Draw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(bus:"Spi0",slave_select:"Ss0",timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(bus:"Spi0",slave_select:"Ss0",timer:(target_dt:None),displays:[])"#;
+        let data: Ws2812DrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drives a strip of clockless WS2812/NeoPixel LEDs over SPI, using the
+/// [`ws2812_spi`] driver (an implementer of [`smart_leds::SmartLedsWrite`])
+/// to shift each [`RGB`][ranos_ds::rgb::RGB] out as the strip's one-wire
+/// protocol expects.
+///
+/// To create a [`Ws2812Draw`] object, use the associated
+/// [builder](Ws2812DrawBuilder), accessed via [`Ws2812Draw::builder()`].
+#[derive(Debug)]
+pub struct Ws2812Draw {
+    leds: Ws2812<Spi>,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl Ws2812Draw {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<Ws2812DrawBuilder> {
+        Box::new(Ws2812DrawBuilder {
+            bus: DEFAULT_BUS,
+            slave_select: DEFAULT_SLAVE_SELECT,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<Ws2812DrawBuilder>) -> Self {
+        let spi = Spi::new(builder.bus, builder.slave_select, 3_000_000, Mode::Mode0).unwrap();
+
+        Self::new(Ws2812::new(spi), builder.timer, builder.displays.drain(0..))
+    }
+
+    fn new<I>(leds: Ws2812<Spi>, timer: Timer, display_iter: I) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            leds,
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Scales and converts the display's frame buffer to [`RGB8`] and shifts
+    /// it out over SPI.
+    fn write_frame(&mut self, display_id: usize) {
+        let frame = self.displays.get(&display_id).unwrap().0.frame();
+
+        let pixels: Vec<RGB8> = frame
+            .iter()
+            .map(|led| {
+                let led = led.scale(frame.brightness());
+                RGB8 {
+                    r: led.red(),
+                    g: led.green(),
+                    b: led.blue(),
+                }
+            })
+            .collect();
+
+        self.leds.write(pixels.into_iter()).unwrap();
+    }
+
+    /// Writes an all-black frame to the strip, sized to the largest display
+    /// this drawer owns. Called when [`SIGINT`] interrupts [`Draw::run`] so
+    /// the strip goes dark instead of freezing lit on whatever frame
+    /// happened to be showing.
+    fn blank(&mut self) {
+        let len = self
+            .displays
+            .values()
+            .map(|(d, _)| d.frame().len())
+            .max()
+            .unwrap_or(0);
+
+        self.leds
+            .write(std::iter::repeat(RGB8 { r: 0, g: 0, b: 0 }).take(len))
+            .unwrap();
+    }
+}
+
+impl Draw for Ws2812Draw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    self.blank();
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
+
+/// `(de)serialize`s [`Bus`] by name, since `rppal` doesn't derive `serde` for it.
+pub(crate) mod bus_serde {
+    use rppal::spi::Bus;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(bus: &Bus, s: S) -> Result<S::Ok, S::Error> {
+        match bus {
+            Bus::Spi0 => "Spi0",
+            Bus::Spi1 => "Spi1",
+            Bus::Spi2 => "Spi2",
+            Bus::Spi3 => "Spi3",
+            Bus::Spi4 => "Spi4",
+            Bus::Spi5 => "Spi5",
+            Bus::Spi6 => "Spi6",
+        }
+        .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Bus, D::Error> {
+        match String::deserialize(d)?.as_str() {
+            "Spi0" => Ok(Bus::Spi0),
+            "Spi1" => Ok(Bus::Spi1),
+            "Spi2" => Ok(Bus::Spi2),
+            "Spi3" => Ok(Bus::Spi3),
+            "Spi4" => Ok(Bus::Spi4),
+            "Spi5" => Ok(Bus::Spi5),
+            "Spi6" => Ok(Bus::Spi6),
+            other => Err(serde::de::Error::custom(format!("unknown SPI bus: {}", other))),
+        }
+    }
+}
+
+/// `(de)serialize`s [`SlaveSelect`] by name, since `rppal` doesn't derive `serde` for it.
+pub(crate) mod slave_select_serde {
+    use rppal::spi::SlaveSelect;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(ss: &SlaveSelect, s: S) -> Result<S::Ok, S::Error> {
+        match ss {
+            SlaveSelect::Ss0 => "Ss0",
+            SlaveSelect::Ss1 => "Ss1",
+            SlaveSelect::Ss2 => "Ss2",
+        }
+        .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<SlaveSelect, D::Error> {
+        match String::deserialize(d)?.as_str() {
+            "Ss0" => Ok(SlaveSelect::Ss0),
+            "Ss1" => Ok(SlaveSelect::Ss1),
+            "Ss2" => Ok(SlaveSelect::Ss2),
+            other => Err(serde::de::Error::custom(format!(
+                "unknown SPI slave select: {}",
+                other
+            ))),
+        }
+    }
+}
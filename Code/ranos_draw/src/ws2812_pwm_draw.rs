@@ -0,0 +1,275 @@
+//! Drives WS2812/SK6812 LEDs over the Pi's PWM+DMA peripheral through the
+//! [`rs_ws281x`] bindings to Jeremy Garff's `rpi_ws281x` library, rather than
+//! riding the SPI peripheral the way [`ws2812_draw`][crate::ws2812_draw] and
+//! [`pi_draw::WS2812PiDraw`][crate::pi_draw::WS2812PiDraw] do. PWM output
+//! frees up the SPI bus for other devices (e.g. an [`Apa102Draw`][crate::apa102_draw::Apa102Draw]
+//! strip sharing the same Pi) at the cost of depending on the native
+//! `rpi_ws281x` library instead of a pure-Rust driver.
+//!
+//! Gated behind the `ws2812_pwm` cargo feature, on top of the `target_os =
+//! "linux"` restriction every other Pi-only backend in this crate shares, so
+//! that pulling in the native library is opt-in even for Pi builds.
+
+#![cfg(all(target_os = "linux", feature = "ws2812_pwm"))]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use rs_ws281x::{ChannelBuilder, Controller, ControllerBuilder, StripType};
+use serde::{Deserialize, Serialize};
+
+use ranos_core::Timer;
+use ranos_display::DisplayState;
+
+use crate::diagnostics::RenderDiagnostics;
+
+use super::*;
+
+/// The PWM channel used to drive the strip; the `rpi_ws281x` library numbers
+/// its two supported PWM channels `0` and `1`, and this backend only ever
+/// drives one strip, so it's always channel `0`.
+const PWM_CHANNEL: usize = 0;
+/// The signal frequency WS2812/SK6812 strips expect their PWM-encoded data at.
+const PWM_FREQ_HZ: u32 = 800_000;
+/// The DMA channel used to feed the PWM peripheral; `10` is the value the
+/// `rpi_ws281x` examples and most Pi HAT vendors settle on, as it doesn't
+/// collide with DMA channels Linux itself tends to claim.
+const DEFAULT_DMA_CHANNEL: i32 = 10;
+
+/// Builder for [`Ws2812PwmDraw`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Ws2812PwmDraw")]
+pub struct Ws2812PwmDrawBuilder {
+    pin: i32,
+    count: usize,
+
+    timer: Timer,
+    displays: VecDeque<DisplayBuilder>,
+}
+
+impl Ws2812PwmDrawBuilder {
+    /// Sets the GPIO pin (BCM numbering) the strip's data line is wired to.
+    /// Must be one of the Pi's PWM-capable pins (e.g. `18` for PWM0).
+    pub fn pin(mut self: Box<Self>, pin: i32) -> Box<Self> {
+        self.pin = pin;
+
+        self
+    }
+
+    /// Sets the number of LEDs on the strip, so the PWM buffer can be sized
+    /// up front.
+    pub fn count(mut self: Box<Self>, count: usize) -> Box<Self> {
+        self.count = count;
+
+        self
+    }
+
+    /// Sets the timer.
+    pub fn timer(mut self: Box<Self>, timer: Timer) -> Box<Self> {
+        self.timer = timer;
+
+        self
+    }
+
+    /// Add a builder for a display that will be built at the same time as this builder.
+    ///
+    /// Be sure to add generators to the display builder before adding it to the drawer as it will be inaccessible afterwards.
+    ///
+    /// Note: Multiple [`DisplayBuilder`]s can be added.
+    pub fn display(mut self: Box<Self>, display: DisplayBuilder) -> Box<Self> {
+        self.displays.push_back(display);
+
+        self
+    }
+
+    /// Constructs a [`Ws2812PwmDraw`] object.
+    pub fn build(self: Box<Self>) -> Ws2812PwmDraw {
+        Ws2812PwmDraw::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl DrawBuilder for Ws2812PwmDrawBuilder {
+    fn timer(self: Box<Self>, timer: Timer) -> Box<dyn DrawBuilder> {
+        self.timer(timer)
+    }
+
+    fn display(self: Box<Self>, display: DisplayBuilder) -> Box<dyn DrawBuilder> {
+        self.display(display)
+    }
+
+    fn build(self: Box<Self>) -> Box<dyn Draw> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Ws2812PwmDraw, Ws2812PwmDrawBuilder};
+    use ranos_core::Timer;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Ws2812PwmDraw::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(pin:18,count:0,timer:(target_dt:None),displays:[])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(pin:18,count:144,timer:(target_dt:None),displays:[])"#;
+        let data: Ws2812PwmDrawBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.pin, 18);
+        assert_eq!(data.count, 144);
+        assert_eq!(data.timer, Timer::new(None));
+        assert_eq!(data.displays.len(), 0);
+    }
+}
+
+/// Drives a strip of clockless WS2812/SK6812 LEDs over the Pi's PWM+DMA
+/// peripheral via [`rs_ws281x`], rather than hand-encoding or driving the
+/// protocol over SPI the way this crate's other WS2812 backends do.
+///
+/// To create a [`Ws2812PwmDraw`] object, use the associated
+/// [builder](Ws2812PwmDrawBuilder), accessed via [`Ws2812PwmDraw::builder()`].
+#[derive(Debug)]
+pub struct Ws2812PwmDraw {
+    controller: Controller,
+
+    displays: HashMap<usize, (Display, bool)>,
+    display_ids: Vec<usize>,
+
+    timer: Timer,
+    diagnostics: RenderDiagnostics,
+}
+
+impl Ws2812PwmDraw {
+    /// Constructs a builder object with safe default values: PWM0 on GPIO 18
+    /// with no LEDs configured, which must be set via [`Ws2812PwmDrawBuilder::count`]
+    /// before building.
+    pub fn builder() -> Box<Ws2812PwmDrawBuilder> {
+        Box::new(Ws2812PwmDrawBuilder {
+            pin: 18,
+            count: 0,
+            timer: Timer::new(None),
+            displays: VecDeque::new(),
+        })
+    }
+
+    fn from_builder(mut builder: Box<Ws2812PwmDrawBuilder>) -> Self {
+        let controller = ControllerBuilder::new()
+            .freq(PWM_FREQ_HZ)
+            .dma(DEFAULT_DMA_CHANNEL)
+            .channel(
+                PWM_CHANNEL,
+                ChannelBuilder::new()
+                    .pin(builder.pin)
+                    .count(builder.count as i32)
+                    .strip_type(StripType::Ws2812)
+                    .brightness(255)
+                    .build(),
+            )
+            .build()
+            .unwrap();
+
+        Self::new(controller, builder.timer, builder.displays.drain(0..))
+    }
+
+    fn new<I>(controller: Controller, timer: Timer, display_iter: I) -> Self
+    where
+        I: Iterator<Item = DisplayBuilder>,
+    {
+        let mut ids = Vec::new();
+        let displays = display_iter
+            .map(|b| {
+                let disp = b.build();
+                ids.push(disp.id());
+                (disp.id(), (disp, false))
+            })
+            .collect();
+        let display_ids = ids;
+
+        let diagnostics = RenderDiagnostics::new(timer.target_dt());
+
+        Self {
+            controller,
+
+            displays,
+            display_ids,
+
+            timer,
+            diagnostics,
+        }
+    }
+
+    /// Scales the display's frame buffer by its brightness and copies it into
+    /// the controller's PWM pixel buffer, GRB-ordered to match the strip's
+    /// wire format like every other WS2812 backend in this crate, then renders it.
+    fn write_frame(&mut self, display_id: usize) {
+        let frame = self.displays.get(&display_id).unwrap().0.frame();
+
+        let leds = self.controller.leds_mut(PWM_CHANNEL);
+        for (dst, led) in leds.iter_mut().zip(frame.iter()) {
+            let led = led.scale(frame.brightness());
+            *dst = [led.green(), led.red(), led.blue(), 0];
+        }
+
+        self.controller.render().unwrap();
+    }
+}
+
+impl Draw for Ws2812PwmDraw {
+    fn run(&mut self) {
+        self.timer.reset();
+        self.diagnostics.reset();
+
+        let mut num_finished = 0;
+
+        while num_finished < self.displays.len() {
+            let dt = self.timer.ping();
+
+            for i in 0..self.displays.len() {
+                let frame_start = Instant::now();
+
+                let display_id = {
+                    let (d, has_finished) = self.displays.get_mut(&self.display_ids[i]).unwrap();
+
+                    if !*has_finished {
+                        match d.render_frame(dt) {
+                            DisplayState::Ok => (),
+                            DisplayState::Done => {
+                                *has_finished = true;
+                                num_finished += 1;
+                            }
+                            DisplayState::Err => return,
+                        }
+                    }
+
+                    d.id()
+                };
+
+                self.write_frame(display_id);
+                let proportion = self.diagnostics.record(frame_start.elapsed());
+                self.displays.get_mut(&display_id).unwrap().0.qos(proportion);
+
+                if SIGINT.load(Ordering::Relaxed) == true {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> &TimerStats {
+        self.timer.stats()
+    }
+
+    fn diagnostics(&self) -> &RenderDiagnostics {
+        &self.diagnostics
+    }
+}
@@ -25,19 +25,22 @@ impl Frame {
 
     /// Attempts to read a `Frame` from the `reader`.
     ///
+    /// `brightness` and the LED count are read as fixed-width, little-endian
+    /// fields, matching [`Self::write`], so a `Frame` recorded on one
+    /// machine reads back correctly on another regardless of its native
+    /// endianness or pointer width.
+    ///
     /// # Errors
     ///
     /// This function returns an error if the `reader` encounters an error while reading.
     pub fn read<R: io::Read>(reader: &mut R) -> io::Result<Frame> {
-        use std::mem::size_of;
-
-        let mut brightness_buf = [0_u8; size_of::<f32>()];
+        let mut brightness_buf = [0_u8; 4];
         reader.read_exact(&mut brightness_buf)?;
-        let brightness = f32::from_ne_bytes(brightness_buf);
+        let brightness = f32::from_le_bytes(brightness_buf);
 
-        let mut len_buf = [0_u8; size_of::<usize>()];
+        let mut len_buf = [0_u8; 4];
         reader.read_exact(&mut len_buf)?;
-        let len = usize::from_ne_bytes(len_buf);
+        let len = u32::from_le_bytes(len_buf) as usize;
 
         let leds = RGB::read_n(reader, len, RGBOrder::RGB)?;
 
@@ -46,17 +49,22 @@ impl Frame {
 
     /// Attempts to write a `Frame` to the `writer`, returning the number of bytes written.
     ///
+    /// `brightness` is written as a little-endian `f32`, and the LED count as
+    /// a little-endian `u32` (not a pointer-width `usize`), so the resulting
+    /// bytes are portable across machines instead of only being readable by
+    /// another process with the same endianness and pointer width.
+    ///
     /// # Errors
     ///
     /// This function returns an error if the `writer` encounters an error while writing.
     pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<usize> {
         let mut count = 0;
 
-        let brightness_buf = self.brightness.to_ne_bytes();
+        let brightness_buf = self.brightness.to_le_bytes();
         writer.write_all(&brightness_buf)?;
         count += brightness_buf.len();
 
-        let len_buf = self.leds.len().to_ne_bytes();
+        let len_buf = (self.leds.len() as u32).to_le_bytes();
         writer.write_all(&len_buf)?;
         count += len_buf.len();
 
@@ -65,6 +73,66 @@ impl Frame {
         Ok(count)
     }
 
+    /// Attempts to read a `Frame` from `reader`, rejecting a led-count above
+    /// `max_leds` with an [`io::ErrorKind::InvalidData`] error rather than
+    /// trusting it enough to allocate, so a corrupt or hostile header can't
+    /// trigger an unbounded allocation the way [`Self::read`] would.
+    ///
+    /// The wire format otherwise matches [`Self::read`]/[`Self::write`]
+    /// (little-endian `f32` brightness, then little-endian `u32` led count,
+    /// then that many [`RGB`]s), with `order` supplied by the caller instead
+    /// of being fixed to [`RGBOrder::RGB`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the led count exceeds `max_leds`, or if `reader` encounters an error while reading.
+    pub fn read_stream<R: io::Read>(
+        reader: &mut R,
+        order: RGBOrder,
+        max_leds: usize,
+    ) -> io::Result<Frame> {
+        let mut brightness_buf = [0_u8; 4];
+        reader.read_exact(&mut brightness_buf)?;
+        let brightness = f32::from_le_bytes(brightness_buf);
+
+        let mut len_buf = [0_u8; 4];
+        reader.read_exact(&mut len_buf)?;
+        let len = u32::from_le_bytes(len_buf) as usize;
+
+        if len > max_leds {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame led count {} exceeds max_leds {}", len, max_leds),
+            ));
+        }
+
+        let leds = RGB::read_n(reader, len, order)?;
+
+        Ok(Self { brightness, leds })
+    }
+
+    /// Attempts to write a `Frame` to `writer` in the given `order`,
+    /// returning the number of bytes written, in the same wire format as [`Self::write`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the `writer` encounters an error while writing.
+    pub fn write_stream<W: io::Write>(&self, writer: &mut W, order: RGBOrder) -> io::Result<usize> {
+        let mut count = 0;
+
+        let brightness_buf = self.brightness.to_le_bytes();
+        writer.write_all(&brightness_buf)?;
+        count += brightness_buf.len();
+
+        let len_buf = (self.leds.len() as u32).to_le_bytes();
+        writer.write_all(&len_buf)?;
+        count += len_buf.len();
+
+        count += RGB::write_slice(&self.leds, writer, order)?;
+
+        Ok(count)
+    }
+
     /// Returns the brightness in range [0, 1].
     pub fn brightness(&self) -> f32 {
         self.brightness
@@ -86,6 +154,12 @@ impl Frame {
         self.leds.len()
     }
 
+    /// Resizes the internal buffer to `size`, truncating or padding with
+    /// black (default) [`RGB`] values as needed.
+    pub fn resize(&mut self, size: usize) {
+        self.leds.resize(size, Default::default());
+    }
+
     /// Returns the internal buffer as a immutable slice.
     pub fn as_slice(&self) -> &[RGB] {
         &self.leds
@@ -114,3 +188,39 @@ impl std::ops::Index<usize> for Frame {
         &self.leds[ind]
     }
 }
+
+#[cfg(test)]
+mod frame_test {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_stream_write_stream_round_trip() {
+        let mut frame = Frame::new(0.5, 3);
+        frame.as_mut_slice()[0] = RGB::from_tuple((1, 2, 3), RGBOrder::RGB);
+        frame.as_mut_slice()[1] = RGB::from_tuple((4, 5, 6), RGBOrder::RGB);
+        frame.as_mut_slice()[2] = RGB::from_tuple((7, 8, 9), RGBOrder::RGB);
+
+        let mut buf = Vec::new();
+        frame.write_stream(&mut buf, RGBOrder::RGB).unwrap();
+
+        let mut reader = Cursor::new(buf);
+        let read_back = Frame::read_stream(&mut reader, RGBOrder::RGB, 3).unwrap();
+
+        assert_eq!(read_back.brightness(), frame.brightness());
+        assert_eq!(read_back.as_slice(), frame.as_slice());
+    }
+
+    #[test]
+    fn read_stream_rejects_a_count_above_max_leds() {
+        let frame = Frame::new(1.0, 4);
+
+        let mut buf = Vec::new();
+        frame.write_stream(&mut buf, RGBOrder::RGB).unwrap();
+
+        let mut reader = Cursor::new(buf);
+        let err = Frame::read_stream(&mut reader, RGBOrder::RGB, 3).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
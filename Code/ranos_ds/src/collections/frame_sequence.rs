@@ -0,0 +1,272 @@
+//! # FrameSequence
+//!
+//! A portable, versioned container of `(Duration, Frame)` records, so a
+//! whole animation's output can be captured once -- via
+//! [`FrameSequence::push`] -- and replayed later with [`FrameSequencePlayer`],
+//! without recomputing it, e.g. on constrained hardware.
+//!
+//! ## Format
+//!
+//! ```text
+//! header: magic: [u8; 8] = b"RANOSSEQ", version: u8, record_count: u32
+//! record: duration_secs: u32, duration_nanos: u32, frame: Frame
+//! ```
+//!
+//! All integers are little-endian; `frame` is encoded via [`Frame::write`],
+//! itself little-endian end to end. `version` lets a future reader recognize
+//! (and reject, or migrate) a container written by an incompatible writer
+//! instead of silently misparsing it.
+
+use std::io;
+use std::slice::Iter;
+use std::time::Duration;
+
+use super::frame::Frame;
+use crate::rgb::RGBOrder;
+
+/// 8-byte magic identifying a stream written by [`FrameSequence::write`].
+pub const MAGIC: &[u8; 8] = b"RANOSSEQ";
+
+/// The container format version this build reads and writes.
+pub const VERSION: u8 = 1;
+
+/// Upper bound on the `record_count` [`FrameSequence::read`] will allocate
+/// space for up front, so a corrupt or hostile header claiming an enormous
+/// count can't force an unbounded allocation before a single record has
+/// actually been read.
+pub const MAX_RECORDS: u32 = 1_000_000;
+
+/// Upper bound on the per-record LED count [`FrameSequence::read`] passes
+/// through to [`Frame::read_stream`], for the same reason as [`MAX_RECORDS`].
+pub const MAX_LEDS: usize = 1_000_000;
+
+/// Errors produced while reading a [`FrameSequence`].
+#[derive(Debug)]
+pub enum ReadError {
+    /// An I/O error occurred while reading the stream.
+    Io(io::Error),
+    /// The stream didn't start with [`MAGIC`].
+    BadMagic,
+    /// The stream's version byte doesn't match [`VERSION`].
+    UnsupportedVersion(u8),
+    /// The stream's `record_count` exceeds [`MAX_RECORDS`].
+    TooManyRecords(u32),
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "I/O error reading frame sequence: {}", e),
+            ReadError::BadMagic => write!(f, "stream did not start with the expected magic bytes"),
+            ReadError::UnsupportedVersion(v) => {
+                write!(f, "unsupported frame sequence version {}, expected {}", v, VERSION)
+            }
+            ReadError::TooManyRecords(count) => write!(
+                f,
+                "frame sequence record count {} exceeds max_records {}",
+                count, MAX_RECORDS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+/// An in-memory, ordered list of `(Duration, Frame)` records: how long each
+/// frame should be shown for, and the frame itself.
+#[derive(Debug, Default, Clone)]
+pub struct FrameSequence {
+    records: Vec<(Duration, Frame)>,
+}
+
+impl FrameSequence {
+    /// Creates a new, empty sequence.
+    pub fn new() -> Self {
+        Self { records: Vec::new() }
+    }
+
+    /// Appends a record to the end of the sequence.
+    pub fn push(&mut self, duration: Duration, frame: Frame) {
+        self.records.push((duration, frame));
+    }
+
+    /// Returns the number of records in the sequence.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if the sequence has no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns an immutable iterator over the sequence's records.
+    pub fn iter(&self) -> Iter<(Duration, Frame)> {
+        self.records.iter()
+    }
+
+    /// Returns the record at `ind`, if any.
+    pub fn get(&self, ind: usize) -> Option<&(Duration, Frame)> {
+        self.records.get(ind)
+    }
+
+    /// Writes this sequence to `writer` as a versioned, little-endian container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `writer` encounters an error while writing.
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(self.records.len() as u32).to_le_bytes())?;
+
+        for (duration, frame) in &self.records {
+            writer.write_all(&(duration.as_secs() as u32).to_le_bytes())?;
+            writer.write_all(&duration.subsec_nanos().to_le_bytes())?;
+            frame.write(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a sequence previously written by [`Self::write`] from `reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream doesn't start with [`MAGIC`], its
+    /// version doesn't match [`VERSION`], its `record_count` exceeds
+    /// [`MAX_RECORDS`], a record's LED count exceeds [`MAX_LEDS`], or the
+    /// `reader` encounters an I/O error. The count checks reject a corrupt or
+    /// hostile header before allocating for it, rather than trusting it the
+    /// way [`Frame::read`] would.
+    pub fn read<R: io::Read>(reader: &mut R) -> Result<Self, ReadError> {
+        let mut magic = [0_u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(ReadError::BadMagic);
+        }
+
+        let mut version_buf = [0_u8; 1];
+        reader.read_exact(&mut version_buf)?;
+        if version_buf[0] != VERSION {
+            return Err(ReadError::UnsupportedVersion(version_buf[0]));
+        }
+
+        let mut count_buf = [0_u8; 4];
+        reader.read_exact(&mut count_buf)?;
+        let count = u32::from_le_bytes(count_buf);
+
+        if count > MAX_RECORDS {
+            return Err(ReadError::TooManyRecords(count));
+        }
+
+        let mut records = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let mut secs_buf = [0_u8; 4];
+            reader.read_exact(&mut secs_buf)?;
+            let secs = u32::from_le_bytes(secs_buf) as u64;
+
+            let mut nanos_buf = [0_u8; 4];
+            reader.read_exact(&mut nanos_buf)?;
+            let nanos = u32::from_le_bytes(nanos_buf);
+
+            let frame = Frame::read_stream(reader, RGBOrder::RGB, MAX_LEDS)?;
+
+            records.push((Duration::new(secs, nanos), frame));
+        }
+
+        Ok(Self { records })
+    }
+}
+
+/// Streams a [`FrameSequence`] back one record at a time, pacing itself by
+/// each record's own duration rather than the cadence [`Self::advance`] is called at.
+#[derive(Debug)]
+pub struct FrameSequencePlayer {
+    sequence: FrameSequence,
+    ind: usize,
+    elapsed: Duration,
+}
+
+impl FrameSequencePlayer {
+    /// Creates a player that starts at the first record of `sequence`.
+    pub fn new(sequence: FrameSequence) -> Self {
+        Self {
+            sequence,
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+        }
+    }
+
+    /// The frame that should currently be displayed, or `None` once every record has played.
+    pub fn current(&self) -> Option<&Frame> {
+        self.sequence.records.get(self.ind).map(|(_, frame)| frame)
+    }
+
+    /// Advances playback by `dt`, stepping past as many records as `dt`
+    /// spans (e.g. if a caller's render cadence is slower than the
+    /// sequence's own timing).
+    ///
+    /// Returns `true` while there's still a record to show, `false` once playback has finished.
+    pub fn advance(&mut self, dt: Duration) -> bool {
+        self.elapsed += dt;
+
+        while let Some((duration, _)) = self.sequence.records.get(self.ind) {
+            if self.elapsed < *duration {
+                break;
+            }
+
+            self.elapsed -= *duration;
+            self.ind += 1;
+        }
+
+        self.ind < self.sequence.records.len()
+    }
+}
+
+#[cfg(test)]
+mod frame_sequence_test {
+    use super::*;
+    use crate::rgb::RGB;
+    use std::io::Cursor;
+
+    #[test]
+    fn write_read_round_trip() {
+        let mut sequence = FrameSequence::new();
+
+        let mut frame = Frame::new(0.5, 2);
+        frame.as_mut_slice()[0] = RGB::from_tuple((1, 2, 3), RGBOrder::RGB);
+        frame.as_mut_slice()[1] = RGB::from_tuple((4, 5, 6), RGBOrder::RGB);
+        sequence.push(Duration::new(0, 500_000_000), frame);
+
+        let mut buf = Vec::new();
+        sequence.write(&mut buf).unwrap();
+
+        let mut reader = Cursor::new(buf);
+        let read_back = FrameSequence::read(&mut reader).unwrap();
+
+        assert_eq!(read_back.len(), 1);
+        let (duration, frame) = read_back.get(0).unwrap();
+        assert_eq!(*duration, Duration::new(0, 500_000_000));
+        assert_eq!(frame.as_slice(), sequence.get(0).unwrap().1.as_slice());
+    }
+
+    #[test]
+    fn read_rejects_a_record_count_above_max_records() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        buf.extend_from_slice(&(MAX_RECORDS + 1).to_le_bytes());
+
+        let mut reader = Cursor::new(buf);
+        let err = FrameSequence::read(&mut reader).unwrap_err();
+
+        assert!(matches!(err, ReadError::TooManyRecords(count) if count == MAX_RECORDS + 1));
+    }
+}
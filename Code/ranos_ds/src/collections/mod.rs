@@ -1,7 +1,9 @@
 //! Module of collections used within this project.
 
 pub use frame::Frame;
+pub use frame_sequence::{FrameSequence, FrameSequencePlayer};
 pub use sparse_vec::SparseVecHeap as SparseVec; // Choose heap-allocation as the default `SparseVec` type
 
 pub mod frame;
+pub mod frame_sequence;
 pub mod sparse_vec;
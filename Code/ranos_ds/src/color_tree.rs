@@ -0,0 +1,256 @@
+//! k-d tree nearest-color quantizer, for snapping arbitrary colors to a
+//! fixed palette -- see [`ColorTree`].
+
+use crate::rgb::RGB;
+
+/// One node of a [`ColorTree`]'s 3-dimensional k-d tree: a palette color, the
+/// axis (0 = red, 1 = green, 2 = blue) it was split on, and the subtrees of
+/// palette colors below/above it along that axis.
+#[derive(Debug, Clone)]
+struct Node {
+    color: RGB,
+    axis: usize,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+/// A 3-dimensional k-d tree built over a fixed palette of [`RGB`] colors,
+/// answering "which palette color is closest" queries in roughly `O(log n)`
+/// time instead of the `O(n)` a linear scan over the palette would need.
+///
+/// Useful for animations or [`crate`] consumers targeting fixed-palette
+/// hardware (e.g. a 16-color console palette), where an arbitrary computed
+/// color has to be snapped to whatever the device can actually display.
+#[derive(Debug, Clone)]
+pub struct ColorTree {
+    root: Box<Node>,
+}
+
+impl ColorTree {
+    /// Builds a tree over `palette`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `palette` is empty.
+    pub fn new(palette: &[RGB]) -> Self {
+        assert!(
+            !palette.is_empty(),
+            "ColorTree::new requires at least one palette color"
+        );
+
+        let mut points = palette.to_vec();
+        let root = Box::new(Self::build(&mut points, 0));
+
+        Self { root }
+    }
+
+    /// Recursively builds a subtree over `points`, splitting on the axis
+    /// `depth % 3` and recursing on the halves either side of the median.
+    fn build(points: &mut [RGB], depth: usize) -> Node {
+        let axis = depth % 3;
+        points.sort_by_key(|c| Self::component(*c, axis));
+
+        let mid = points.len() / 2;
+        let color = points[mid];
+
+        let (left_points, rest) = points.split_at_mut(mid);
+        let right_points = &mut rest[1..];
+
+        let left = (!left_points.is_empty())
+            .then(|| Box::new(Self::build(left_points, depth + 1)));
+        let right = (!right_points.is_empty())
+            .then(|| Box::new(Self::build(right_points, depth + 1)));
+
+        Node {
+            color,
+            axis,
+            left,
+            right,
+        }
+    }
+
+    /// Returns `color`'s byte value along `axis` (0 = red, 1 = green, 2 = blue).
+    fn component(color: RGB, axis: usize) -> u8 {
+        match axis {
+            0 => color.red(),
+            1 => color.green(),
+            _ => color.blue(),
+        }
+    }
+
+    /// Returns the squared Euclidean distance between `a` and `b` in raw `RGB` byte space.
+    fn dist_sq(a: RGB, b: RGB) -> i32 {
+        let dr = a.red() as i32 - b.red() as i32;
+        let dg = a.green() as i32 - b.green() as i32;
+        let db = a.blue() as i32 - b.blue() as i32;
+
+        dr * dr + dg * dg + db * db
+    }
+
+    /// Finds the palette color closest to `query` in raw `RGB` byte space.
+    pub fn nearest(&self, query: RGB) -> RGB {
+        let best_dist = Self::dist_sq(self.root.color, query);
+
+        Self::nearest_in(&self.root, query, self.root.color, best_dist).0
+    }
+
+    /// Branch-and-bound descent: recurses into the child containing `query`
+    /// first, then only visits the far child if the squared distance from
+    /// `query` to the splitting plane is less than the current best.
+    fn nearest_in(node: &Node, query: RGB, mut best: RGB, mut best_dist: i32) -> (RGB, i32) {
+        let dist = Self::dist_sq(node.color, query);
+        if dist < best_dist {
+            best = node.color;
+            best_dist = dist;
+        }
+
+        let diff =
+            Self::component(query, node.axis) as i32 - Self::component(node.color, node.axis) as i32;
+        let (near, far) = if diff <= 0 {
+            (&node.left, &node.right)
+        } else {
+            (&node.right, &node.left)
+        };
+
+        if let Some(near) = near {
+            let (b, d) = Self::nearest_in(near, query, best, best_dist);
+            best = b;
+            best_dist = d;
+        }
+
+        if diff * diff < best_dist {
+            if let Some(far) = far {
+                let (b, d) = Self::nearest_in(far, query, best, best_dist);
+                best = b;
+                best_dist = d;
+            }
+        }
+
+        (best, best_dist)
+    }
+
+    /// As [`Self::nearest`], but projects colors out of HSV's cylindrical
+    /// space (hue as angle, saturation scaled by value as radius, value as
+    /// height) into Cartesian coordinates before measuring distance, so the
+    /// match respects perceived color -- e.g. a dim and a bright color aren't
+    /// considered close just because their raw byte difference is small.
+    pub fn nearest_perceptual(&self, query: RGB) -> RGB {
+        let q = Self::perceptual_point(query);
+
+        let mut best = self.root.color;
+        let mut best_dist = f32::MAX;
+
+        Self::visit(&self.root, &mut |color| {
+            let p = Self::perceptual_point(color);
+            let dist =
+                (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+
+            if dist < best_dist {
+                best_dist = dist;
+                best = color;
+            }
+        });
+
+        best
+    }
+
+    /// Projects `color` out of HSV's cylindrical coordinates into Cartesian ones.
+    fn perceptual_point(color: RGB) -> [f32; 3] {
+        let (h, s, v) = color.into_hsv();
+        let radius = s * v;
+        let angle = h.to_radians();
+
+        [radius * angle.cos(), radius * angle.sin(), v]
+    }
+
+    /// Visits every palette color in the tree, in no particular order.
+    fn visit(node: &Node, f: &mut impl FnMut(RGB)) {
+        f(node.color);
+
+        if let Some(left) = &node.left {
+            Self::visit(left, f);
+        }
+        if let Some(right) = &node.right {
+            Self::visit(right, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod color_tree_test {
+    use super::*;
+    use crate::rgb::RGBOrder;
+
+    fn palette() -> Vec<RGB> {
+        vec![
+            RGB::from_tuple((255, 0, 0), RGBOrder::RGB),
+            RGB::from_tuple((0, 255, 0), RGBOrder::RGB),
+            RGB::from_tuple((0, 0, 255), RGBOrder::RGB),
+            RGB::from_tuple((0, 0, 0), RGBOrder::RGB),
+            RGB::from_tuple((255, 255, 255), RGBOrder::RGB),
+        ]
+    }
+
+    #[test]
+    fn nearest_returns_exact_matches() {
+        let tree = ColorTree::new(&palette());
+
+        for color in palette() {
+            assert_eq!(tree.nearest(color), color);
+        }
+    }
+
+    #[test]
+    fn nearest_finds_the_closest_palette_color() {
+        let tree = ColorTree::new(&palette());
+
+        let query = RGB::from_tuple((250, 10, 5), RGBOrder::RGB);
+        assert_eq!(tree.nearest(query), RGB::from_tuple((255, 0, 0), RGBOrder::RGB));
+    }
+
+    #[test]
+    fn nearest_matches_a_brute_force_scan() {
+        let palette = vec![
+            RGB::from_tuple((12, 200, 90), RGBOrder::RGB),
+            RGB::from_tuple((80, 40, 220), RGBOrder::RGB),
+            RGB::from_tuple((5, 5, 5), RGBOrder::RGB),
+            RGB::from_tuple((250, 250, 10), RGBOrder::RGB),
+            RGB::from_tuple((128, 128, 128), RGBOrder::RGB),
+            RGB::from_tuple((60, 10, 10), RGBOrder::RGB),
+        ];
+        let tree = ColorTree::new(&palette);
+
+        for query in [
+            RGB::from_tuple((100, 100, 100), RGBOrder::RGB),
+            RGB::from_tuple((0, 0, 0), RGBOrder::RGB),
+            RGB::from_tuple((255, 255, 255), RGBOrder::RGB),
+            RGB::from_tuple((60, 200, 80), RGBOrder::RGB),
+        ] {
+            let expected = *palette
+                .iter()
+                .min_by_key(|c| ColorTree::dist_sq(**c, query))
+                .unwrap();
+
+            assert_eq!(tree.nearest(query), expected);
+        }
+    }
+
+    #[test]
+    fn nearest_perceptual_prefers_similar_hue_over_similar_bytes() {
+        let palette = vec![
+            RGB::from_hsv(0.0, 1.0, 0.2),   // dim red, byte-close to black
+            RGB::from_hsv(0.0, 1.0, 1.0),   // full-brightness red
+        ];
+        let tree = ColorTree::new(&palette);
+
+        let query = RGB::from_hsv(0.0, 1.0, 0.9);
+
+        assert_eq!(tree.nearest_perceptual(query), RGB::from_hsv(0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_panics_on_empty_palette() {
+        ColorTree::new(&[]);
+    }
+}
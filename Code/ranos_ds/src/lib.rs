@@ -7,5 +7,6 @@
 #![warn(clippy::all)]
 
 pub mod collections;
+pub mod color_tree;
 pub mod const_val;
 pub mod rgb;
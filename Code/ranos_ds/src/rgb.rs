@@ -5,7 +5,7 @@ use std::io;
 use serde::{Deserialize, Serialize};
 
 /// Enum defining all possible combinations of color order.
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
 pub enum RGBOrder {
     /// RGB-order color
     RGB,
@@ -21,6 +21,39 @@ pub enum RGBOrder {
     BGR,
 }
 
+/// Where the white byte sits relative to the three color bytes when an
+/// [`RGBW`] value is serialized with [`RGBW::as_tuple`], since SK6812-class
+/// RGBW strips disagree on whether white is clocked out first or last.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WhitePosition {
+    /// White byte is clocked out before the three color bytes, e.g. `WRGB`.
+    First,
+    /// White byte is clocked out after the three color bytes, e.g. `RGBW`.
+    Last,
+}
+
+/// How two overlapping layers' colors combine in [`RGB::blend`], for
+/// compositing several animations' frames into one.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// The top layer's color entirely replaces the bottom layer's.
+    Replace,
+    /// Each channel is summed and clamped to 255, brightening where both layers are lit.
+    Additive,
+    /// Each channel takes the brighter of the two layers.
+    Max,
+    /// The top layer is alpha-composited over the bottom, using the top
+    /// layer's own brightest channel (`max(r, g, b) / 255`) as its coverage --
+    /// black is fully transparent, full-brightness color is fully opaque.
+    AlphaOver,
+}
+
+/// Linearly interpolates a single `u8` channel from `a` to `b` by `t` in `[0, 1]`.
+#[inline]
+fn lerp(a: u8, b: u8, t: f32) -> u8 {
+    (a as f32 + (b as f32 - a as f32) * t).round().max(0.0).min(255.0) as u8
+}
+
 /// Simple RGB struct that holds the color as a single `u32` value.
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
@@ -99,6 +132,28 @@ impl RGB {
         Ok(out)
     }
 
+    /// Attempts to read one [`RGB`] per entry of `offsets`, seeking `reader`
+    /// to each offset before decoding it, so a sparse/indexed frame file can
+    /// be decoded without reading the spans between its entries.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `reader` encounters an error while seeking or reading.
+    pub fn read_with_offsets<R: io::Read + io::Seek>(
+        reader: &mut R,
+        offsets: &[u64],
+        order: RGBOrder,
+    ) -> io::Result<Vec<RGB>> {
+        let mut out = Vec::with_capacity(offsets.len());
+
+        for &offset in offsets {
+            reader.seek(io::SeekFrom::Start(offset))?;
+            out.push(RGB::read(reader, order)?);
+        }
+
+        Ok(out)
+    }
+
     /// Attempts to write `self` to the `writer` in the given `order`, returning the number of bytes written.
     ///
     /// # Errors
@@ -355,6 +410,40 @@ impl RGB {
         self.2
     }
 
+    /// Adds `self` and `other` channel-wise, clamping each to 255.
+    #[inline]
+    pub fn add(&self, other: Self) -> Self {
+        Self(
+            self.0.saturating_add(other.0),
+            self.1.saturating_add(other.1),
+            self.2.saturating_add(other.2),
+        )
+    }
+
+    /// Combines `self` (the bottom layer) with `other` (the top layer)
+    /// according to `mode`. See [`BlendMode`] for what each mode does.
+    #[inline]
+    pub fn blend(&self, other: Self, mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Replace => other,
+            BlendMode::Additive => self.add(other),
+            BlendMode::Max => Self(
+                self.0.max(other.0),
+                self.1.max(other.1),
+                self.2.max(other.2),
+            ),
+            BlendMode::AlphaOver => {
+                let alpha = other.0.max(other.1).max(other.2) as f32 / 255.0;
+
+                Self(
+                    lerp(self.0, other.0, alpha),
+                    lerp(self.1, other.1, alpha),
+                    lerp(self.2, other.2, alpha),
+                )
+            }
+        }
+    }
+
     /// Returns the value in the given order.
     #[inline]
     pub fn as_tuple(&self, o: RGBOrder) -> (u8, u8, u8) {
@@ -367,6 +456,117 @@ impl RGB {
             RGBOrder::BGR => (self.blue(), self.green(), self.red()),
         }
     }
+
+    /// Converts this color to [`RGBW`] for SK6812-class RGBW strips, pulling
+    /// the shared brightness of all three channels out into a dedicated white
+    /// channel: `w = min(r, g, b)`, subtracted from each of `r`, `g`, `b`.
+    ///
+    /// This is the standard, lossless-brightness RGB-to-RGBW conversion --
+    /// it doesn't attempt to account for the white LED's color temperature
+    /// differing from a mixed-color white, just the naive channel-minimum split.
+    #[inline]
+    pub fn extract_white(&self) -> RGBW {
+        let w = self.0.min(self.1).min(self.2);
+
+        RGBW(self.0 - w, self.1 - w, self.2 - w, w)
+    }
+
+    /// Maps a linear distance `index` along a 3D Hilbert space-filling curve
+    /// through the RGB cube (`bits` bits per channel) to the [`RGB`] value at
+    /// that point.
+    ///
+    /// Adjacent `index` values land on adjacent points in the cube, so
+    /// walking `index` up by one each call produces colors that vary
+    /// smoothly with no harsh jumps, unlike e.g. a raw counter over `u32` codes.
+    ///
+    /// Implements Skilling's transpose-to-axes algorithm: `index`'s
+    /// `bits * 3` bits are first de-interleaved into per-axis integers, then
+    /// un-Gray-coded into cube coordinates, which are scaled up to `0..=255`
+    /// per channel.
+    pub fn hilbert_nth(index: u64, bits: u8) -> Self {
+        let mut x = [0_u32; 3];
+
+        for i in 0..(bits as u32 * 3) {
+            let bit = (index >> i) & 1;
+            let axis = (2 - i % 3) as usize;
+            let pos = i / 3;
+            x[axis] |= (bit as u32) << pos;
+        }
+
+        let t = x[2] >> 1;
+        for i in (1..3).rev() {
+            x[i] ^= x[i - 1];
+        }
+        x[0] ^= t;
+
+        let mut q: u32 = 2;
+        while q != (1 << bits) {
+            let p = q - 1;
+
+            for i in (0..3).rev() {
+                if x[i] & q != 0 {
+                    x[0] ^= p;
+                } else {
+                    let t = (x[0] ^ x[i]) & p;
+                    x[0] ^= t;
+                    x[i] ^= t;
+                }
+            }
+
+            q <<= 1;
+        }
+
+        let shift = 8 - bits.min(8);
+        let channel = |v: u32| -> u8 { (v << shift) as u8 };
+
+        Self(channel(x[0]), channel(x[1]), channel(x[2]))
+    }
+}
+
+/// An [`RGB`] color plus a dedicated white channel, for SK6812-class RGBW
+/// LED strips that clock out 4 bytes per pixel instead of 3.
+///
+/// Construct one from an existing [`RGB`] value via [`RGB::extract_white`].
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RGBW(u8, u8, u8, u8);
+
+impl RGBW {
+    /// Returns the red color value.
+    #[inline]
+    pub fn red(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns the green color value.
+    #[inline]
+    pub fn green(&self) -> u8 {
+        self.1
+    }
+
+    /// Returns the blue color value.
+    #[inline]
+    pub fn blue(&self) -> u8 {
+        self.2
+    }
+
+    /// Returns the white color value.
+    #[inline]
+    pub fn white(&self) -> u8 {
+        self.3
+    }
+
+    /// Returns the value as a 4-tuple with the color channels in the given
+    /// `order` and the white channel slotted in at `white_position`.
+    #[inline]
+    pub fn as_tuple(&self, order: RGBOrder, white_position: WhitePosition) -> (u8, u8, u8, u8) {
+        let (a, b, c) = RGB(self.0, self.1, self.2).as_tuple(order);
+
+        match white_position {
+            WhitePosition::First => (self.3, a, b, c),
+            WhitePosition::Last => (a, b, c, self.3),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -450,4 +650,153 @@ mod rgb_test {
         let result = bgr.as_tuple(RGBOrder::BGR);
         assert_eq!(sample, result);
     }
+
+    #[test]
+    fn read_with_offsets_reads_each_entry_at_its_own_offset() {
+        use std::io::Cursor;
+
+        let mut buf = Vec::new();
+        RGB::from_tuple((1, 2, 3), RGBOrder::RGB)
+            .write(&mut buf, RGBOrder::RGB)
+            .unwrap();
+        RGB::from_tuple((4, 5, 6), RGBOrder::RGB)
+            .write(&mut buf, RGBOrder::RGB)
+            .unwrap();
+        RGB::from_tuple((7, 8, 9), RGBOrder::RGB)
+            .write(&mut buf, RGBOrder::RGB)
+            .unwrap();
+
+        let mut reader = Cursor::new(buf);
+        let offsets = [6, 0, 3];
+        let colors = RGB::read_with_offsets(&mut reader, &offsets, RGBOrder::RGB).unwrap();
+
+        assert_eq!(
+            colors,
+            vec![
+                RGB::from_tuple((7, 8, 9), RGBOrder::RGB),
+                RGB::from_tuple((1, 2, 3), RGBOrder::RGB),
+                RGB::from_tuple((4, 5, 6), RGBOrder::RGB),
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_white() {
+        let rgb = RGB::from_tuple((200, 100, 50), RGBOrder::RGB);
+
+        let rgbw = rgb.extract_white();
+        assert_eq!(rgbw.red(), 150);
+        assert_eq!(rgbw.green(), 50);
+        assert_eq!(rgbw.blue(), 0);
+        assert_eq!(rgbw.white(), 50);
+    }
+
+    #[test]
+    fn rgbw_as_tuple() {
+        let rgbw = RGB::from_tuple((200, 100, 50), RGBOrder::RGB).extract_white();
+
+        assert_eq!(
+            rgbw.as_tuple(RGBOrder::RGB, WhitePosition::Last),
+            (150, 50, 0, 50)
+        );
+        assert_eq!(
+            rgbw.as_tuple(RGBOrder::RGB, WhitePosition::First),
+            (50, 150, 50, 0)
+        );
+        assert_eq!(
+            rgbw.as_tuple(RGBOrder::GRB, WhitePosition::Last),
+            (50, 150, 0, 50)
+        );
+    }
+
+    #[test]
+    fn add_clamps() {
+        let a = RGB::from_tuple((200, 10, 0), RGBOrder::RGB);
+        let b = RGB::from_tuple((100, 20, 0), RGBOrder::RGB);
+
+        let result = a.add(b);
+        assert_eq!(result.as_tuple(RGBOrder::RGB), (255, 30, 0));
+    }
+
+    #[test]
+    fn blend_replace() {
+        let a = RGB::from_tuple((10, 20, 30), RGBOrder::RGB);
+        let b = RGB::from_tuple((200, 100, 0), RGBOrder::RGB);
+
+        assert_eq!(a.blend(b, BlendMode::Replace).as_tuple(RGBOrder::RGB), (200, 100, 0));
+    }
+
+    #[test]
+    fn blend_additive() {
+        let a = RGB::from_tuple((200, 10, 0), RGBOrder::RGB);
+        let b = RGB::from_tuple((100, 20, 0), RGBOrder::RGB);
+
+        assert_eq!(a.blend(b, BlendMode::Additive).as_tuple(RGBOrder::RGB), (255, 30, 0));
+    }
+
+    #[test]
+    fn blend_max() {
+        let a = RGB::from_tuple((200, 10, 30), RGBOrder::RGB);
+        let b = RGB::from_tuple((100, 20, 0), RGBOrder::RGB);
+
+        assert_eq!(a.blend(b, BlendMode::Max).as_tuple(RGBOrder::RGB), (200, 20, 30));
+    }
+
+    #[test]
+    fn blend_alpha_over() {
+        let a = RGB::from_tuple((100, 100, 100), RGBOrder::RGB);
+
+        // Fully black top layer is fully transparent -- bottom shows through unchanged.
+        let black = RGB::from_tuple((0, 0, 0), RGBOrder::RGB);
+        assert_eq!(a.blend(black, BlendMode::AlphaOver).as_tuple(RGBOrder::RGB), (100, 100, 100));
+
+        // Full-brightness top layer is fully opaque -- top replaces bottom.
+        let white = RGB::from_tuple((255, 0, 0), RGBOrder::RGB);
+        assert_eq!(a.blend(white, BlendMode::AlphaOver).as_tuple(RGBOrder::RGB), (255, 0, 0));
+    }
+
+    #[test]
+    fn hilbert_nth_starts_at_origin() {
+        assert_eq!(RGB::hilbert_nth(0, 4).as_tuple(RGBOrder::RGB), (0, 0, 0));
+    }
+
+    #[test]
+    fn hilbert_nth_covers_the_cube_without_repeats() {
+        let bits = 3;
+        let n = 1_u64 << (bits as u32 * 3);
+
+        let mut seen = std::collections::HashSet::new();
+        for i in 0..n {
+            let color = RGB::hilbert_nth(i, bits);
+            assert!(seen.insert(color.as_tuple(RGBOrder::RGB)), "duplicate color at index {}", i);
+        }
+        assert_eq!(seen.len(), n as usize);
+    }
+
+    #[test]
+    fn hilbert_nth_consecutive_steps_stay_adjacent_in_the_cube() {
+        let bits = 4;
+        let step = 1_u32 << (8 - bits as u32);
+
+        let mut prev = RGB::hilbert_nth(0, bits);
+        for i in 1..(1_u64 << (bits as u32 * 3)) {
+            let cur = RGB::hilbert_nth(i, bits);
+
+            let dist = (cur.red() as i32 - prev.red() as i32).abs()
+                + (cur.green() as i32 - prev.green() as i32).abs()
+                + (cur.blue() as i32 - prev.blue() as i32).abs();
+
+            assert_eq!(dist, step as i32, "step {} moved by {} instead of one cube unit", i, dist);
+
+            prev = cur;
+        }
+    }
+
+    #[test]
+    fn hilbert_nth_wraps_on_bit_width() {
+        let bits = 3;
+        let period = 1_u64 << (bits as u32 * 3);
+
+        assert_eq!(RGB::hilbert_nth(0, bits), RGB::hilbert_nth(period, bits));
+    }
 }
@@ -0,0 +1,288 @@
+//! A filter that drives `Frame` brightness from an ambient-light (lux) reading.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::const_val::ConstVal;
+
+use super::*;
+
+/// Builder for the [`AmbientBrightness`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AmbientBrightness")]
+pub struct AmbientBrightnessBuilder {
+    control_points: Vec<(f32, f32)>,
+    min_brightness: f32,
+    multiplier: f32,
+    hysteresis: f32,
+    smoothing: f32,
+    fast_cadence: Duration,
+    slow_cadence: Duration,
+}
+
+impl AmbientBrightnessBuilder {
+    /// Adds a `(lux, brightness)` control point the curve is interpolated
+    /// between. Points are sorted by `lux` at build time, so they can be
+    /// added in any order.
+    pub fn control_point(mut self: Box<Self>, lux: f32, brightness: f32) -> Box<Self> {
+        self.control_points.push((lux.max(0.0), brightness.min(1.0).max(0.0)));
+
+        self
+    }
+
+    /// Sets the brightness floor, in `[0, 1]`, that the curve's output is never allowed below.
+    pub fn min_brightness(mut self: Box<Self>, min_brightness: f32) -> Box<Self> {
+        self.min_brightness = min_brightness.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets the multiplier applied to the curve's output before the
+    /// `min_brightness` floor, for a user-facing "overall dimness" preference on top of the sensor curve.
+    pub fn multiplier(mut self: Box<Self>, multiplier: f32) -> Box<Self> {
+        self.multiplier = multiplier.max(0.0);
+
+        self
+    }
+
+    /// Sets the hysteresis threshold: a new target brightness is only
+    /// accepted once it differs from the current target by more than this
+    /// amount, so small lux fluctuations don't cause visible flicker.
+    pub fn hysteresis(mut self: Box<Self>, hysteresis: f32) -> Box<Self> {
+        self.hysteresis = hysteresis.max(0.0);
+
+        self
+    }
+
+    /// Sets how much of the remaining gap to the target brightness is closed
+    /// on each update, in `[0, 1]`. Lower values smooth the transition over more updates.
+    pub fn smoothing(mut self: Box<Self>, smoothing: f32) -> Box<Self> {
+        self.smoothing = smoothing.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets how often the controller re-samples lux and steps towards its
+    /// target immediately after a hysteresis-triggered change.
+    pub fn fast_cadence(mut self: Box<Self>, fast_cadence: Duration) -> Box<Self> {
+        self.fast_cadence = fast_cadence;
+
+        self
+    }
+
+    /// Sets how often the controller re-samples lux and steps towards its target once it's stable.
+    pub fn slow_cadence(mut self: Box<Self>, slow_cadence: Duration) -> Box<Self> {
+        self.slow_cadence = slow_cadence;
+
+        self
+    }
+
+    /// Constructs an [`AmbientBrightness`] object.
+    pub fn build(self: Box<Self>) -> AmbientBrightness {
+        AmbientBrightness::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for AmbientBrightnessBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{AmbientBrightness, AmbientBrightnessBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = AmbientBrightness::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(control_points:[(0,0.05),(1000,1)],min_brightness:0.05,multiplier:1,hysteresis:0.05,smoothing:0.2,fast_cadence:(secs:0,nanos:200000000),slow_cadence:(secs:2,nanos:0))"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(control_points:[(0.0,0.1),(500.0,1.0)],min_brightness:0.1,multiplier:0.8,hysteresis:0.02,smoothing:0.5,fast_cadence:(secs:0,nanos:100000000),slow_cadence:(secs:1,nanos:0))"#;
+        let data: AmbientBrightnessBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.control_points, vec![(0.0, 0.1), (500.0, 1.0)]);
+        assert_eq!(data.min_brightness, 0.1);
+        assert_eq!(data.multiplier, 0.8);
+        assert_eq!(data.hysteresis, 0.02);
+        assert_eq!(data.smoothing, 0.5);
+    }
+}
+
+/// Linearly interpolates the brightness curve defined by `points` (sorted
+/// ascending by lux) at `lux`, clamping to the nearest endpoint's brightness outside its range.
+fn evaluate_curve(points: &[(f32, f32)], lux: f32) -> f32 {
+    match points {
+        [] => 1.0,
+        [(_, b)] => *b,
+        points => {
+            if lux <= points[0].0 {
+                return points[0].1;
+            }
+            if lux >= points[points.len() - 1].0 {
+                return points[points.len() - 1].1;
+            }
+
+            for w in points.windows(2) {
+                let (x0, y0) = w[0];
+                let (x1, y1) = w[1];
+
+                if lux <= x1 {
+                    let t = if x1 > x0 { (lux - x0) / (x1 - x0) } else { 0.0 };
+                    return y0 + (y1 - y0) * t;
+                }
+            }
+
+            points[points.len() - 1].1
+        }
+    }
+}
+
+/// Filter that maps a pushed ambient-light reading to [`Frame::brightness`]
+/// via a configurable `(lux, brightness)` curve, so an installation can
+/// dim/brighten automatically as the room around it changes.
+///
+/// Every [`AmbientBrightnessBuilder::fast_cadence`]/`slow_cadence`-gated
+/// update: the curve is evaluated at the most recent lux pushed via
+/// [`Self::set_lux`], scaled by the user `multiplier` and clamped above
+/// `min_brightness`. That target is only accepted once it differs from the
+/// previous one by more than `hysteresis`, so small lux fluctuations don't
+/// flicker the display; accepting a new target also switches the controller
+/// to `fast_cadence` so it catches up quickly, while an unchanged target
+/// settles it back to `slow_cadence`. The actual frame brightness then
+/// smooths a `smoothing` fraction of the way to the target each update,
+/// rather than snapping straight to it.
+#[derive(Debug)]
+pub struct AmbientBrightness {
+    control_points: ConstVal<Vec<(f32, f32)>>,
+    min_brightness: ConstVal<f32>,
+    multiplier: ConstVal<f32>,
+    hysteresis: ConstVal<f32>,
+    smoothing: ConstVal<f32>,
+    fast_cadence: ConstVal<Duration>,
+    slow_cadence: ConstVal<Duration>,
+
+    lux: f32,
+    target: f32,
+    current: f32,
+
+    cadence: Duration,
+    time_since_update: Duration,
+}
+
+impl AmbientBrightness {
+    /// Constructs a builder object with a gentle night/day default curve:
+    /// dim at `0` lux, full brightness by `1000` lux.
+    pub fn builder() -> Box<AmbientBrightnessBuilder> {
+        Box::new(AmbientBrightnessBuilder {
+            control_points: vec![(0.0, 0.05), (1000.0, 1.0)],
+            min_brightness: 0.05,
+            multiplier: 1.0,
+            hysteresis: 0.05,
+            smoothing: 0.2,
+            fast_cadence: Duration::from_millis(200),
+            slow_cadence: Duration::from_secs(2),
+        })
+    }
+
+    fn from_builder(builder: Box<AmbientBrightnessBuilder>) -> Self {
+        Self::new(
+            builder.control_points,
+            builder.min_brightness,
+            builder.multiplier,
+            builder.hysteresis,
+            builder.smoothing,
+            builder.fast_cadence,
+            builder.slow_cadence,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        mut control_points: Vec<(f32, f32)>,
+        min_brightness: f32,
+        multiplier: f32,
+        hysteresis: f32,
+        smoothing: f32,
+        fast_cadence: Duration,
+        slow_cadence: Duration,
+    ) -> Self {
+        control_points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let target = evaluate_curve(&control_points, 0.0).max(min_brightness);
+
+        Self {
+            control_points: ConstVal::new(control_points),
+            min_brightness: ConstVal::new(min_brightness),
+            multiplier: ConstVal::new(multiplier),
+            hysteresis: ConstVal::new(hysteresis),
+            smoothing: ConstVal::new(smoothing),
+            fast_cadence: ConstVal::new(fast_cadence),
+            slow_cadence: ConstVal::new(slow_cadence),
+
+            lux: 0.0,
+            target,
+            current: target,
+
+            cadence: slow_cadence,
+            time_since_update: Duration::new(0, 0),
+        }
+    }
+
+    /// Pushes the most recent ambient-light reading, in lux, to be picked up
+    /// on this filter's next cadence-gated update.
+    pub fn set_lux(&mut self, lux: f32) {
+        self.lux = lux.max(0.0);
+    }
+
+    /// Re-evaluates the curve against the current lux reading and steps the
+    /// controller towards its (possibly newly-accepted) target.
+    fn update(&mut self) {
+        let raw = evaluate_curve(self.control_points.get(), self.lux);
+        let target = (raw * *self.multiplier.get())
+            .max(*self.min_brightness.get())
+            .min(1.0);
+
+        if (target - self.target).abs() > *self.hysteresis.get() {
+            self.target = target;
+            self.cadence = *self.fast_cadence.get();
+        } else {
+            self.cadence = *self.slow_cadence.get();
+        }
+
+        self.current += (self.target - self.current) * *self.smoothing.get();
+    }
+}
+
+impl Filter for AmbientBrightness {
+    fn filter_frame(&mut self, frame: &mut Frame, dt: Duration) -> FilterState {
+        self.time_since_update += dt;
+
+        while self.time_since_update >= self.cadence {
+            self.time_since_update -= self.cadence;
+            self.update();
+        }
+
+        frame.set_brightness(self.current);
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {
+        let target = evaluate_curve(self.control_points.get(), 0.0).max(*self.min_brightness.get());
+
+        self.lux = 0.0;
+        self.target = target;
+        self.current = target;
+        self.cadence = *self.slow_cadence.get();
+        self.time_since_update = Duration::new(0, 0);
+    }
+}
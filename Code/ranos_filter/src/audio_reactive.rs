@@ -0,0 +1,237 @@
+//! Filter that scales LED brightness by live, real-time audio, by way of an FFT.
+
+use std::{f32::consts::PI, sync::Arc, time::Duration};
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::{capture::Capture, SIZE};
+use ranos_ds::const_val::ConstVal;
+
+use super::*;
+
+/// Builder for the [`AudioReactive`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AudioReactive")]
+pub struct AudioReactiveBuilder {
+    num_bands: usize,
+    decay: f32,
+    gain: f32,
+}
+
+impl AudioReactiveBuilder {
+    /// Sets the number of logarithmically-spaced frequency bands the frame is divided into.
+    pub fn num_bands(mut self: Box<Self>, num_bands: usize) -> Box<Self> {
+        self.num_bands = num_bands.max(1);
+
+        self
+    }
+
+    /// Sets how much each band's level decays per frame, in `[0, 1)`, once
+    /// its energy falls. A level of `1.0` would never decay; values close to
+    /// that make for a laggier, smoother display.
+    pub fn decay(mut self: Box<Self>, decay: f32) -> Box<Self> {
+        self.decay = decay.clamp(0.0, 0.999);
+
+        self
+    }
+
+    /// Sets the linear gain applied to each band's magnitude before it's
+    /// clamped into `[0, 1]`, to compensate for quiet input devices.
+    pub fn gain(mut self: Box<Self>, gain: f32) -> Box<Self> {
+        self.gain = gain.max(0.0);
+
+        self
+    }
+
+    /// Constructs an [`AudioReactive`] object, opening the system's default
+    /// audio input device.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no input device is available or it could not be captured from.
+    pub fn build(self: Box<Self>) -> AudioReactive {
+        AudioReactive::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for AudioReactiveBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::AudioReactiveBuilder;
+    use crate::AudioReactive;
+
+    #[test]
+    fn test_serialize() {
+        let builder = AudioReactive::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(num_bands:16,decay:0.8,gain:1)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(num_bands:24,decay:0.9,gain:2)"#;
+
+        let data: AudioReactiveBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.num_bands, 24);
+        assert_eq!(data.decay, 0.9);
+        assert_eq!(data.gain, 2.0);
+    }
+}
+
+/// Filter that scales each LED's existing color by the live frequency
+/// spectrum of the system's default audio input device, WLED-audioreactive
+/// style.
+///
+/// Each frame, the most recent [`ranos_audio::SIZE`] captured samples are
+/// windowed with a Hann window, transformed with a real FFT, and the
+/// magnitude spectrum is grouped into logarithmically-spaced bands spanning
+/// ~20 Hz to Nyquist. Each band's level rises instantly with new energy and
+/// decays exponentially otherwise. Unlike [`ranos_generator::AudioSpectrum`],
+/// which overwrites the frame with a hue sweep, this filter only scales the
+/// brightness of whatever colors are already in the frame, region by region
+/// -- bass at one end of the strip, treble at the other.
+///
+/// To create an [`AudioReactive`] filter, use the associated
+/// [builder](AudioReactiveBuilder), accessed via [`AudioReactive::builder()`].
+#[derive(Debug)]
+pub struct AudioReactive {
+    capture: Capture,
+
+    hann_window: ConstVal<[f32; SIZE]>,
+    fft: Arc<dyn Fft<f32>>,
+    spectrum: Box<[Complex<f32>; SIZE]>,
+    scratch: Box<[Complex<f32>; SIZE]>,
+
+    num_bands: ConstVal<usize>,
+    band_edges: ConstVal<Vec<usize>>,
+    decay: f32,
+    gain: f32,
+    band_levels: Vec<f32>,
+}
+
+impl AudioReactive {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<AudioReactiveBuilder> {
+        Box::new(AudioReactiveBuilder {
+            num_bands: 16,
+            decay: 0.8,
+            gain: 1.0,
+        })
+    }
+
+    fn from_builder(builder: Box<AudioReactiveBuilder>) -> Self {
+        Self::new(builder.num_bands, builder.decay, builder.gain)
+    }
+
+    fn new(num_bands: usize, decay: f32, gain: f32) -> Self {
+        let capture =
+            Capture::new().expect("AudioReactive filter requires an audio input device");
+        let sample_rate = capture.sample_rate();
+
+        let mut hann_window = [0.0_f32; SIZE];
+        for (n, w) in hann_window.iter_mut().enumerate() {
+            *w = 0.5 - 0.5 * (2.0 * PI * n as f32 / (SIZE as f32 - 1.0)).cos();
+        }
+
+        Self {
+            capture,
+
+            hann_window: ConstVal::new(hann_window),
+            fft: FftPlanner::new().plan_fft_forward(SIZE),
+            spectrum: Box::new([Complex::new(0.0, 0.0); SIZE]),
+            scratch: Box::new([Complex::new(0.0, 0.0); SIZE]),
+
+            num_bands: ConstVal::new(num_bands),
+            band_edges: ConstVal::new(log_band_edges(num_bands, sample_rate)),
+            decay,
+            gain,
+            band_levels: vec![0.0; num_bands],
+        }
+    }
+
+    /// Captures the most recent samples, windows and transforms them, and
+    /// updates the per-band levels.
+    fn update_bands(&mut self) {
+        let samples = self.capture.most_recent_data();
+
+        for (s, (sample, window)) in self
+            .spectrum
+            .iter_mut()
+            .zip(samples.iter().zip(self.hann_window.get().iter()))
+        {
+            *s = Complex::new(sample * window, 0.0);
+        }
+
+        self.fft
+            .process_with_scratch(&mut *self.spectrum, &mut *self.scratch);
+
+        let edges = self.band_edges.get();
+        for band in 0..*self.num_bands.get() {
+            let (begin, end) = (edges[band], edges[band + 1]);
+
+            let mut energy = 0.0;
+            // Drop the DC bin (index 0) from band 0's range.
+            for bin in begin.max(1)..end {
+                energy += self.spectrum[bin].norm() / (SIZE as f32);
+            }
+            let new_level = ((energy / (end - begin.max(1)).max(1) as f32) * self.gain).min(1.0);
+
+            let level = &mut self.band_levels[band];
+            *level = new_level.max(*level * self.decay);
+        }
+    }
+}
+
+impl Filter for AudioReactive {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        self.update_bands();
+
+        let num_bands = *self.num_bands.get();
+        let len = frame.len();
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let band = (i * num_bands / len.max(1)).min(num_bands - 1);
+
+            *led = led.scale(self.band_levels[band]);
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {
+        for level in self.band_levels.iter_mut() {
+            *level = 0.0;
+        }
+    }
+}
+
+/// Computes `num_bands + 1` FFT bin-index edges, logarithmically spaced from
+/// ~20 Hz to Nyquist (`sample_rate / 2`), across the first `SIZE / 2` bins.
+fn log_band_edges(num_bands: usize, sample_rate: f32) -> Vec<usize> {
+    let nyquist = sample_rate / 2.0;
+    let min_freq = 20.0_f32.min(nyquist);
+
+    let log_min = min_freq.ln();
+    let log_max = nyquist.ln();
+
+    (0..=num_bands)
+        .map(|i| {
+            let t = i as f32 / num_bands as f32;
+            let freq = (log_min + t * (log_max - log_min)).exp();
+            let bin = (freq / nyquist * (SIZE as f32 / 2.0)).round() as usize;
+
+            bin.min(SIZE / 2)
+        })
+        .collect()
+}
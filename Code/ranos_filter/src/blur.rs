@@ -0,0 +1,270 @@
+//! Filters that post-process a frame's colors directly -- blurring LEDs into
+//! their neighbors and scaling every channel -- rather than correcting for
+//! strip wiring or timing like [`super::spatial`]/[`super::temporal`] do.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// Scales each channel of `color` by `scale / 255`, FastLED `scale8` style.
+pub(crate) fn scale8(color: RGB, scale: u8) -> RGB {
+    RGB::from_tuple(
+        (
+            ((color.red() as u16 * scale as u16) / 255) as u8,
+            ((color.green() as u16 * scale as u16) / 255) as u8,
+            ((color.blue() as u16 * scale as u16) / 255) as u8,
+        ),
+        RGBOrder::RGB,
+    )
+}
+
+/// Adds two colors channel-wise, saturating at 255 instead of wrapping.
+fn add(a: RGB, b: RGB) -> RGB {
+    RGB::from_tuple(
+        (
+            a.red().saturating_add(b.red()),
+            a.green().saturating_add(b.green()),
+            a.blue().saturating_add(b.blue()),
+        ),
+        RGBOrder::RGB,
+    )
+}
+
+/// Builder for the [`Blur1D`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Blur1D")]
+pub struct Blur1DBuilder {
+    blur_amount: u8,
+    smear: bool,
+}
+
+impl Blur1DBuilder {
+    /// Sets how strongly each LED seeps into its neighbors, `0` (no blur) to `255` (maximum).
+    pub fn blur_amount(mut self: Box<Self>, blur_amount: u8) -> Box<Self> {
+        self.blur_amount = blur_amount;
+
+        self
+    }
+
+    /// Sets whether the source LED keeps its full color instead of dimming
+    /// by `blur_amount`, so repeated passes brighten toward white rather
+    /// than fading, per WLED's take on the classic FastLED `blur1d`.
+    pub fn smear(mut self: Box<Self>, smear: bool) -> Box<Self> {
+        self.smear = smear;
+
+        self
+    }
+
+    /// Constructs a [`Blur1D`] object.
+    pub fn build(self: Box<Self>) -> Blur1D {
+        Blur1D::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for Blur1DBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Blur1D, Blur1DBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Blur1D::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(blur_amount:64,smear:false)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(blur_amount:128,smear:true)"#;
+        let data: Blur1DBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.blur_amount, 128);
+        assert_eq!(data.smear, true);
+    }
+}
+
+/// Filter that blurs a [`Frame`]'s LEDs into their immediate neighbors each
+/// pass, leaving smooth motion trails behind whatever the upstream generator
+/// drew -- the classic FastLED/WLED `blur1d` effect, for a 1-D strip.
+///
+/// Each LED keeps `scale8(color, 255 - blur_amount)` of itself (or the full
+/// color when [`Blur1DBuilder::smear`] is set) and seeps `scale8(color,
+/// blur_amount / 2)` into each of its two neighbors. In `smear` mode,
+/// skipping the self-dimming term means repeated passes over a frame that's
+/// never recleared compound toward white instead of fading out.
+///
+/// To create a [`Blur1D`], use the associated [builder](Blur1DBuilder),
+/// accessed via [`Blur1D::builder()`].
+#[derive(Debug)]
+pub struct Blur1D {
+    blur_amount: ConstVal<u8>,
+    smear: ConstVal<bool>,
+}
+
+impl Blur1D {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<Blur1DBuilder> {
+        Box::new(Blur1DBuilder {
+            blur_amount: 64,
+            smear: false,
+        })
+    }
+
+    fn from_builder(builder: Box<Blur1DBuilder>) -> Self {
+        Self::new(builder.blur_amount, builder.smear)
+    }
+
+    fn new(blur_amount: u8, smear: bool) -> Self {
+        Self {
+            blur_amount: ConstVal::new(blur_amount),
+            smear: ConstVal::new(smear),
+        }
+    }
+}
+
+impl Filter for Blur1D {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        let blur_amount = *self.blur_amount.get();
+        let keep = if *self.smear.get() {
+            255
+        } else {
+            255u8.saturating_sub(blur_amount)
+        };
+        let seep = blur_amount / 2;
+
+        let len = frame.len();
+        let source: Vec<RGB> = frame.iter().copied().collect();
+        let mut blurred = vec![RGB::new(); len];
+
+        for (i, &color) in source.iter().enumerate() {
+            blurred[i] = add(blurred[i], scale8(color, keep));
+
+            if seep > 0 {
+                let carried = scale8(color, seep);
+
+                if i > 0 {
+                    blurred[i - 1] = add(blurred[i - 1], carried);
+                }
+                if i + 1 < len {
+                    blurred[i + 1] = add(blurred[i + 1], carried);
+                }
+            }
+        }
+
+        for (led, new_color) in frame.iter_mut().zip(blurred) {
+            *led = new_color;
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Builder for the [`Scale`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Scale")]
+pub struct ScaleBuilder {
+    factor: u8,
+}
+
+impl ScaleBuilder {
+    /// Sets the 8-bit fraction (`factor / 255`) every channel is multiplied by.
+    pub fn factor(mut self: Box<Self>, factor: u8) -> Box<Self> {
+        self.factor = factor;
+
+        self
+    }
+
+    /// Constructs a [`Scale`] object.
+    pub fn build(self: Box<Self>) -> Scale {
+        Scale::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for ScaleBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod scale_builder_test {
+    use super::{Scale, ScaleBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Scale::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(factor:255)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(factor:128)"#;
+        let data: ScaleBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.factor, 128);
+    }
+}
+
+/// Filter that multiplies every channel of every LED in a frame by an 8-bit
+/// fraction, `scale8` style -- the same primitive [`Blur1D`] uses
+/// internally, exposed here as its own chainable stage so a pipeline like
+/// "Rainbow -> blur -> scale" can be built entirely from [`Filter`]s.
+///
+/// To create a [`Scale`], use the associated [builder](ScaleBuilder),
+/// accessed via [`Scale::builder()`].
+#[derive(Debug)]
+pub struct Scale {
+    factor: ConstVal<u8>,
+}
+
+impl Scale {
+    /// Constructs a builder object with safe default values (full brightness, no-op).
+    pub fn builder() -> Box<ScaleBuilder> {
+        Box::new(ScaleBuilder { factor: 255 })
+    }
+
+    fn from_builder(builder: Box<ScaleBuilder>) -> Self {
+        Self::new(builder.factor)
+    }
+
+    fn new(factor: u8) -> Self {
+        Self {
+            factor: ConstVal::new(factor),
+        }
+    }
+}
+
+impl Filter for Scale {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        let factor = *self.factor.get();
+
+        for led in frame.iter_mut() {
+            *led = scale8(*led, factor);
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {}
+}
@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use ranos_core::clock_duration::ClockDuration;
 use ranos_ds::const_val::ConstVal;
 
 use super::*;
@@ -61,12 +62,22 @@ mod builder_test {
 
 /// Struct for a filtered breathing display, fading a supplied frame along a
 /// parabolic curve from black to the full frame and back down to black.
+///
+/// Rather than integrating `vel`/`pos` frame-by-frame in `f32` (which
+/// accumulates rounding error over a long-running breath), this tracks total
+/// elapsed time as an exact [`ClockDuration`] and evaluates the parabola's
+/// closed form from it each frame, so playback stays phase-accurate no
+/// matter how long it runs.
 #[derive(Debug)]
 pub struct Breath {
-    acc: ConstVal<f32>,
-    vel: f32,
-    vel0: ConstVal<f32>,
-    pos: f32,
+    breath_duration: ConstVal<ClockDuration>,
+    elapsed: ClockDuration,
+
+    /// Set by [`Filter::qos`] once the render loop reports it's running
+    /// behind; when set, every other frame skips the per-LED scale and
+    /// passes the frame on exactly as it arrived from upstream.
+    degraded: bool,
+    skip_next: bool,
 }
 
 impl Breath {
@@ -83,32 +94,52 @@ impl Breath {
 
     fn new(breath_duration: Duration) -> Self {
         Self {
-            acc: ConstVal::new(-8.0 / breath_duration.as_secs_f32().powi(2)),
-            vel: 4.0 / breath_duration.as_secs_f32(),
-            vel0: ConstVal::new(4.0 / breath_duration.as_secs_f32()),
-            pos: 0.0,
+            breath_duration: ConstVal::new(ClockDuration::from(breath_duration)),
+            elapsed: ClockDuration::ZERO,
+            degraded: false,
+            skip_next: false,
         }
     }
 }
 
 impl Filter for Breath {
     fn filter_frame(&mut self, frame: &mut Frame, dt: Duration) -> FilterState {
-        self.vel += self.acc.get() * dt.as_secs_f32();
-        self.pos += self.vel * dt.as_secs_f32();
-
-        if self.pos <= 0.0 && self.vel < 0.0 {
-            self.pos = 0.0;
-            self.vel = *self.vel0.get();
-        }
-
-        for led in frame.iter_mut() {
-            led.scale(self.pos);
+        self.elapsed += ClockDuration::from(dt);
+
+        let period = *self.breath_duration.get();
+        let t = self.elapsed.rem(period).as_secs_f64();
+        let big_t = period.as_secs_f64();
+
+        // Closed form of a projectile launched at vel0 = 4/T, acc = -8/T^2:
+        // pos(t) = vel0*t + 0.5*acc*t^2, which is 0 at t=0 and t=T.
+        let pos = if big_t == 0.0 {
+            0.0
+        } else {
+            (4.0 / big_t) * t - (4.0 / (big_t * big_t)) * t * t
+        } as f32;
+
+        if self.degraded && self.skip_next {
+            // Running behind: skip scaling every LED this frame, leaving the
+            // frame as upstream rendered it.
+            self.skip_next = false;
+        } else {
+            self.skip_next = self.degraded;
+
+            for led in frame.iter_mut() {
+                *led = led.scale(pos);
+            }
         }
 
         FilterState::Ok
     }
 
     fn reset(&mut self) {
-        self.vel = *self.vel0.get();
+        self.elapsed = ClockDuration::ZERO;
+        self.degraded = false;
+        self.skip_next = false;
+    }
+
+    fn qos(&mut self, proportion: f64) {
+        self.degraded = proportion > 1.0;
     }
 }
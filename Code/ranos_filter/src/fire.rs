@@ -0,0 +1,229 @@
+//! A flame filter driven by a bottom-up energy simulation, in the same style
+//! as `ranos_animation`'s `Fire` generator, but usable as a post-process
+//! stage layered over any `Display`'s filter pipeline instead of occupying a
+//! generator slot of its own.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// Per-step multiplicative damping applied to the topmost cell only, on top
+/// of [`FireBuilder::cooldown`], so heat escapes off the end of the strip
+/// instead of pooling there.
+const TOP_DAMPING: f32 = 0.85;
+
+/// Builder for the [`Fire`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Fire")]
+pub struct FireBuilder {
+    new_energy: f32,
+    cooldown: f32,
+    max_propagation: f32,
+    rgb_exponent: f32,
+}
+
+impl FireBuilder {
+    /// Sets the maximum amount of energy injected into the bottom of the
+    /// strip each frame (actual injection is `rand::random::<f32>() * new_energy`).
+    pub fn new_energy(mut self: Box<Self>, new_energy: f32) -> Box<Self> {
+        self.new_energy = new_energy.max(0.0);
+
+        self
+    }
+
+    /// Sets the per-second multiplicative cooldown applied to every cell's
+    /// energy, e.g. `0.99995` for a slow-burning flame, lower for a
+    /// flickerier one.
+    pub fn cooldown(mut self: Box<Self>, cooldown: f32) -> Box<Self> {
+        self.cooldown = cooldown.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets the largest fraction of a cell's energy that a single frame's
+    /// upward propagation step may pass on to the cell above it, clamped to
+    /// \[0, 1\].
+    pub fn max_propagation(mut self: Box<Self>, max_propagation: f32) -> Box<Self> {
+        self.max_propagation = max_propagation.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets the gamma curve exponent mapping energy to color intensity --
+    /// higher values push more of the strip toward black before the flame shows.
+    pub fn rgb_exponent(mut self: Box<Self>, rgb_exponent: f32) -> Box<Self> {
+        self.rgb_exponent = rgb_exponent.max(0.0);
+
+        self
+    }
+
+    /// Constructs a [`Fire`] object.
+    pub fn build(self: Box<Self>) -> Fire {
+        Fire::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for FireBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Fire, FireBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Fire::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(new_energy:0.8,cooldown:0.99995,max_propagation:0.4,rgb_exponent:1.6)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(new_energy:0.8,cooldown:0.99995,max_propagation:0.4,rgb_exponent:1.6)"#;
+
+        let data: FireBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.new_energy, 0.8);
+        assert_eq!(data.cooldown, 0.99995);
+        assert_eq!(data.max_propagation, 0.4);
+        assert_eq!(data.rgb_exponent, 1.6);
+    }
+}
+
+/// Filter that simulates a flame climbing whatever frame it's applied to:
+/// heat is injected at LED 0, propagated upward cell by cell, cooled, and
+/// mapped to a red-orange-yellow-white ramp, overwriting the frame entirely
+/// rather than modulating the colors already in it.
+///
+/// The per-LED energy buffer is sized to match the first frame it sees and
+/// persists across calls to [`Filter::filter_frame`], so the flame keeps
+/// climbing from where it left off rather than restarting each frame.
+///
+/// To create a [`Fire`], use the associated [builder](FireBuilder), accessed
+/// via [`Fire::builder()`].
+#[derive(Debug)]
+pub struct Fire {
+    energy: Vec<f32>,
+
+    new_energy: ConstVal<f32>,
+    cooldown: ConstVal<f32>,
+    max_propagation: ConstVal<f32>,
+    rgb_exponent: ConstVal<f32>,
+}
+
+impl Fire {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<FireBuilder> {
+        Box::new(FireBuilder {
+            new_energy: 0.8,
+            cooldown: 0.99995,
+            max_propagation: 0.4,
+            rgb_exponent: 1.6,
+        })
+    }
+
+    fn from_builder(builder: Box<FireBuilder>) -> Self {
+        Self::new(
+            builder.new_energy,
+            builder.cooldown,
+            builder.max_propagation,
+            builder.rgb_exponent,
+        )
+    }
+
+    fn new(new_energy: f32, cooldown: f32, max_propagation: f32, rgb_exponent: f32) -> Self {
+        Self {
+            energy: Vec::new(),
+
+            new_energy: ConstVal::new(new_energy),
+            cooldown: ConstVal::new(cooldown),
+            max_propagation: ConstVal::new(max_propagation),
+            rgb_exponent: ConstVal::new(rgb_exponent),
+        }
+    }
+
+    /// Maps a single cell's energy (expected roughly in `[0, 1]`, though
+    /// nothing here clamps the input) to the red-orange-yellow-white flame
+    /// color: red and green each follow a `energy.powf(rgb_exponent)` gamma
+    /// curve (green lagging behind red so low energy reads as red, not
+    /// yellow), clamped to `[0, 1]`, with energy above `1.0` bleeding into a
+    /// white overdrive boost so the hottest cells saturate toward white
+    /// instead of clipping at solid red.
+    fn energy_to_color(energy: f32, rgb_exponent: f32) -> RGB {
+        let energy = energy.max(0.0);
+
+        let red = energy.powf(rgb_exponent).min(1.0);
+        let green = (energy - 0.5).max(0.0).powf(rgb_exponent).min(1.0);
+        let overdrive = (energy - 1.0).max(0.0);
+
+        RGB::from_tuple(
+            (
+                ((red + overdrive) * 255.0).min(255.0) as u8,
+                ((green + overdrive) * 255.0).min(255.0) as u8,
+                (overdrive * 255.0).min(255.0) as u8,
+            ),
+            RGBOrder::RGB,
+        )
+    }
+}
+
+impl Filter for Fire {
+    fn filter_frame(&mut self, frame: &mut Frame, dt: Duration) -> FilterState {
+        let len = frame.len();
+        if self.energy.len() != len {
+            self.energy = vec![0.0; len];
+        }
+
+        if len > 0 {
+            // 1. Inject new energy at the strip's base.
+            self.energy[0] += rand::random::<f32>() * self.new_energy.get();
+
+            // 2. Cool every cell multiplicatively.
+            let cooldown = self.cooldown.get().powf(dt.as_secs_f32());
+            for e in self.energy.iter_mut() {
+                *e *= cooldown;
+            }
+
+            // 3. Bleed energy upward, each cell pulling a capped fraction of
+            // the cell below it. Walking top-down reads each lower cell's
+            // energy before this frame's propagation has touched it.
+            let max_propagation = *self.max_propagation.get();
+            for i in (1..len).rev() {
+                let frac = rand::random::<f32>().min(max_propagation);
+                self.energy[i] += self.energy[i - 1] * frac;
+            }
+
+            // 4. Damp the topmost cell so heat escapes off the end of the
+            // strip instead of pooling there.
+            if let Some(top) = self.energy.last_mut() {
+                *top *= TOP_DAMPING;
+            }
+
+            // 5. Map energy to color.
+            let rgb_exponent = *self.rgb_exponent.get();
+            for (led, &e) in frame.iter_mut().zip(self.energy.iter()) {
+                *led = Self::energy_to_color(e, rgb_exponent);
+            }
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.energy.iter_mut().for_each(|e| *e = 0.0);
+    }
+}
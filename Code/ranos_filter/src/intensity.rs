@@ -0,0 +1,124 @@
+//! A filter that applies a global scale and gamma curve to every channel of every LED in a frame.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// Builder for the [`Intensity`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Intensity")]
+pub struct IntensityBuilder {
+    scale: f32,
+    gamma: f32,
+}
+
+impl IntensityBuilder {
+    /// Sets the global scale applied to every channel, in the range `[0, 1]`.
+    pub fn scale(mut self: Box<Self>, scale: f32) -> Box<Self> {
+        self.scale = scale.min(1.0).max(0.0);
+
+        self
+    }
+
+    /// Sets the gamma exponent applied to every channel before scaling. `1.0` is linear.
+    pub fn gamma(mut self: Box<Self>, gamma: f32) -> Box<Self> {
+        self.gamma = gamma;
+
+        self
+    }
+
+    /// Constructs an [`Intensity`] object.
+    pub fn build(self: Box<Self>) -> Intensity {
+        Intensity::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for IntensityBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Intensity, IntensityBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Intensity::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(scale:1,gamma:1)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(scale:0.5,gamma:2.2)"#;
+        let data: IntensityBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.scale, 0.5);
+        assert_eq!(data.gamma, 2.2);
+    }
+}
+
+/// Filter that applies a global scale and gamma curve to every channel of
+/// every LED in a frame, for correcting a strip's perceived brightness or
+/// capping its overall power draw.
+#[derive(Debug)]
+pub struct Intensity {
+    scale: ConstVal<f32>,
+    gamma: ConstVal<f32>,
+}
+
+impl Intensity {
+    /// Constructs a builder object with safe default values (linear, full brightness).
+    pub fn builder() -> Box<IntensityBuilder> {
+        Box::new(IntensityBuilder {
+            scale: 1.0,
+            gamma: 1.0,
+        })
+    }
+
+    fn from_builder(builder: Box<IntensityBuilder>) -> Self {
+        Self::new(builder.scale, builder.gamma)
+    }
+
+    fn new(scale: f32, gamma: f32) -> Self {
+        Self {
+            scale: ConstVal::new(scale),
+            gamma: ConstVal::new(gamma),
+        }
+    }
+
+    #[inline]
+    fn apply(&self, channel: u8) -> u8 {
+        let normalized = channel as f32 / 255.0;
+        let corrected = normalized.powf(*self.gamma.get()) * self.scale.get();
+
+        (corrected.min(1.0).max(0.0) * 255.0) as u8
+    }
+}
+
+impl Filter for Intensity {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        for led in frame.iter_mut() {
+            *led = RGB::from_tuple(
+                (self.apply(led.red()), self.apply(led.green()), self.apply(led.blue())),
+                RGBOrder::RGB,
+            );
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {}
+}
@@ -2,11 +2,27 @@ use std::{fmt::Debug, time::Duration};
 
 use ranos_ds::collections::Frame;
 
+pub use ambient_brightness::AmbientBrightness;
+pub use audio_reactive::AudioReactive;
+pub use blur::{Blur1D, Scale};
 pub use breath::Breath;
+pub use fire::Fire;
+pub use intensity::Intensity;
+pub use resample::Resample;
+pub use spatial::Spatial;
 pub use strobe::Strobe;
+pub use temporal::Temporal;
 
+pub mod ambient_brightness;
+pub mod audio_reactive;
+pub mod blur;
 pub mod breath;
+pub mod fire;
+pub mod intensity;
+pub mod resample;
+pub mod spatial;
 pub mod strobe;
+pub mod temporal;
 
 /// Enum denoting different end-states that an [`Filter`] object may return.
 ///
@@ -33,6 +49,14 @@ pub trait Filter: Debug {
 
     /// Resets the filter to its pre-run state, operating as if it were never run before
     fn reset(&mut self);
+
+    /// Reports a quality-of-service signal: `proportion` is how long the most
+    /// recent frame actually took versus its target duration, so `1.0` means
+    /// right on schedule and `2.0` means the frame took twice as long as
+    /// budgeted. Filters that can shed work under load should do so here.
+    ///
+    /// The default implementation ignores the signal.
+    fn qos(&mut self, _proportion: f64) {}
 }
 
 /// Trait for building filter types.
@@ -0,0 +1,133 @@
+//! A filter that maps a frame authored for `N` virtual LEDs onto `M` physical LEDs via linear interpolation.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    const_val::ConstVal,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// Builder for the [`Resample`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Resample")]
+pub struct ResampleBuilder {
+    physical_len: usize,
+}
+
+impl ResampleBuilder {
+    /// Sets the number of physical LEDs to resample the frame onto.
+    pub fn physical_len(mut self: Box<Self>, physical_len: usize) -> Box<Self> {
+        self.physical_len = physical_len;
+
+        self
+    }
+
+    /// Constructs a [`Resample`] object.
+    pub fn build(self: Box<Self>) -> Resample {
+        Resample::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for ResampleBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Resample, ResampleBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Resample::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(physical_len:60)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(physical_len:144)"#;
+        let data: ResampleBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.physical_len, 144);
+    }
+}
+
+/// Filter that stretches or shrinks a frame authored for some number of
+/// "virtual" LEDs onto the drawer's actual physical LED count, via linear
+/// interpolation between the two nearest virtual samples. This lets one
+/// animation definition target strips of different physical lengths
+/// unmodified.
+#[derive(Debug)]
+pub struct Resample {
+    physical_len: ConstVal<usize>,
+}
+
+impl Resample {
+    /// Constructs a builder object with a safe default physical length of 60 LEDs.
+    pub fn builder() -> Box<ResampleBuilder> {
+        Box::new(ResampleBuilder { physical_len: 60 })
+    }
+
+    fn from_builder(builder: Box<ResampleBuilder>) -> Self {
+        Self::new(builder.physical_len)
+    }
+
+    fn new(physical_len: usize) -> Self {
+        Self {
+            physical_len: ConstVal::new(physical_len),
+        }
+    }
+}
+
+impl Filter for Resample {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        let physical_len = *self.physical_len.get();
+        let virtual_len = frame.len();
+
+        if virtual_len == 0 || physical_len == virtual_len {
+            return FilterState::Ok;
+        }
+
+        let sample = |pos: f32| -> RGB {
+            let pos = pos.min((virtual_len - 1) as f32).max(0.0);
+            let lo = pos.floor() as usize;
+            let hi = (lo + 1).min(virtual_len - 1);
+            let t = pos - lo as f32;
+
+            let a = frame[lo];
+            let b = frame[hi];
+
+            let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t) as u8;
+
+            RGB::from_tuple(
+                (lerp(a.red(), b.red()), lerp(a.green(), b.green()), lerp(a.blue(), b.blue())),
+                RGBOrder::RGB,
+            )
+        };
+
+        let resampled: Vec<RGB> = if physical_len == 1 {
+            vec![sample(0.0)]
+        } else {
+            let step = (virtual_len - 1) as f32 / (physical_len - 1) as f32;
+            (0..physical_len).map(|i| sample(i as f32 * step)).collect()
+        };
+
+        frame.resize(physical_len);
+        for (led, resampled) in frame.iter_mut().zip(resampled) {
+            *led = resampled;
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {}
+}
@@ -0,0 +1,111 @@
+//! A filter that reorders the LEDs of a frame in place, to correct a strip's wiring orientation in software.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::const_val::ConstVal;
+
+use super::*;
+
+/// The reordering a [`Spatial`] filter applies to a frame's LEDs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Reorder {
+    /// Leaves the LED order untouched.
+    Identity,
+    /// Mirrors the LED order end-to-end.
+    Reverse,
+    /// Translates the LED order left by `n` positions, wrapping around.
+    Rotate(usize),
+}
+
+/// Builder for the [`Spatial`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Spatial")]
+pub struct SpatialBuilder {
+    reorder: Reorder,
+}
+
+impl SpatialBuilder {
+    /// Sets the reordering to apply to the frame's LEDs.
+    pub fn reorder(mut self: Box<Self>, reorder: Reorder) -> Box<Self> {
+        self.reorder = reorder;
+
+        self
+    }
+
+    /// Constructs a [`Spatial`] object.
+    pub fn build(self: Box<Self>) -> Spatial {
+        Spatial::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for SpatialBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Reorder, Spatial, SpatialBuilder};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Spatial::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(reorder:Identity)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(reorder:Reverse)"#;
+        let data: SpatialBuilder = ron::de::from_str(input).unwrap();
+
+        assert!(matches!(data.reorder, Reorder::Reverse));
+    }
+}
+
+/// Filter that reorders a frame's LEDs in place, e.g. to mirror or reverse a
+/// strip's indices so its physical wiring orientation can be fixed in
+/// software rather than by changing the animation that targets it.
+#[derive(Debug)]
+pub struct Spatial {
+    reorder: ConstVal<Reorder>,
+}
+
+impl Spatial {
+    /// Constructs a builder object with safe default values (no reordering).
+    pub fn builder() -> Box<SpatialBuilder> {
+        Box::new(SpatialBuilder {
+            reorder: Reorder::Identity,
+        })
+    }
+
+    fn from_builder(builder: Box<SpatialBuilder>) -> Self {
+        Self::new(builder.reorder)
+    }
+
+    fn new(reorder: Reorder) -> Self {
+        Self {
+            reorder: ConstVal::new(reorder),
+        }
+    }
+}
+
+impl Filter for Spatial {
+    fn filter_frame(&mut self, frame: &mut Frame, _dt: Duration) -> FilterState {
+        match *self.reorder.get() {
+            Reorder::Identity => (),
+            Reorder::Reverse => frame.as_mut_slice().reverse(),
+            Reorder::Rotate(n) => frame.as_mut_slice().rotate_left(n % frame.len().max(1)),
+        }
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {}
+}
@@ -0,0 +1,143 @@
+//! A filter that resamples the generator's per-tick output towards a fixed
+//! target refresh rate by blending between the previous and current frame.
+//!
+//! Note: filters run once per [`Display`](ranos_display::Display) tick, at
+//! whatever cadence its [`Draw`](ranos_draw::Draw) loop already calls
+//! `render_frame` with -- there's no separate "generator clock" decoupled
+//! from that tick to resample between. What this filter actually gives you
+//! is the practical effect the request is after: it tracks how far into a
+//! `target_period`-long output interval the accumulated `dt` has reached and
+//! linearly blends towards the newly-rendered frame over that interval,
+//! rather than snapping to it instantly, which is what interpolating
+//! between "the previous frame" and "the current frame" amounts to once
+//! there's only one tick rate to work with.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_core::clock_duration::ClockDuration;
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// Builder for the [`Temporal`] filter.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "Temporal")]
+pub struct TemporalBuilder {
+    target_period: Duration,
+}
+
+impl TemporalBuilder {
+    /// Sets the target output period to resample towards (the reciprocal of the draw target's refresh rate).
+    pub fn target_period(mut self: Box<Self>, target_period: Duration) -> Box<Self> {
+        self.target_period = target_period;
+
+        self
+    }
+
+    /// Constructs a [`Temporal`] object.
+    pub fn build(self: Box<Self>) -> Temporal {
+        Temporal::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl FilterBuilder for TemporalBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Filter> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{Temporal, TemporalBuilder};
+    use std::time::Duration;
+
+    #[test]
+    fn test_serialize() {
+        let builder = Temporal::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(target_period:(secs:0,nanos:16666666))"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(target_period:(secs:0,nanos:16666667))"#;
+        let data: TemporalBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.target_period, Duration::from_nanos(16_666_667));
+    }
+}
+
+/// Filter that smooths a generator's output towards a fixed target refresh
+/// rate, by blending the previous frame's colors into the current frame by
+/// how far the accumulated `dt` has progressed through `target_period`.
+#[derive(Debug)]
+pub struct Temporal {
+    target_period: ConstVal<ClockDuration>,
+    phase: ClockDuration,
+    prev: Option<Vec<RGB>>,
+}
+
+impl Temporal {
+    /// Constructs a builder object with a safe default target period of 60Hz.
+    pub fn builder() -> Box<TemporalBuilder> {
+        Box::new(TemporalBuilder {
+            target_period: Duration::from_secs_f64(1.0 / 60.0),
+        })
+    }
+
+    fn from_builder(builder: Box<TemporalBuilder>) -> Self {
+        Self::new(builder.target_period)
+    }
+
+    fn new(target_period: Duration) -> Self {
+        Self {
+            target_period: ConstVal::new(ClockDuration::from(target_period)),
+            phase: ClockDuration::ZERO,
+            prev: None,
+        }
+    }
+}
+
+impl Filter for Temporal {
+    fn filter_frame(&mut self, frame: &mut Frame, dt: Duration) -> FilterState {
+        self.phase += ClockDuration::from(dt);
+
+        let period = *self.target_period.get();
+        let t = if period.as_femtos() == 0 {
+            1.0
+        } else {
+            (self.phase.as_secs_f64() / period.as_secs_f64()).min(1.0)
+        } as f32;
+
+        self.phase = self.phase.rem(period);
+
+        if let Some(prev) = &self.prev {
+            let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+            for (led, prev) in frame.iter_mut().zip(prev.iter()) {
+                *led = RGB::from_tuple(
+                    (
+                        lerp(prev.red(), led.red()),
+                        lerp(prev.green(), led.green()),
+                        lerp(prev.blue(), led.blue()),
+                    ),
+                    ranos_ds::rgb::RGBOrder::RGB,
+                );
+            }
+        }
+
+        self.prev = Some(frame.iter().copied().collect());
+
+        FilterState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.phase = ClockDuration::ZERO;
+        self.prev = None;
+    }
+}
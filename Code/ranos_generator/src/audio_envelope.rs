@@ -0,0 +1,257 @@
+//! A generator driven by a precomputed audio amplitude envelope, rather than
+//! live capture -- see [`AudioEnvelope`].
+
+use std::{fs::File, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::util::{envelope, read_wav};
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// The size, in samples, of the RMS window [`Source::Path`] is reduced to
+/// before being normalized into an envelope, matching the `gain-test`
+/// binary's own chunk size.
+const DEFAULT_CHUNK_SIZE: usize = 1 << 10;
+
+/// How [`AudioEnvelope`] maps its current envelope value, in `[0, 1]`, onto the frame.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum EnvelopeMapping {
+    /// Scales the frame's overall brightness by the envelope value.
+    Brightness,
+    /// Fills every LED with a color interpolated between `low` (at an
+    /// envelope value of `0`) and `high` (at `1`).
+    ColorRamp {
+        /// The color shown at the envelope's quietest value.
+        low: RGB,
+        /// The color shown at the envelope's loudest value.
+        high: RGB,
+    },
+}
+
+/// Where [`AudioEnvelopeBuilder`] gets its envelope from.
+#[derive(Debug, Clone)]
+enum Source {
+    /// A WAV file path; its envelope is computed at build time via
+    /// [`ranos_audio::util::envelope`].
+    Path(PathBuf),
+    /// An already-computed envelope, normalized into `[0, 1]`, and the
+    /// sample rate it was computed at.
+    Envelope(Vec<f32>, usize),
+}
+
+/// Builder for the [`AudioEnvelope`] generator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AudioEnvelope")]
+pub struct AudioEnvelopeBuilder {
+    #[serde(skip)]
+    source: Option<Source>,
+    chunk_size: usize,
+    mapping: EnvelopeMapping,
+}
+
+impl AudioEnvelopeBuilder {
+    /// Sets the WAV file this generator computes its envelope from, via
+    /// [`ranos_audio::util::envelope`] at build time.
+    pub fn wav_path(mut self: Box<Self>, path: impl Into<PathBuf>) -> Box<Self> {
+        self.source = Some(Source::Path(path.into()));
+
+        self
+    }
+
+    /// Sets an already-computed envelope (normalized into `[0, 1]`) and the
+    /// sample rate it was computed at, instead of loading a WAV file via
+    /// [`Self::wav_path`].
+    pub fn envelope(mut self: Box<Self>, envelope: Vec<f32>, sample_rate: usize) -> Box<Self> {
+        self.source = Some(Source::Envelope(envelope, sample_rate));
+
+        self
+    }
+
+    /// Sets the RMS window size, in samples, [`Self::wav_path`] reduces the
+    /// WAV file to before normalizing it into an envelope. Has no effect when
+    /// the envelope is supplied directly via [`Self::envelope`].
+    pub fn chunk_size(mut self: Box<Self>, chunk_size: usize) -> Box<Self> {
+        self.chunk_size = chunk_size.max(1);
+
+        self
+    }
+
+    /// Sets how the current envelope value is mapped onto the frame.
+    pub fn mapping(mut self: Box<Self>, mapping: EnvelopeMapping) -> Box<Self> {
+        self.mapping = mapping;
+
+        self
+    }
+
+    /// Constructs an [`AudioEnvelope`] object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither [`Self::wav_path`] nor [`Self::envelope`] was
+    /// called, or if the WAV file at [`Self::wav_path`] couldn't be read.
+    pub fn build(self: Box<Self>) -> AudioEnvelope {
+        AudioEnvelope::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for AudioEnvelopeBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{AudioEnvelope, EnvelopeMapping};
+
+    #[test]
+    fn test_serialize() {
+        let builder = AudioEnvelope::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(chunk_size:1024,mapping:Brightness)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(chunk_size:512,mapping:Brightness)"#;
+
+        let data: super::AudioEnvelopeBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.chunk_size, 512);
+        assert!(matches!(data.mapping, EnvelopeMapping::Brightness));
+    }
+}
+
+/// Generator that maps a precomputed audio amplitude envelope onto a frame,
+/// connecting the gain-tracking analysis the `gain-test` binary already
+/// performs to the animation subsystem, instead of only writing it out to a
+/// `gain.wav` debug file.
+///
+/// Each frame, [`Self::render_frame`] advances a playback cursor by `dt`
+/// scaled to the envelope's sample rate, reads the envelope's value at that
+/// position (holding the last value once the cursor runs past the end), and
+/// either scales the frame's overall brightness by it or interpolates every
+/// LED between [`EnvelopeMapping::ColorRamp`]'s two configured colors, per
+/// [`AudioEnvelopeBuilder::mapping`].
+///
+/// To create an [`AudioEnvelope`], use the associated
+/// [builder](AudioEnvelopeBuilder), accessed via [`AudioEnvelope::builder()`].
+#[derive(Debug)]
+pub struct AudioEnvelope {
+    id: usize,
+
+    envelope: ConstVal<Vec<f32>>,
+    sample_rate: ConstVal<f32>,
+    mapping: EnvelopeMapping,
+
+    cursor: f32,
+}
+
+impl AudioEnvelope {
+    /// Constructs a builder object with safe default values: a 1024-sample
+    /// RMS window and a brightness mapping. A sample source must still be
+    /// supplied via [`AudioEnvelopeBuilder::wav_path`] or
+    /// [`AudioEnvelopeBuilder::envelope`] before building.
+    pub fn builder() -> Box<AudioEnvelopeBuilder> {
+        Box::new(AudioEnvelopeBuilder {
+            source: None,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            mapping: EnvelopeMapping::Brightness,
+        })
+    }
+
+    fn from_builder(builder: Box<AudioEnvelopeBuilder>) -> Self {
+        let (data, sample_rate) = match builder.source.expect(
+            "AudioEnvelope generator requires a sample source, set via AudioEnvelopeBuilder::wav_path or AudioEnvelopeBuilder::envelope",
+        ) {
+            Source::Path(path) => {
+                let mut file = File::open(&path)
+                    .unwrap_or_else(|e| panic!("failed to open {:?}: {}", path, e));
+                let (sample_rate, samples) = read_wav(&mut file)
+                    .unwrap_or_else(|e| panic!("failed to read wav {:?}: {}", path, e));
+
+                (envelope(&samples, builder.chunk_size), sample_rate)
+            }
+            Source::Envelope(data, sample_rate) => (data, sample_rate),
+        };
+
+        Self::new(data, sample_rate, builder.mapping)
+    }
+
+    fn new(envelope: Vec<f32>, sample_rate: usize, mapping: EnvelopeMapping) -> Self {
+        Self {
+            id: ranos_core::id::generate(),
+
+            envelope: ConstVal::new(envelope),
+            sample_rate: ConstVal::new(sample_rate as f32),
+            mapping,
+
+            cursor: 0.0,
+        }
+    }
+
+    /// Reads the envelope's value at the current cursor position, holding
+    /// its last value once the cursor has run past the end.
+    fn current_level(&self) -> f32 {
+        let envelope = self.envelope.get();
+
+        if envelope.is_empty() {
+            return 0.0;
+        }
+
+        let ind = (self.cursor as usize).min(envelope.len() - 1);
+
+        envelope[ind]
+    }
+}
+
+impl Generator for AudioEnvelope {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        self.cursor += dt.as_secs_f32() * *self.sample_rate.get();
+
+        let level = self.current_level().clamp(0.0, 1.0);
+
+        match self.mapping {
+            EnvelopeMapping::Brightness => frame.set_brightness(level),
+            EnvelopeMapping::ColorRamp { low, high } => {
+                let color = lerp_color(low, high, level);
+
+                for led in frame.iter_mut() {
+                    *led = color;
+                }
+            }
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.cursor = 0.0;
+    }
+}
+
+/// Linearly interpolates between two colors by `t`, clamped to `[0, 1]`.
+fn lerp_color(from: RGB, to: RGB, t: f32) -> RGB {
+    let t = t.min(1.0).max(0.0);
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+    RGB::from_tuple(
+        (
+            lerp(from.red(), to.red()),
+            lerp(from.green(), to.green()),
+            lerp(from.blue(), to.blue()),
+        ),
+        ranos_ds::rgb::RGBOrder::RGB,
+    )
+}
@@ -0,0 +1,288 @@
+//! Generator that maps live, real-time audio into colors by way of an FFT.
+
+use std::{f32::consts::PI, sync::Arc, time::Duration};
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+
+use ranos_audio::{capture::Capture, SIZE};
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// The rate at which a band's held peak marker decays relative to its own
+/// `decay`, so the marker always lags behind the level it's tracking.
+const PEAK_DECAY_EXPONENT: f32 = 0.25;
+
+/// Builder for the [`AudioSpectrum`] generator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "AudioSpectrum")]
+pub struct AudioSpectrumBuilder {
+    num_bands: usize,
+    decay: f32,
+    gain: f32,
+    peak_hold: bool,
+}
+
+impl AudioSpectrumBuilder {
+    /// Sets the number of logarithmically-spaced frequency bands to group the spectrum into.
+    pub fn num_bands(mut self: Box<Self>, num_bands: usize) -> Box<Self> {
+        self.num_bands = num_bands.max(1);
+
+        self
+    }
+
+    /// Sets how much each band's level decays per frame, in `[0, 1)`, once
+    /// its energy falls. A decay of `1.0` would never let a level fall; values close to
+    /// that make for a laggier, smoother display.
+    pub fn decay(mut self: Box<Self>, decay: f32) -> Box<Self> {
+        self.decay = decay.clamp(0.0, 0.999);
+
+        self
+    }
+
+    /// Sets the linear gain applied to each band's magnitude before it's
+    /// clamped into `[0, 1]`, to compensate for quiet input devices.
+    pub fn gain(mut self: Box<Self>, gain: f32) -> Box<Self> {
+        self.gain = gain.max(0.0);
+
+        self
+    }
+
+    /// Sets whether each band also renders a slowly-decaying peak marker at
+    /// the edge of its segment of the strip.
+    pub fn peak_hold(mut self: Box<Self>, peak_hold: bool) -> Box<Self> {
+        self.peak_hold = peak_hold;
+
+        self
+    }
+
+    /// Constructs an [`AudioSpectrum`] object, opening the system's default
+    /// audio input device.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no input device is available or it could not be captured from.
+    pub fn build(self: Box<Self>) -> AudioSpectrum {
+        AudioSpectrum::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for AudioSpectrumBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::AudioSpectrumBuilder;
+    use crate::AudioSpectrum;
+
+    #[test]
+    fn test_serialize() {
+        let builder = AudioSpectrum::builder();
+
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(num_bands:16,decay:0.8,gain:1,peak_hold:true)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(num_bands:24,decay:0.9,gain:2,peak_hold:false)"#;
+
+        let data: AudioSpectrumBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.num_bands, 24);
+        assert_eq!(data.decay, 0.9);
+        assert_eq!(data.gain, 2.0);
+        assert_eq!(data.peak_hold, false);
+    }
+}
+
+/// Generator that maps the live frequency spectrum of the system's default
+/// audio input device onto the LED strip, WLED-audioreactive style.
+///
+/// Each frame, the most recent [`ranos_audio::SIZE`] captured samples are
+/// windowed with a Hann window, transformed with a real FFT, and the
+/// magnitude spectrum is grouped into logarithmically-spaced bands spanning
+/// ~20 Hz to Nyquist. Each band's level rises instantly with new energy and
+/// decays exponentially otherwise, then drives a hue sweep across the strip
+/// -- bass at one end, treble at the other -- with band energy setting
+/// brightness. With peak hold enabled, each band also carries a marker that
+/// decays more slowly than the level itself.
+///
+/// To create an [`AudioSpectrum`], use the associated
+/// [builder](AudioSpectrumBuilder), accessed via [`AudioSpectrum::builder()`].
+#[derive(Debug)]
+pub struct AudioSpectrum {
+    id: usize,
+
+    capture: Capture,
+
+    hann_window: ConstVal<[f32; SIZE]>,
+    fft: Arc<dyn Fft<f32>>,
+    spectrum: Box<[Complex<f32>; SIZE]>,
+    scratch: Box<[Complex<f32>; SIZE]>,
+
+    num_bands: ConstVal<usize>,
+    band_edges: ConstVal<Vec<usize>>,
+    decay: f32,
+    gain: f32,
+    band_levels: Vec<f32>,
+
+    peak_hold: bool,
+    peak_levels: Vec<f32>,
+}
+
+impl AudioSpectrum {
+    /// Constructs a builder object with safe default values.
+    pub fn builder() -> Box<AudioSpectrumBuilder> {
+        Box::new(AudioSpectrumBuilder {
+            num_bands: 16,
+            decay: 0.8,
+            gain: 1.0,
+            peak_hold: true,
+        })
+    }
+
+    fn from_builder(builder: Box<AudioSpectrumBuilder>) -> Self {
+        Self::new(
+            builder.num_bands,
+            builder.decay,
+            builder.gain,
+            builder.peak_hold,
+        )
+    }
+
+    fn new(num_bands: usize, decay: f32, gain: f32, peak_hold: bool) -> Self {
+        let capture = Capture::new().expect("AudioSpectrum generator requires an audio input device");
+        let sample_rate = capture.sample_rate();
+
+        let mut hann_window = [0.0_f32; SIZE];
+        for (n, w) in hann_window.iter_mut().enumerate() {
+            *w = 0.5 - 0.5 * (2.0 * PI * n as f32 / (SIZE as f32 - 1.0)).cos();
+        }
+
+        Self {
+            id: ranos_core::id::generate(),
+
+            capture,
+
+            hann_window: ConstVal::new(hann_window),
+            fft: FftPlanner::new().plan_fft_forward(SIZE),
+            spectrum: Box::new([Complex::new(0.0, 0.0); SIZE]),
+            scratch: Box::new([Complex::new(0.0, 0.0); SIZE]),
+
+            num_bands: ConstVal::new(num_bands),
+            band_edges: ConstVal::new(log_band_edges(num_bands, sample_rate)),
+            decay,
+            gain,
+            band_levels: vec![0.0; num_bands],
+
+            peak_hold,
+            peak_levels: vec![0.0; num_bands],
+        }
+    }
+
+    /// Captures the most recent samples, windows and transforms them, and
+    /// updates the per-band levels (and peak markers, if enabled).
+    fn update_bands(&mut self) {
+        let samples = self.capture.most_recent_data();
+
+        for (s, (sample, window)) in self
+            .spectrum
+            .iter_mut()
+            .zip(samples.iter().zip(self.hann_window.get().iter()))
+        {
+            *s = Complex::new(sample * window, 0.0);
+        }
+
+        self.fft
+            .process_with_scratch(&mut *self.spectrum, &mut *self.scratch);
+
+        let edges = self.band_edges.get();
+        for band in 0..*self.num_bands.get() {
+            let (begin, end) = (edges[band], edges[band + 1]);
+
+            let mut energy = 0.0;
+            // Drop the DC bin (index 0) from band 0's range.
+            for bin in begin.max(1)..end {
+                energy += self.spectrum[bin].norm() / (SIZE as f32);
+            }
+            let new_level =
+                ((energy / (end - begin.max(1)).max(1) as f32) * self.gain).min(1.0);
+
+            let level = &mut self.band_levels[band];
+            *level = new_level.max(*level * self.decay);
+
+            if self.peak_hold {
+                let peak = &mut self.peak_levels[band];
+                let peak_decay = self.decay.powf(PEAK_DECAY_EXPONENT);
+                *peak = level.max(*peak * peak_decay);
+            }
+        }
+    }
+}
+
+impl Generator for AudioSpectrum {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, _dt: Duration) -> GeneratorState {
+        self.update_bands();
+
+        let num_bands = *self.num_bands.get();
+        let len = frame.len();
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let band = (i * num_bands / len.max(1)).min(num_bands - 1);
+            let hue = band as f32 / num_bands as f32 * 360.0;
+
+            let next_band = ((i + 1) * num_bands / len.max(1)).min(num_bands - 1);
+            let is_last_in_band = i + 1 == len || next_band != band;
+            let value = if self.peak_hold && is_last_in_band {
+                self.peak_levels[band]
+            } else {
+                self.band_levels[band]
+            };
+
+            *led = RGB::from_hsv(hue, 1.0, value);
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        for level in self.band_levels.iter_mut() {
+            *level = 0.0;
+        }
+        for peak in self.peak_levels.iter_mut() {
+            *peak = 0.0;
+        }
+    }
+}
+
+/// Computes `num_bands + 1` FFT bin-index edges, logarithmically spaced from
+/// ~20 Hz to Nyquist (`sample_rate / 2`), across the first `SIZE / 2` bins.
+fn log_band_edges(num_bands: usize, sample_rate: f32) -> Vec<usize> {
+    let nyquist = sample_rate / 2.0;
+    let min_freq = 20.0_f32.min(nyquist);
+
+    let log_min = min_freq.ln();
+    let log_max = nyquist.ln();
+
+    (0..=num_bands)
+        .map(|i| {
+            let t = i as f32 / num_bands as f32;
+            let freq = (log_min + t * (log_max - log_min)).exp();
+            let bin = (freq / nyquist * (SIZE as f32 / 2.0)).round() as usize;
+
+            bin.min(SIZE / 2)
+        })
+        .collect()
+}
@@ -0,0 +1,248 @@
+//! # Compositor
+//!
+//! A generator that plays several sub-generators simultaneously onto the
+//! same frame, each a prioritized layer confined to its own LED range and
+//! merged with its own blend mode -- rather than [`Display`](ranos_display::Display)'s
+//! usual one-generator-at-a-time queue, which only ever has a single
+//! generator's output on screen at once.
+//!
+//! [`KeyframeGenerator`](crate::KeyframeGenerator) solves a similar layering
+//! problem for its own keyframe data; [`Compositor`] generalizes it to
+//! arbitrary sub-generators, e.g. a background [`Cycle`](crate::Cycle) with a
+//! foreground [`KeyframeGenerator`] alert confined to a sub-segment of the strip.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{
+    collections::frame::Frame,
+    rgb::{RGBOrder, RGB},
+};
+
+use super::*;
+
+/// How a layer's rendered LEDs are merged into the composited output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Overwrites the output LED with the layer's outright.
+    Replace,
+    /// Adds the layer's channel values to the output's, clamping each
+    /// channel to `u8::MAX` rather than wrapping.
+    Additive,
+    /// Linearly interpolates the output LED towards the layer's by a fixed
+    /// alpha in `[0, 1]`.
+    AlphaOver(f32),
+}
+
+impl BlendMode {
+    /// Merges `src` (this layer's rendered color) onto `dst` (the output
+    /// accumulated so far) according to this blend mode.
+    fn merge(&self, dst: RGB, src: RGB) -> RGB {
+        match *self {
+            BlendMode::Replace => src,
+            BlendMode::Additive => {
+                let add = |a: u8, b: u8| a.saturating_add(b);
+                RGB::from_tuple(
+                    (add(dst.red(), src.red()), add(dst.green(), src.green()), add(dst.blue(), src.blue())),
+                    RGBOrder::RGB,
+                )
+            }
+            BlendMode::AlphaOver(alpha) => {
+                let alpha = alpha.min(1.0).max(0.0);
+                let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * alpha) as u8;
+                RGB::from_tuple(
+                    (lerp(dst.red(), src.red()), lerp(dst.green(), src.green()), lerp(dst.blue(), src.blue())),
+                    RGBOrder::RGB,
+                )
+            }
+        }
+    }
+}
+
+/// One prioritized sub-generator layer, built up on a [`CompositorBuilder`] via [`CompositorBuilder::layer`].
+#[derive(Debug, Serialize, Deserialize)]
+struct LayerBuilder {
+    generator: Box<dyn GeneratorBuilder>,
+    priority: i32,
+    range: Option<Range<usize>>,
+    blend: BlendMode,
+}
+
+/// Builder for the [`Compositor`] generator.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Compositor")]
+pub struct CompositorBuilder {
+    layers: Vec<LayerBuilder>,
+}
+
+impl CompositorBuilder {
+    /// Adds a layer playing `generator` every tick, merged into the output
+    /// with `blend`. Layers composite low-to-high `priority`, so a
+    /// higher-priority layer painted afterwards overlays a lower-priority
+    /// one beneath it. `range` confines which output LED indices this layer
+    /// is allowed to write; `None` lets it write the whole frame.
+    pub fn layer(
+        mut self: Box<Self>,
+        generator: Box<dyn GeneratorBuilder>,
+        priority: i32,
+        range: Option<Range<usize>>,
+        blend: BlendMode,
+    ) -> Box<Self> {
+        self.layers.push(LayerBuilder {
+            generator,
+            priority,
+            range,
+            blend,
+        });
+
+        self
+    }
+
+    /// Constructs a [`Compositor`] object.
+    pub fn build(self: Box<Self>) -> Compositor {
+        Compositor::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for CompositorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{BlendMode, CompositorBuilder};
+    use crate::{Compositor, Cycle};
+
+    #[test]
+    fn test_serialize() {
+        let builder = Box::new(CompositorBuilder::default())
+            .layer(Cycle::builder(), 0, None, BlendMode::Replace);
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let expected = r#"(layers:[(generator:(type:"CycleBuilder",value:(cycle_period:(secs:0,nanos:363636363),order:Ordered([(255,0,0),(0,255,0),(0,0,255)]))),priority:0,range:None,blend:Replace)])"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(layers:[(generator:(type:"CycleBuilder",value:(cycle_period:(secs:0,nanos:363636363),order:Ordered([(255,0,0),(0,255,0),(0,0,255)]))),priority:0,range:Some((start:0,end:8)),blend:AlphaOver(0.5))])"#;
+
+        let builder: CompositorBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(builder.layers.len(), 1);
+        assert_eq!(builder.layers[0].priority, 0);
+        assert_eq!(builder.layers[0].range, Some(0..8));
+    }
+}
+
+/// A single layer's runtime state: its sub-generator, the scratch frame it
+/// renders into in isolation, and how that scratch frame gets merged into
+/// the composited output.
+#[derive(Debug)]
+struct Layer {
+    generator: Box<dyn Generator>,
+    priority: i32,
+    range: Option<Range<usize>>,
+    blend: BlendMode,
+    scratch: Frame,
+}
+
+impl Layer {
+    fn from_builder(builder: LayerBuilder) -> Self {
+        Self {
+            generator: builder.generator.build(),
+            priority: builder.priority,
+            range: builder.range,
+            blend: builder.blend,
+            scratch: Frame::new(1.0, 0),
+        }
+    }
+}
+
+/// Generator that plays several sub-generators at once, each a prioritized
+/// layer rendered into its own scratch frame and then merged into the
+/// output frame over a confined LED range with its own [`BlendMode`].
+///
+/// Every layer renders every tick regardless of its priority or range --
+/// priority and range only affect how the already-rendered layers are
+/// merged together afterwards, back-to-front by priority.
+///
+/// To create a [`Compositor`], use the associated [builder](CompositorBuilder),
+/// accessed via [`Compositor::builder()`].
+#[derive(Debug)]
+pub struct Compositor {
+    id: usize,
+    layers: Vec<Layer>,
+}
+
+impl Compositor {
+    /// Constructs a builder object with no layers by default.
+    pub fn builder() -> Box<CompositorBuilder> {
+        Box::new(CompositorBuilder::default())
+    }
+
+    fn from_builder(builder: Box<CompositorBuilder>) -> Self {
+        Self::new(builder.layers)
+    }
+
+    fn new(layer_builders: Vec<LayerBuilder>) -> Self {
+        let mut layers: Vec<Layer> = layer_builders.into_iter().map(Layer::from_builder).collect();
+        layers.sort_by_key(|l| l.priority);
+
+        Self {
+            id: ranos_core::id::generate(),
+            layers,
+        }
+    }
+}
+
+impl Generator for Compositor {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        let len = frame.len();
+
+        for layer in self.layers.iter_mut() {
+            if layer.scratch.len() != len {
+                layer.scratch = Frame::new(1.0, len);
+            }
+
+            loop {
+                match layer.generator.render_frame(&mut layer.scratch, dt) {
+                    GeneratorState::Ok => break,
+                    GeneratorState::ErrRetry => continue,
+                    GeneratorState::ErrSkip => break,
+                    GeneratorState::ErrFatal => return GeneratorState::ErrFatal,
+                }
+            }
+        }
+
+        for layer in self.layers.iter() {
+            let range = layer.range.clone().unwrap_or(0..len);
+
+            for i in range {
+                if i >= len {
+                    continue;
+                }
+
+                let merged = layer.blend.merge(frame[i], layer.scratch[i]);
+                frame.as_mut_slice()[i] = merged;
+            }
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.generator.reset();
+        }
+    }
+}
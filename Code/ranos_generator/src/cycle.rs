@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use ranos_core::ClockDuration;
 use ranos_ds::{
     const_val::ConstVal,
     rgb::{RGBOrder, RGB},
@@ -87,12 +88,16 @@ mod builder_test {
 /// amount of time before proceeding to the next color.
 #[derive(Debug)]
 pub struct Cycle {
+    id: usize,
+
     order: ColorOrder,
     ind: usize,
     current_color: RGB,
 
     cycle_period: ConstVal<Duration>,
-    cycle_time_remaining: Duration,
+    cycle_period_exact: ConstVal<ClockDuration>,
+    elapsed: ClockDuration,
+    current_period: u128,
 }
 
 impl Cycle {
@@ -114,6 +119,8 @@ impl Cycle {
 
     fn new(cycle_period: Duration, order: ColorOrder) -> Self {
         Self {
+            id: ranos_core::id::generate(),
+
             order: order.clone(),
             ind: 0,
             current_color: match order {
@@ -123,44 +130,56 @@ impl Cycle {
             },
 
             cycle_period: cycle_period.into(),
-            cycle_time_remaining: cycle_period,
+            cycle_period_exact: ClockDuration::from(cycle_period).into(),
+            elapsed: ClockDuration::ZERO,
+            current_period: 0,
         }
     }
 }
 
 impl Generator for Cycle {
+    fn id(&self) -> usize {
+        self.id
+    }
+
     fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
-        self.cycle_time_remaining = if let Some(d) = self.cycle_time_remaining.checked_sub(dt) {
-            d
-        } else {
-            if let ColorOrder::Ordered(v) = &self.order {
-                self.ind += 1;
-                self.ind %= v.len();
-                self.current_color = v[self.ind];
-            } else {
-                self.current_color = RGB::random()
-            }
+        self.elapsed += ClockDuration::from(dt);
+
+        // Computing the cycle index as `floor(elapsed / period)` off an
+        // absolute femtosecond counter -- rather than incrementally
+        // subtracting `dt` from a `Duration` remaining-time countdown --
+        // keeps cycle boundaries exact no matter how much rounding error a
+        // long run's `dt`s would otherwise have accumulated.
+        let period = self.elapsed.periods(*self.cycle_period_exact.get());
+
+        if period != self.current_period {
+            self.current_period = period;
+
+            self.current_color = match &self.order {
+                ColorOrder::Ordered(v) => {
+                    self.ind = (period % v.len() as u128) as usize;
+                    v[self.ind]
+                }
+                _ => RGB::random(),
+            };
 
             // Only update the frame when there's a new color
             for led in frame.iter_mut() {
                 *led = self.current_color;
             }
-
-            self.cycle_period.get().clone() + self.cycle_time_remaining - dt
-        };
+        }
 
         GeneratorState::Ok
     }
 
-    fn reset(mut self: Box<Self>) -> Box<dyn Generator> {
+    fn reset(&mut self) {
         self.ind = 0;
         self.current_color = match &self.order {
             ColorOrder::Ordered(v) => v[0],
             ColorOrder::Random => RGB::random(),
             ColorOrder::RandomBright => RGB::random_bright(),
         };
-        self.cycle_time_remaining = *self.cycle_period.get();
-
-        self
+        self.elapsed = ClockDuration::ZERO;
+        self.current_period = 0;
     }
 }
@@ -0,0 +1,188 @@
+//! Generator that replays a [`ranos_ds::collections::FrameSequence`] stream
+//! -- the reader half of `ranos_draw`'s `RecorderDraw` -- letting a session
+//! captured once (e.g. on a Pi) be replayed deterministically into any other
+//! [`Draw`](ranos_draw)-implementing target, honoring each recorded frame's
+//! exact presentation duration rather than assuming a constant nominal fps
+//! the way [`RecordingGenerator`](crate::RecordingGenerator) does.
+
+use std::{fs::File, io::BufReader, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{collections::frame_sequence::FrameSequence, const_val::ConstVal};
+
+use super::*;
+
+/// Builder for the [`FrameSequenceGenerator`] generator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "FrameSequenceGenerator")]
+pub struct FrameSequenceGeneratorBuilder {
+    path: PathBuf,
+    looping: bool,
+}
+
+impl FrameSequenceGeneratorBuilder {
+    /// Sets the path of the [`FrameSequence`] recording to load.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets whether the sequence loops once its last record is reached.
+    ///
+    /// If `false`, the generator simply holds the last frame once it's been reached.
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.looping = looping;
+
+        self
+    }
+
+    /// Constructs a [`FrameSequenceGenerator`] object, reading the configured recording from disk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file can't be read, or doesn't parse as a [`FrameSequence`].
+    pub fn build(self: Box<Self>) -> FrameSequenceGenerator {
+        FrameSequenceGenerator::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for FrameSequenceGeneratorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::FrameSequenceGeneratorBuilder;
+    use crate::FrameSequenceGenerator;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = FrameSequenceGenerator::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(path:"",looping:true)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"capture.ranseq",looping:false)"#;
+        let data: FrameSequenceGeneratorBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("capture.ranseq"));
+        assert_eq!(data.looping, false);
+    }
+}
+
+/// Generator that replays a [`FrameSequence`] recording from disk instead of
+/// a procedural effect: each record is shown for its own recorded
+/// [`Duration`], with `dt` accumulated between [`Generator::render_frame`]
+/// calls the same way [`ImageGenerator`](crate::ImageGenerator) advances
+/// between GIF frames.
+///
+/// To create a [`FrameSequenceGenerator`], use the associated
+/// [builder](FrameSequenceGeneratorBuilder), accessed via
+/// [`FrameSequenceGenerator::builder()`].
+#[derive(Debug)]
+pub struct FrameSequenceGenerator {
+    id: usize,
+
+    sequence: ConstVal<FrameSequence>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+}
+
+impl FrameSequenceGenerator {
+    /// Constructs a builder object with safe default values: an empty path
+    /// (must be set before building), looping.
+    pub fn builder() -> Box<FrameSequenceGeneratorBuilder> {
+        Box::new(FrameSequenceGeneratorBuilder {
+            path: PathBuf::new(),
+            looping: true,
+        })
+    }
+
+    fn from_builder(builder: Box<FrameSequenceGeneratorBuilder>) -> Self {
+        Self::new(builder.path, builder.looping)
+    }
+
+    fn new(path: PathBuf, looping: bool) -> Self {
+        let file = File::open(&path)
+            .unwrap_or_else(|e| panic!("failed to open frame sequence {:?}: {}", path, e));
+        let sequence = FrameSequence::read(&mut BufReader::new(file))
+            .unwrap_or_else(|e| panic!("failed to read frame sequence {:?}: {}", path, e));
+
+        Self {
+            id: ranos_core::id::generate(),
+
+            sequence: ConstVal::new(sequence),
+            looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+        }
+    }
+
+    /// Advances which record is current by accumulating `dt` against each
+    /// record's own recorded duration. A no-op if there's only one record
+    /// (or none).
+    fn advance(&mut self, dt: Duration) {
+        if self.sequence.get().len() <= 1 {
+            return;
+        }
+
+        self.elapsed += dt;
+
+        while let Some((duration, _)) = self.sequence.get().get(self.ind) {
+            if self.elapsed < *duration {
+                break;
+            }
+
+            self.elapsed -= *duration;
+            self.ind += 1;
+
+            if self.ind >= self.sequence.get().len() {
+                if self.looping {
+                    self.ind = 0;
+                } else {
+                    self.ind = self.sequence.get().len() - 1;
+                    self.elapsed = Duration::new(0, 0);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Generator for FrameSequenceGenerator {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        self.advance(dt);
+
+        if let Some((_, current)) = self.sequence.get().get(self.ind) {
+            frame.set_brightness(current.brightness());
+
+            for (led, color) in frame.iter_mut().zip(current.iter()) {
+                *led = *color;
+            }
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+    }
+}
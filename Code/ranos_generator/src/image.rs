@@ -0,0 +1,348 @@
+//! A generator that maps a still image or animated GIF onto a frame, either
+//! by scrolling a sampled row/column across a 1-D LED strip or by resampling
+//! to a known grid layout.
+//!
+//! Decoding is done with the [`image`] crate. Before any pixel buffer is
+//! allocated, the source's dimensions are checked against
+//! [`MAX_IMAGE_DIMENSION`] and rejected if they're absurd, the same guard the
+//! Maraiah PICT loader applies before trusting a file's declared size.
+
+use std::{path::PathBuf, time::Duration};
+
+use image::{
+    codecs::gif::GifDecoder, io::Reader as ImageReader, AnimationDecoder, DynamicImage,
+    GenericImageView, ImageDecoder, RgbImage,
+};
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// Refuses to decode a source image whose width or height exceeds this many
+/// pixels, so a malformed or absurdly large file can't be used to trigger an
+/// out-of-memory allocation before it's even been mapped onto a frame.
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+/// How an [`ImageGenerator`] maps a decoded image's pixels onto the target [`Frame`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Layout {
+    /// Samples a single row (or column) of the source image and scrolls it
+    /// across a 1-D strip of LEDs over time.
+    Strip {
+        /// The row (if `horizontal`) or column (if not) to sample.
+        line: u32,
+        /// Scrolls left-to-right along a row if `true`, top-to-bottom along a column if `false`.
+        horizontal: bool,
+        /// How many image pixels to advance per second.
+        pixels_per_sec: f32,
+    },
+    /// Resamples the source image to an explicit `width` x `height` grid,
+    /// with no scrolling. The frame this generator renders into must be
+    /// exactly `width * height` LEDs, laid out row-major.
+    Grid {
+        /// Grid width, in LEDs.
+        width: u32,
+        /// Grid height, in LEDs.
+        height: u32,
+    },
+}
+
+/// Builder for the [`ImageGenerator`] generator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "ImageGenerator")]
+pub struct ImageGeneratorBuilder {
+    path: PathBuf,
+    layout: Layout,
+    looping: bool,
+}
+
+impl ImageGeneratorBuilder {
+    /// Sets the path of the still image or GIF to load.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets how the decoded image is mapped onto the target frame.
+    pub fn layout(mut self: Box<Self>, layout: Layout) -> Box<Self> {
+        self.layout = layout;
+
+        self
+    }
+
+    /// Sets whether an animated GIF's frames loop once its last frame is reached.
+    ///
+    /// Has no effect on a still image. If `false`, the generator simply holds
+    /// the last frame once it's been reached.
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.looping = looping;
+
+        self
+    }
+
+    /// Constructs an [`ImageGenerator`] object, decoding the configured image from disk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file can't be read, can't be decoded, or its dimensions exceed [`MAX_IMAGE_DIMENSION`].
+    pub fn build(self: Box<Self>) -> ImageGenerator {
+        ImageGenerator::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for ImageGeneratorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{ImageGenerator, ImageGeneratorBuilder, Layout};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = ImageGenerator::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(path:"",layout:Strip(line:0,horizontal:true,pixels_per_sec:8),looping:true)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"logo.gif",layout:Grid(width:8,height:8),looping:false)"#;
+        let data: ImageGeneratorBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("logo.gif"));
+        assert!(matches!(
+            data.layout,
+            Layout::Grid {
+                width: 8,
+                height: 8
+            }
+        ));
+        assert_eq!(data.looping, false);
+    }
+}
+
+/// A single decoded frame of source pixel data, paired with how long it's shown for.
+#[derive(Debug)]
+struct DecodedFrame {
+    image: RgbImage,
+    delay: Duration,
+}
+
+/// Generator that drives a frame from artwork on disk instead of a
+/// procedural effect: a still image is sampled once, while an animated GIF's
+/// frames advance by accumulating `dt` against each frame's own delay.
+///
+/// To create an [`ImageGenerator`], use the associated
+/// [builder](ImageGeneratorBuilder), accessed via [`ImageGenerator::builder()`].
+#[derive(Debug)]
+pub struct ImageGenerator {
+    id: usize,
+
+    frames: ConstVal<Vec<DecodedFrame>>,
+    layout: ConstVal<Layout>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+    scroll_elapsed: Duration,
+}
+
+impl ImageGenerator {
+    /// Constructs a builder object with safe default values: an empty path
+    /// (must be set before building), scrolling the top row of the source
+    /// image at 8 pixels/sec, looping.
+    pub fn builder() -> Box<ImageGeneratorBuilder> {
+        Box::new(ImageGeneratorBuilder {
+            path: PathBuf::new(),
+            layout: Layout::Strip {
+                line: 0,
+                horizontal: true,
+                pixels_per_sec: 8.0,
+            },
+            looping: true,
+        })
+    }
+
+    fn from_builder(builder: Box<ImageGeneratorBuilder>) -> Self {
+        Self::new(builder.path, builder.layout, builder.looping)
+    }
+
+    fn new(path: PathBuf, layout: Layout, looping: bool) -> Self {
+        let frames = Self::load(&path)
+            .unwrap_or_else(|e| panic!("failed to load image {:?}: {}", path, e));
+
+        Self {
+            id: ranos_core::id::generate(),
+
+            frames: ConstVal::new(frames),
+            layout: ConstVal::new(layout),
+            looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+            scroll_elapsed: Duration::new(0, 0),
+        }
+    }
+
+    /// Loads `path` as either a still image or an animated GIF, validating
+    /// its dimensions before decoding any pixel data.
+    fn load(path: &PathBuf) -> image::ImageResult<Vec<DecodedFrame>> {
+        let is_gif = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("gif"))
+            .unwrap_or(false);
+
+        if is_gif {
+            let file = std::fs::File::open(path)?;
+            let decoder = GifDecoder::new(file)?;
+
+            let (width, height) = decoder.dimensions();
+            Self::check_dimensions(width, height);
+
+            decoder
+                .into_frames()
+                .map(|f| {
+                    f.map(|frame| {
+                        let (numer, denom) = frame.delay().numer_denom_ms();
+                        let delay = Duration::from_millis(numer as u64 / denom.max(1) as u64);
+
+                        DecodedFrame {
+                            image: DynamicImage::ImageRgba8(frame.into_buffer()).to_rgb8(),
+                            delay,
+                        }
+                    })
+                })
+                .collect()
+        } else {
+            let (width, height) = ImageReader::open(path)?.with_guessed_format()?.into_dimensions()?;
+            Self::check_dimensions(width, height);
+
+            let image = ImageReader::open(path)?.with_guessed_format()?.decode()?;
+
+            Ok(vec![DecodedFrame {
+                image: image.to_rgb8(),
+                delay: Duration::new(0, 0),
+            }])
+        }
+    }
+
+    fn check_dimensions(width: u32, height: u32) {
+        assert!(
+            width <= MAX_IMAGE_DIMENSION && height <= MAX_IMAGE_DIMENSION,
+            "image dimensions {}x{} exceed the maximum of {}x{}",
+            width,
+            height,
+            MAX_IMAGE_DIMENSION,
+            MAX_IMAGE_DIMENSION,
+        );
+    }
+
+    /// Advances which decoded frame is current by accumulating `dt` against
+    /// each frame's own delay. A no-op for a still image (a single frame with
+    /// a zero delay).
+    fn advance(&mut self, dt: Duration) {
+        if self.frames.get().len() <= 1 {
+            return;
+        }
+
+        self.elapsed += dt;
+
+        while self.elapsed >= self.frames.get()[self.ind].delay {
+            self.elapsed -= self.frames.get()[self.ind].delay;
+            self.ind += 1;
+
+            if self.ind >= self.frames.get().len() {
+                if self.looping {
+                    self.ind = 0;
+                } else {
+                    self.ind = self.frames.get().len() - 1;
+                    self.elapsed = Duration::new(0, 0);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn render_strip(&self, frame: &mut Frame, line: u32, horizontal: bool, pixels_per_sec: f32) {
+        let image = &self.frames.get()[self.ind].image;
+        let (width, height) = image.dimensions();
+
+        let scroll_len = if horizontal { width } else { height };
+        if scroll_len == 0 || frame.len() == 0 {
+            return;
+        }
+
+        let offset =
+            (self.scroll_elapsed.as_secs_f32() * pixels_per_sec) as u32 % scroll_len;
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            let pos = (offset + i as u32) % scroll_len;
+            let pixel = if horizontal {
+                image.get_pixel(pos, line.min(height.saturating_sub(1)))
+            } else {
+                image.get_pixel(line.min(width.saturating_sub(1)), pos)
+            };
+
+            *led = RGB::from_tuple((pixel[0], pixel[1], pixel[2]), ranos_ds::rgb::RGBOrder::RGB);
+        }
+    }
+
+    fn render_grid(&self, frame: &mut Frame, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let image = &self.frames.get()[self.ind].image;
+        let resized = image::imageops::resize(image, width, height, image::imageops::FilterType::Triangle);
+
+        for (i, led) in frame.iter_mut().enumerate() {
+            if i >= (width * height) as usize {
+                break;
+            }
+
+            let x = i as u32 % width;
+            let y = i as u32 / width;
+            let pixel = resized.get_pixel(x, y);
+
+            *led = RGB::from_tuple((pixel[0], pixel[1], pixel[2]), ranos_ds::rgb::RGBOrder::RGB);
+        }
+    }
+}
+
+impl Generator for ImageGenerator {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        self.advance(dt);
+        self.scroll_elapsed += dt;
+
+        match *self.layout.get() {
+            Layout::Strip {
+                line,
+                horizontal,
+                pixels_per_sec,
+            } => self.render_strip(frame, line, horizontal, pixels_per_sec),
+            Layout::Grid { width, height } => self.render_grid(frame, width, height),
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+        self.scroll_elapsed = Duration::new(0, 0);
+    }
+}
@@ -0,0 +1,321 @@
+//! # Keyframe
+//!
+//! A data-driven generator built from one or more prioritized keyframe
+//! layers, each addressing a contiguous LED range or named tag with a target
+//! color that's linearly interpolated towards over the keyframe's duration.
+//!
+//! Note: [`KeyframeGenerator`] layers its own keyframe data, independently
+//! of [`Display`](ranos_display::Display)'s compositing mode and of
+//! [`Compositor`](crate::Compositor), by holding multiple prioritized layers
+//! itself: each
+//! layer only overwrites the indices its current keyframe settings target,
+//! so a high-priority layer that only touches a few LEDs overlays whatever a
+//! lower-priority, full-frame layer underneath it already drew.
+
+use std::{collections::HashMap, ops::Range, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// The target addressed by a single [`KeyFrameSetting`]: either a contiguous
+/// range of LED indices, or a named tag resolved against the layer's tag table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Target {
+    /// A contiguous range of LED indices, `start..end`.
+    Range(Range<usize>),
+    /// A named group of LED indices, resolved via the layer's tag table.
+    Tag(String),
+}
+
+/// A single per-segment setting within a [`KeyFrame`]: the LEDs it addresses
+/// and the color they should reach by the keyframe's `duration`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFrameSetting {
+    /// The LEDs this setting applies to.
+    pub target: Target,
+    /// The color to interpolate towards over the keyframe's duration.
+    pub color: RGB,
+}
+
+/// A single keyframe: a duration and the per-segment settings to interpolate towards over it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFrame {
+    /// How long it takes to interpolate from the previous keyframe's colors to this one's.
+    pub duration: Duration,
+    /// The settings addressed by this keyframe. LEDs not addressed by any
+    /// setting here are left untouched.
+    pub settings: Vec<KeyFrameSetting>,
+}
+
+/// One prioritized keyframe timeline, built up on a [`KeyframeGeneratorBuilder`]
+/// via [`KeyframeGeneratorBuilder::layer`] and the calls that follow it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LayerBuilder {
+    priority: i32,
+    keyframes: Vec<KeyFrame>,
+    tags: HashMap<String, Range<usize>>,
+    looping: bool,
+}
+
+/// Builder for the [`KeyframeGenerator`] generator.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename = "Keyframe")]
+pub struct KeyframeGeneratorBuilder {
+    layers: Vec<LayerBuilder>,
+}
+
+impl KeyframeGeneratorBuilder {
+    /// Starts a new layer at the given priority; lower-priority layers are
+    /// composited first, so later (higher-priority) layers overlay them.
+    /// Subsequent calls to [`Self::keyframe`], [`Self::tag`], and
+    /// [`Self::looping`] apply to this layer, until the next [`Self::layer`] call.
+    pub fn layer(mut self: Box<Self>, priority: i32) -> Box<Self> {
+        self.layers.push(LayerBuilder {
+            priority,
+            ..Default::default()
+        });
+
+        self
+    }
+
+    /// Appends a keyframe to the end of the current layer's timeline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`Self::layer`].
+    pub fn keyframe(mut self: Box<Self>, keyframe: KeyFrame) -> Box<Self> {
+        self.current_layer().keyframes.push(keyframe);
+
+        self
+    }
+
+    /// Registers a named tag as an alias for a contiguous LED range on the
+    /// current layer, so its keyframes can address it via [`Target::Tag`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`Self::layer`].
+    pub fn tag(mut self: Box<Self>, name: impl Into<String>, range: Range<usize>) -> Box<Self> {
+        self.current_layer().tags.insert(name.into(), range);
+
+        self
+    }
+
+    /// Sets whether the current layer's keyframe list loops back to the start once it finishes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`Self::layer`].
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.current_layer().looping = looping;
+
+        self
+    }
+
+    fn current_layer(&mut self) -> &mut LayerBuilder {
+        self.layers
+            .last_mut()
+            .expect("call `.layer(priority)` before adding keyframes, tags, or looping")
+    }
+
+    /// Constructs a [`KeyframeGenerator`] object.
+    pub fn build(self: Box<Self>) -> KeyframeGenerator {
+        KeyframeGenerator::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for KeyframeGeneratorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{KeyFrame, KeyFrameSetting, KeyframeGenerator, Target};
+    use ranos_ds::rgb::{RGBOrder, RGB};
+    use std::time::Duration;
+
+    #[test]
+    fn test_serialize() {
+        let builder = KeyframeGenerator::builder()
+            .layer(0)
+            .keyframe(KeyFrame {
+                duration: Duration::from_secs(2),
+                settings: vec![KeyFrameSetting {
+                    target: Target::Range(0..8),
+                    color: RGB::from_code(0xFF0000, RGBOrder::RGB),
+                }],
+            });
+
+        let data = ron::ser::to_string(&builder).unwrap();
+        let expected = r#"(layers:[(priority:0,keyframes:[(duration:(secs:2,nanos:0),settings:[(target:Range((start:0,end:8)),color:(255,0,0))])],tags:{},looping:false)])"#;
+        assert_eq!(data, expected);
+    }
+}
+
+/// A single layer's runtime state: its immutable timeline plus where playback
+/// currently is within it.
+#[derive(Debug)]
+struct Layer {
+    priority: i32,
+    keyframes: ConstVal<Vec<KeyFrame>>,
+    tags: ConstVal<HashMap<String, Range<usize>>>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+    prev_colors: HashMap<usize, RGB>,
+}
+
+impl Layer {
+    fn from_builder(builder: LayerBuilder) -> Self {
+        Self {
+            priority: builder.priority,
+            keyframes: ConstVal::new(builder.keyframes),
+            tags: ConstVal::new(builder.tags),
+            looping: builder.looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+            prev_colors: HashMap::new(),
+        }
+    }
+
+    fn resolve<'a>(&'a self, target: &'a Target) -> Option<Range<usize>> {
+        match target {
+            Target::Range(r) => Some(r.clone()),
+            Target::Tag(name) => self.tags.get().get(name).cloned(),
+        }
+    }
+
+    /// Renders this layer's current keyframe into `frame`, advancing its
+    /// timeline by `dt`. LEDs not addressed by any of this layer's settings
+    /// are left as whatever a lower-priority layer already wrote.
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) {
+        if self.keyframes.get().is_empty() {
+            return;
+        }
+
+        self.elapsed += dt;
+
+        let current = &self.keyframes.get()[self.ind];
+        let t = (self.elapsed.as_secs_f32() / current.duration.as_secs_f32()).min(1.0);
+
+        for setting in &current.settings {
+            if let Some(range) = self.resolve(&setting.target) {
+                for i in range {
+                    if i >= frame.len() {
+                        continue;
+                    }
+
+                    let prev = *self.prev_colors.get(&i).unwrap_or(&RGB::new());
+                    frame.as_mut_slice()[i] = lerp_color(prev, setting.color, t);
+                }
+            }
+        }
+
+        if self.elapsed >= current.duration {
+            for setting in &current.settings {
+                if let Some(range) = self.resolve(&setting.target) {
+                    for i in range {
+                        self.prev_colors.insert(i, setting.color);
+                    }
+                }
+            }
+
+            self.elapsed = Duration::new(0, 0);
+            self.ind += 1;
+
+            if self.ind >= self.keyframes.get().len() {
+                self.ind = if self.looping { 0 } else { self.ind - 1 };
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+        self.prev_colors.clear();
+    }
+}
+
+/// Generator for a layered, tagged, keyframe-driven light show: one or more
+/// prioritized timelines of [`KeyFrame`]s, composited low-to-high priority
+/// into the same frame each tick.
+///
+/// Each layer addresses some subset of the LED strip, by contiguous range or
+/// by a named tag; LEDs a layer doesn't address are left showing whatever a
+/// lower-priority layer drew there, which is what lets a high-priority layer
+/// (e.g. an alert flashing a handful of tagged LEDs) sit on top of a
+/// lower-priority, full-frame ambient layer. A layer with no keyframes left
+/// and `looping: false` simply stops advancing and holds its last frame.
+///
+/// To create a [`KeyframeGenerator`], use the associated
+/// [builder](KeyframeGeneratorBuilder), accessed via [`KeyframeGenerator::builder()`].
+#[derive(Debug)]
+pub struct KeyframeGenerator {
+    id: usize,
+    layers: Vec<Layer>,
+}
+
+impl KeyframeGenerator {
+    /// Constructs a builder object with no layers by default.
+    pub fn builder() -> Box<KeyframeGeneratorBuilder> {
+        Box::new(KeyframeGeneratorBuilder::default())
+    }
+
+    fn from_builder(builder: Box<KeyframeGeneratorBuilder>) -> Self {
+        Self::new(builder.layers)
+    }
+
+    fn new(layer_builders: Vec<LayerBuilder>) -> Self {
+        let mut layers: Vec<Layer> = layer_builders.into_iter().map(Layer::from_builder).collect();
+        layers.sort_by_key(|l| l.priority);
+
+        Self {
+            id: ranos_core::id::generate(),
+            layers,
+        }
+    }
+}
+
+impl Generator for KeyframeGenerator {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        for layer in self.layers.iter_mut() {
+            layer.render_frame(frame, dt);
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.reset();
+        }
+    }
+}
+
+/// Linearly interpolates between two colors by `t`, clamped to `[0, 1]`.
+fn lerp_color(from: RGB, to: RGB, t: f32) -> RGB {
+    let t = t.min(1.0).max(0.0);
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+
+    RGB::from_tuple(
+        (
+            lerp(from.red(), to.red()),
+            lerp(from.green(), to.green()),
+            lerp(from.blue(), to.blue()),
+        ),
+        ranos_ds::rgb::RGBOrder::RGB,
+    )
+}
@@ -4,6 +4,7 @@
 #![deny(broken_intra_doc_links)]
 #![warn(clippy::all)]
 
+extern crate ranos_audio;
 extern crate ranos_core;
 extern crate ranos_ds;
 
@@ -11,17 +12,31 @@ use std::time::Duration;
 
 use ranos_ds::collections::frame::Frame;
 
+pub use audio_envelope::AudioEnvelope;
+pub use audio_spectrum::AudioSpectrum;
 pub use breath::Breath;
 pub use color_order::ColorOrder;
+pub use compositor::Compositor;
 pub use cycle::Cycle;
+pub use frame_sequence::FrameSequenceGenerator;
+pub use image::ImageGenerator;
+pub use keyframe::KeyframeGenerator;
 pub use rainbow::Rainbow;
+pub use recording::RecordingGenerator;
 pub use solid::Solid;
 pub use strobe::Strobe;
 
+pub mod audio_envelope;
+pub mod audio_spectrum;
 pub mod breath;
 pub mod color_order;
+pub mod compositor;
 pub mod cycle;
+pub mod frame_sequence;
+pub mod image;
+pub mod keyframe;
 pub mod rainbow;
+pub mod recording;
 pub mod solid;
 pub mod strobe;
 
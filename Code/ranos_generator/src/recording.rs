@@ -0,0 +1,329 @@
+//! Generator that replays a recording written by
+//! `ranos_draw`'s `FileDraw` -- the reader half of that drawer's compact
+//! binary format, letting a session captured once (e.g. on a Pi) be replayed
+//! deterministically into any other [`Draw`](ranos_draw)-implementing target.
+//!
+//! The on-disk format is documented in full on `ranos_draw::file_draw`; in
+//! short, an 8-byte magic, a one-byte codec tag, a header of `{ width: u32,
+//! height: u32, fps: f32, frame_count: u32 }`, then `frame_count`
+//! length-prefixed records each holding one frame's `width * height` RGB
+//! triples -- individually deflate-compressed first if the codec tag calls
+//! for it -- all big-endian. Every size implied by the header is bounded
+//! before any frame buffer is allocated -- `width * height` by
+//! [`MAX_RECORDING_PIXELS`], `frame_count` and each record's length by the
+//! file's actual length -- so a corrupt or truncated recording can't be used to trigger an
+//! out-of-memory allocation; a compressed record's decompressed size is
+//! capped the same way, so a maliciously inflated block can't either.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    time::Duration,
+};
+
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+
+use ranos_ds::{const_val::ConstVal, rgb::RGB};
+
+use super::*;
+
+/// 8-byte magic identifying a recording produced by `ranos_draw::FileDraw`.
+const MAGIC: &[u8; 8] = b"RANOSREC";
+
+/// The one-byte codec tags `ranos_draw::file_draw::Compression` is written as.
+const CODEC_NONE: u8 = 0;
+const CODEC_DEFLATE: u8 = 1;
+
+/// Refuses to replay a recording whose `width * height` exceeds this many
+/// pixels, so a malformed or absurdly large header can't be used to trigger
+/// an out-of-memory allocation before the frame data has even been read.
+pub const MAX_RECORDING_PIXELS: u64 = 16 * 1024 * 1024;
+
+/// Builder for the [`RecordingGenerator`] generator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "RecordingGenerator")]
+pub struct RecordingGeneratorBuilder {
+    path: PathBuf,
+    looping: bool,
+}
+
+impl RecordingGeneratorBuilder {
+    /// Sets the path of the recording to load.
+    pub fn path(mut self: Box<Self>, path: PathBuf) -> Box<Self> {
+        self.path = path;
+
+        self
+    }
+
+    /// Sets whether the recording loops once its last frame is reached.
+    ///
+    /// If `false`, the generator simply holds the last frame once it's been reached.
+    pub fn looping(mut self: Box<Self>, looping: bool) -> Box<Self> {
+        self.looping = looping;
+
+        self
+    }
+
+    /// Constructs a [`RecordingGenerator`] object, decoding the configured recording from disk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file can't be read, doesn't start with the expected
+    /// magic, or its header's sizes don't agree with the file's actual
+    /// length.
+    pub fn build(self: Box<Self>) -> RecordingGenerator {
+        RecordingGenerator::from_builder(self)
+    }
+}
+
+#[typetag::serde]
+impl GeneratorBuilder for RecordingGeneratorBuilder {
+    fn build(self: Box<Self>) -> Box<dyn Generator> {
+        Box::new(self.build())
+    }
+}
+
+#[cfg(test)]
+mod builder_test {
+    use super::{RecordingGenerator, RecordingGeneratorBuilder};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let builder = RecordingGenerator::builder();
+        let data = ron::ser::to_string(&builder).unwrap();
+
+        let expected = r#"(path:"",looping:true)"#;
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let input = r#"(path:"capture.ranrec",looping:false)"#;
+        let data: RecordingGeneratorBuilder = ron::de::from_str(input).unwrap();
+
+        assert_eq!(data.path, PathBuf::from("capture.ranrec"));
+        assert_eq!(data.looping, false);
+    }
+}
+
+/// Generator that replays a recording from disk instead of a procedural
+/// effect: every decoded frame is shown for `1 / fps` seconds, where `fps`
+/// is the nominal rate stored in the recording's header, with `dt`
+/// accumulated between [`Generator::render_frame`] calls the same way
+/// [`ImageGenerator`](crate::ImageGenerator) advances between GIF frames.
+///
+/// To create a [`RecordingGenerator`], use the associated
+/// [builder](RecordingGeneratorBuilder), accessed via
+/// [`RecordingGenerator::builder()`].
+#[derive(Debug)]
+pub struct RecordingGenerator {
+    id: usize,
+
+    frames: ConstVal<Vec<Vec<RGB>>>,
+    frame_delay: ConstVal<Duration>,
+    looping: bool,
+
+    ind: usize,
+    elapsed: Duration,
+}
+
+impl RecordingGenerator {
+    /// Constructs a builder object with safe default values: an empty path
+    /// (must be set before building), looping.
+    pub fn builder() -> Box<RecordingGeneratorBuilder> {
+        Box::new(RecordingGeneratorBuilder {
+            path: PathBuf::new(),
+            looping: true,
+        })
+    }
+
+    fn from_builder(builder: Box<RecordingGeneratorBuilder>) -> Self {
+        Self::new(builder.path, builder.looping)
+    }
+
+    fn new(path: PathBuf, looping: bool) -> Self {
+        let (frames, fps) = Self::load(&path)
+            .unwrap_or_else(|e| panic!("failed to load recording {:?}: {}", path, e));
+
+        let frame_delay = Duration::from_secs_f32(1.0 / fps.max(f32::EPSILON));
+
+        Self {
+            id: ranos_core::id::generate(),
+
+            frames: ConstVal::new(frames),
+            frame_delay: ConstVal::new(frame_delay),
+            looping,
+
+            ind: 0,
+            elapsed: Duration::new(0, 0),
+        }
+    }
+
+    /// Loads and validates a recording written by `ranos_draw::FileDraw`,
+    /// returning its decoded frames and the nominal fps from its header.
+    ///
+    /// Unlike the uncompressed format, a compressed record's length on disk
+    /// doesn't predict the file's total size up front, so records are walked
+    /// one at a time -- each one's length (and, for a compressed record, its
+    /// decompressed size) is checked against the data actually available
+    /// before it's read, rather than validating one overall expected length
+    /// first.
+    fn load(path: &PathBuf) -> io::Result<(Vec<Vec<RGB>>, f32)> {
+        let data = fs::read(path)?;
+
+        let header = data.get(..25).ok_or_else(|| truncated("header"))?;
+
+        if &header[0..8] != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "recording is missing the expected magic bytes",
+            ));
+        }
+
+        let codec = header[8];
+        if codec != CODEC_NONE && codec != CODEC_DEFLATE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("recording has an unrecognized codec tag {}", codec),
+            ));
+        }
+
+        let width = u32::from_be_bytes(header[9..13].try_into().unwrap());
+        let height = u32::from_be_bytes(header[13..17].try_into().unwrap());
+        let fps = f32::from_be_bytes(header[17..21].try_into().unwrap());
+        let frame_count = u32::from_be_bytes(header[21..25].try_into().unwrap());
+
+        let pixel_count = width as u64 * height as u64;
+        if pixel_count > MAX_RECORDING_PIXELS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "recording is {}x{} ({} pixels), which exceeds the maximum of {} pixels",
+                    width, height, pixel_count, MAX_RECORDING_PIXELS
+                ),
+            ));
+        }
+
+        let pixel_bytes = pixel_count as usize * 3;
+
+        // Every record carries at least its 4-byte length prefix, so the
+        // file's own size bounds how many records it can really hold.
+        let mut frames = Vec::with_capacity((frame_count as usize).min(data.len() / 4));
+        let mut pos = 25_usize;
+
+        for _ in 0..frame_count {
+            let len_bytes = data
+                .get(pos..pos + 4)
+                .ok_or_else(|| truncated("record length"))?;
+            let record_len = u32::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+            pos += 4;
+
+            let end = pos
+                .checked_add(record_len)
+                .ok_or_else(|| truncated("record body"))?;
+            let block = data.get(pos..end).ok_or_else(|| truncated("record body"))?;
+            pos = end;
+
+            // Capped at `pixel_bytes` so a maliciously (or corruptly)
+            // inflated compressed block can't be used to balloon memory use
+            // past what the header already promised.
+            let raw = if codec == CODEC_DEFLATE {
+                let mut buf = Vec::with_capacity(pixel_bytes);
+                ZlibDecoder::new(block)
+                    .take(pixel_bytes as u64)
+                    .read_to_end(&mut buf)?;
+                buf
+            } else {
+                block.to_vec()
+            };
+
+            if raw.len() != pixel_bytes {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "recording has a record that doesn't decode to its header dimensions",
+                ));
+            }
+
+            let mut frame = Vec::with_capacity(pixel_count as usize);
+            for chunk in raw.chunks_exact(3) {
+                frame.push(RGB::from_tuple(
+                    (chunk[0], chunk[1], chunk[2]),
+                    ranos_ds::rgb::RGBOrder::RGB,
+                ));
+            }
+
+            frames.push(frame);
+        }
+
+        if pos != data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "recording has trailing data after its last record",
+            ));
+        }
+
+        Ok((frames, fps))
+    }
+
+    /// Advances which decoded frame is current by accumulating `dt` against
+    /// the recording's nominal per-frame delay. A no-op if there's only one
+    /// frame (or none).
+    fn advance(&mut self, dt: Duration) {
+        if self.frames.get().len() <= 1 {
+            return;
+        }
+
+        self.elapsed += dt;
+
+        while self.elapsed >= *self.frame_delay.get() {
+            self.elapsed -= *self.frame_delay.get();
+            self.ind += 1;
+
+            if self.ind >= self.frames.get().len() {
+                if self.looping {
+                    self.ind = 0;
+                } else {
+                    self.ind = self.frames.get().len() - 1;
+                    self.elapsed = Duration::new(0, 0);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn truncated(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("recording ended before its {} could be read", what),
+    )
+}
+
+impl Generator for RecordingGenerator {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn render_frame(&mut self, frame: &mut Frame, dt: Duration) -> GeneratorState {
+        self.advance(dt);
+
+        if self.frames.get().is_empty() {
+            return GeneratorState::Ok;
+        }
+
+        let current = &self.frames.get()[self.ind];
+        for (led, color) in frame.iter_mut().zip(current.iter()) {
+            *led = *color;
+        }
+
+        GeneratorState::Ok
+    }
+
+    fn reset(&mut self) {
+        self.ind = 0;
+        self.elapsed = Duration::new(0, 0);
+    }
+}